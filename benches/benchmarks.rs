@@ -1,42 +1,176 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+//! Stepping networks of increasing size, and of each combination of neuron model and synapse.
+//!
+//! With the `parallel` feature, each size is stepped both serially and in parallel, so that
+//! the two paths are compared in the same group:
+//!
+//!     cargo bench --bench benchmarks --features parallel
+//!
+//! The networks are created from a dense weight matrix, which takes 1.6 GB at the largest size.
 
-use srsnn::pools::Pool;
-use model::network::Network;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+
+use model::DefaultNetwork;
+use model::network::{Network, SpikingNetwork};
+use model::network::builder::NetworkBuilder;
+use model::network::representation::{NetworkRepresentation, NeuronDescription};
+use model::neuron::NeuronModel;
+use model::neuron::izhikevich::Izhikevich;
+use model::neuron::lif::Lif;
+use model::neuron::adex::AdEx;
+use model::neuron::lapicque::Lapicque;
+use model::neuron::mixed::{Mixed, MixedParameters};
+use model::neuron::lif::LifParameters;
+use model::synapse::Synapse;
+use model::synapse::basic::BasicSynapse;
+use model::synapse::exponential::ExponentialSynapse;
+use model::synapse::bi_exponential::BiExponentialSynapse;
+use model::synapse::conductance::ConductanceSynapse;
+use model::synapse::tsodyks_markram::TsodyksMarkramSynapse;
+use model::spikes::Spikes;
+
+use utils::environment::Environment;
 
 use ndarray::{Array, Array2};
 
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+
+const SIZES: [usize; 4] = [2500, 5000, 10000, 20000];
+
+/// Size of the networks compared across neuron models and synapses
+const MODEL_SIZE: usize = 1000;
+
+/// Number of steps per iteration
+const T: usize = 100;
+
+/// Number of connections of each neuron
+const FAN_IN: usize = 100;
 
-const T: usize = 1000;
-const P: f32 = 0.1;
 const INHIBITORY_FRACTION: f32 = 0.2;
 
-const LARGE_POOL_SIZE: usize = 100;
-const SMALL_POOL_SIZE: usize = 20;
+const ENV: Environment = Environment { inputs: 10, outputs: 10 };
+
+/// Paths that the size benchmarks compare, by whether model::parallel is enabled
+const PATHS: &[(&str, bool)] = if cfg!(feature = "parallel") {
+    &[("serial", false), ("parallel", true)]
+} else {
+    &[("serial", false)]
+};
+
+/// A random network of n neurons, where f gives the parameters of a neuron from whether it is inhibitory
+fn representation<N, F>(n: usize, rng: &mut StdRng, f: F) -> NetworkRepresentation<NeuronDescription<N>>
+    where N: NeuronModel, F: Fn(bool) -> N::Parameters
+{
+    let neurons = Array::from_shape_fn(n, |i| {
+        let inhibitory = i < n - ENV.outputs && rng.gen::<f32>() < INHIBITORY_FRACTION;
+
+        NeuronDescription::new(i as u32, f(inhibitory), inhibitory)
+    });
+
+    let p = FAN_IN as f32 / n as f32;
+
+    let cm: Array2<u32> = Array::from_shape_fn((n, n), |_| (rng.gen::<f32>() < p) as u32);
+    let w: Array2<f32> = cm.mapv(|c| c as f32 * rng.gen_range(0.0..2.0));
+
+    let input_cm: Array2<u32> = Array::ones((n - ENV.outputs, ENV.inputs));
+    let input_w: Array2<f32> = Array::from_elem((n - ENV.outputs, ENV.inputs), 5.0);
+
+    NetworkRepresentation::new(neurons, cm, w, input_cm, input_w, ENV)
+}
+
+/// A random network of n neurons of the default model, created from its weights rather than from
+/// a representation, which would hold two more n x n matrices
+fn network(n: usize, rng: &mut StdRng) -> DefaultNetwork {
+    let p = FAN_IN as f32 / n as f32;
+
+    let w: Array2<f32> = Array::from_shape_fn((n, n), |_| if rng.gen::<f32>() < p { rng.gen_range(0.0..2.0) } else { 0.0 });
+    let neuron_type = Array::from_shape_fn(n, |i|
+        if i < n - ENV.outputs && rng.gen::<f32>() < INHIBITORY_FRACTION { -1.0 } else { 1.0 });
+
+    let input_w: Array2<f32> = Array::from_elem((n - ENV.outputs, ENV.inputs), 5.0);
+
+    SpikingNetwork::new(
+        Izhikevich::new(n, vec![Default::default(); n]),
+        ExponentialSynapse::new(w, neuron_type),
+        ExponentialSynapse::new(input_w, Array::ones(ENV.inputs)),
+        ENV)
+}
 
-/// Benchmark a pool of size N
-fn run_pool(n: usize) {
-    let input: Array2<f32> = Array::ones((T, n)) * 17.3;
+fn input(rng: &mut StdRng) -> Vec<Spikes> {
+    (0..T).map(|_| Spikes { data: Array::from_shape_fn(ENV.inputs, |_| rng.gen::<f32>() < 0.5) }).collect()
+}
+
+fn bench_step<N, S>(group: &mut BenchmarkGroup<WallTime>, id: BenchmarkId, network: &mut SpikingNetwork<N, S>, input: &[Spikes])
+    where N: NeuronModel, S: Synapse
+{
+    group.bench_with_input(id, input, |b, input| b.iter(|| {
+        network.reset_state();
+
+        for s in input {
+            black_box(network.step(s.clone()));
+        }
+    }));
+}
+
+fn size_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("network step");
+    group.sample_size(20);
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut network = network(n, &mut rng);
+        let input = input(&mut rng);
+
+        for (path, parallel) in PATHS {
+            model::parallel::set_enabled(*parallel);
+
+            bench_step(&mut group, BenchmarkId::new(*path, n), &mut network, &input);
+        }
+    }
+
+    model::parallel::set_enabled(true);
+    group.finish();
+}
+
+/// Steps a network of MODEL_SIZE neurons of model N connected by synapse S
+fn bench_model<N: NeuronModel, S: Synapse>(group: &mut BenchmarkGroup<WallTime>, name: &str, f: fn(bool) -> N::Parameters) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let mut network: SpikingNetwork<N, S> = NetworkBuilder::build(&representation(MODEL_SIZE, &mut rng, f));
+
+    bench_step(group, BenchmarkId::new(name, MODEL_SIZE), &mut network, &input(&mut rng));
+}
+
+fn bench_synapses<N: NeuronModel>(group: &mut BenchmarkGroup<WallTime>, neuron: &str, f: fn(bool) -> N::Parameters) {
+    bench_model::<N, ExponentialSynapse>(group, &format!("{neuron}/exponential"), f);
+    bench_model::<N, BiExponentialSynapse>(group, &format!("{neuron}/bi_exponential"), f);
+    bench_model::<N, BasicSynapse>(group, &format!("{neuron}/basic"), f);
+    bench_model::<N, ConductanceSynapse>(group, &format!("{neuron}/conductance"), f);
+    bench_model::<N, TsodyksMarkramSynapse>(group, &format!("{neuron}/tsodyks_markram"), f);
+}
+
+fn lif(inhibitory: bool) -> LifParameters {
+    if inhibitory { LifParameters::INHIBITORY } else { LifParameters::EXCITATORY }
+}
 
-    let mut network = Pool::new(n, P, INHIBITORY_FRACTION);
-    let _ = network.run(T, &input);
+fn mixed(inhibitory: bool) -> MixedParameters {
+    if inhibitory { MixedParameters::Lif(LifParameters::INHIBITORY) } else { MixedParameters::default() }
 }
 
-//fn fibonacci(n: u64) -> u64 {
-//    match n {
-//        0 => 1,
-//        1 => 1,
-//        n => fibonacci(n-1) + fibonacci(n-2),
-//    }
-//}
+fn model_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neuron and synapse");
 
-//fn criterion_benchmark(c: &mut Criterion) {
-//    c.bench_function("fib 20", |b| b.iter(|| fibonacci(black_box(20))));
-//}
+    bench_synapses::<Izhikevich>(&mut group, "izhikevich", |_| Default::default());
+    bench_synapses::<Lif>(&mut group, "lif", lif);
+    bench_synapses::<AdEx>(&mut group, "adex", |_| Default::default());
+    bench_synapses::<Lapicque>(&mut group, "lapicque", |_| Default::default());
+    bench_synapses::<Mixed>(&mut group, "mixed", mixed);
 
-fn pool_benchmark(c: &mut Criterion) {
-    c.bench_function("large pool", |b| b.iter(|| run_pool(black_box(LARGE_POOL_SIZE))));
-    c.bench_function("small pool", |b| b.iter(|| run_pool(black_box(SMALL_POOL_SIZE))));
+    group.finish();
 }
 
-criterion_group!(benches, pool_benchmark);
+criterion_group!(benches, size_benchmark, model_benchmark);
 criterion_main!(benches);
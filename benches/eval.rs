@@ -0,0 +1,86 @@
+//! Throughput of MultiEvaluator::eval, in evaluated models per second, on a single thread
+//! and on all available threads.
+//!
+//! The models are developed by the base model generator configured by config/default.toml.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use srsnn::config::{base_config, get_config};
+use srsnn::eval::MultiEvaluator;
+use srsnn::eval::config::EvalConfig;
+use srsnn::models::generator_model::GeneratorModel;
+use srsnn::models::generator::base::BaseModel;
+
+use model::Model;
+use model::clock::Clock;
+
+use tasks::{Task, TaskEval};
+use tasks::xor_task::XORTask;
+
+use evolution::Evaluate;
+
+use utils::environment::Environment;
+
+use ndarray::Array;
+use ndarray_rand::RandomExt;
+use ndarray_rand::rand_distr::Normal;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+
+/// Number of models evaluated per iteration
+const MODELS: usize = 16;
+
+type M = GeneratorModel<BaseModel>;
+
+fn eval_benchmark(c: &mut Criterion) {
+    base_config(None);
+
+    let e = XORTask::environment();
+    let env = Environment { inputs: e.agent_inputs, outputs: e.agent_outputs };
+
+    let conf = get_config::<M>();
+
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let params = <M as Model>::params(&conf, &env);
+    let models: Vec<M> = (0..MODELS)
+        .map(|_| params.assign(&Array::random_using(params.size(), Normal::new(0.0, 1.0).unwrap(), &mut rng)))
+        .map(|p| <M as Model>::new(&conf, &p, &env))
+        .collect();
+
+    let models: Vec<(u32, &M)> = models.iter().enumerate().map(|(i, m)| (i as u32, m)).collect();
+
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    let mut group = c.benchmark_group("eval");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(MODELS as u64));
+
+    let mut thread_counts = vec![1];
+    if threads > 1 {
+        thread_counts.push(threads);
+    }
+
+    for max_threads in thread_counts {
+        let config = EvalConfig {
+            max_threads,
+            trials: 1,
+            clock: Clock::default(),
+            validation_clock: None,
+            readout: None,
+        };
+
+        let mut evaluator = MultiEvaluator::<XORTask>::new(config, None, XORTask::eval_setups());
+
+        group.bench_with_input(BenchmarkId::new("xor", max_threads), &models, |b, models| b.iter(||
+            Evaluate::<M, _>::eval(&mut evaluator, models)
+        ));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, eval_benchmark);
+criterion_main!(benches);
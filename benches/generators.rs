@@ -0,0 +1,78 @@
+//! Developing networks from each generator, for increasing network sizes.
+//!
+//! The generators are configured by config/default.toml, with random parameters.
+
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+
+use srsnn::config::{base_config, get_config};
+use srsnn::models::generator::Generator;
+use srsnn::models::generator_model::GeneratorModel;
+use srsnn::models::generator::uniform::UniformModel;
+use srsnn::models::generator::typed_uniform::TypedUniformModel;
+use srsnn::models::generator::base::BaseModel;
+use srsnn::models::generator::ed::EvolvedDynamicsModel;
+use srsnn::models::generator::er::ER0Model;
+use srsnn::models::generator::udd_base::UDDBaseModel;
+use srsnn::models::generator::utd_base::UTDBaseModel;
+use srsnn::models::generator::edd_base::EDDModel;
+use srsnn::models::generator::ex1_ablation::{GeometricModel, TypedModel, GeometricTypedModel};
+
+use model::{DefaultNeuron, Model};
+
+use utils::environment::Environment;
+
+use ndarray::Array;
+use ndarray_rand::RandomExt;
+use ndarray_rand::rand_distr::Normal;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+
+const SIZES: [usize; 3] = [128, 256, 512];
+
+const ENV: Environment = Environment { inputs: 10, outputs: 10 };
+
+fn bench_develop<G: Generator>(group: &mut BenchmarkGroup<WallTime>, name: &str) {
+    let mut conf = get_config::<GeneratorModel<G>>();
+
+    for n in SIZES {
+        conf.n = n;
+
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let params = <GeneratorModel<G> as Model>::params(&conf, &ENV);
+        let params = params.assign(&Array::random_using(params.size(), Normal::new(0.0, 1.0).unwrap(), &mut rng));
+
+        let model = <GeneratorModel<G> as Model>::new(&conf, &params, &ENV);
+
+        group.bench_with_input(BenchmarkId::new(name, n), &model, |b, model| b.iter(||
+            Model::<DefaultNeuron>::develop(model)
+        ));
+    }
+}
+
+fn develop_benchmark(c: &mut Criterion) {
+    base_config(None);
+
+    let mut group = c.benchmark_group("develop");
+    group.sample_size(20);
+
+    bench_develop::<BaseModel>(&mut group, "base_model");
+    bench_develop::<EvolvedDynamicsModel>(&mut group, "ed_model");
+    bench_develop::<UniformModel>(&mut group, "uniform_model");
+    bench_develop::<ER0Model>(&mut group, "er0_model");
+    bench_develop::<TypedUniformModel>(&mut group, "typed_uniform_model");
+    bench_develop::<UDDBaseModel>(&mut group, "udd_base_model");
+    bench_develop::<UTDBaseModel>(&mut group, "utd_base_model");
+    bench_develop::<EDDModel>(&mut group, "edd_base_model");
+    bench_develop::<GeometricModel>(&mut group, "geometric_uniform");
+    bench_develop::<TypedModel>(&mut group, "typed_model");
+    bench_develop::<GeometricTypedModel>(&mut group, "geometric_typed_model");
+
+    group.finish();
+}
+
+criterion_group!(benches, develop_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! Evaluating connection set masks into matrices, for increasing network sizes.
+//!
+//! Each mask is evaluated both by enumerating its rows and, labelled dense, by evaluating
+//! it for every entry.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
+use criterion::measurement::WallTime;
+
+use csa::ValueSet;
+use csa::mask::{self, Mask};
+use csa::op;
+use csa::op::geometric;
+
+use ndarray::Array;
+
+
+const SIZES: [usize; 4] = [256, 512, 1024, 2048];
+
+/// Number of types of the stochastic block model
+const K: usize = 8;
+
+fn bench_mask<F>(group: &mut BenchmarkGroup<WallTime>, name: &str, mask: F)
+    where F: Fn(usize) -> Mask
+{
+    for n in SIZES {
+        let m = mask(n);
+        let dense = Mask::new(m.f.clone());
+
+        group.bench_with_input(BenchmarkId::new(name, n), &n, |b, n| b.iter(||
+            black_box(m.matrix(*n))
+        ));
+
+        group.bench_with_input(BenchmarkId::new(format!("{name}/dense"), n), &n, |b, n| b.iter(||
+            black_box(dense.matrix(*n))
+        ));
+    }
+}
+
+/// Stochastic block model over K types of equal size
+fn sbm(n: usize) -> Mask {
+    let labels = op::label(vec![n / K; K], K);
+    let cpm = Array::from_shape_fn((K, K), |(i, j)| if i == j { 0.5 } else { 0.1 });
+
+    op::sbm(labels, ValueSet::from_value(cpm))
+}
+
+fn disc(n: usize) -> Mask {
+    let coords = geometric::random_coordinates(0.0, 10.0, n);
+
+    geometric::disc(3.0, geometric::distance_metric(coords.clone(), coords))
+}
+
+fn coordinate_disc(n: usize) -> Mask {
+    let coords = geometric::random_coordinates(0.0, 10.0, n);
+
+    geometric::coordinate_disc(3.0, coords.clone(), coords)
+}
+
+fn mask_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mask matrix");
+    group.sample_size(20);
+
+    bench_mask(&mut group, "one_to_one", |_| mask::one_to_one());
+    bench_mask(&mut group, "random", |_| mask::random(0.1));
+    bench_mask(&mut group, "sbm", sbm);
+    bench_mask(&mut group, "disc", disc);
+    bench_mask(&mut group, "coordinate_disc", coordinate_disc);
+
+    // As used by the base model generator, without self connections
+    bench_mask(&mut group, "sbm_disc", |n| (sbm(n) & disc(n)) - mask::one_to_one());
+
+    group.finish();
+}
+
+criterion_group!(benches, mask_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+//! Dense vs. sparse (CSR) synaptic propagation for increasing network sizes, and the cost
+//! of the synapse models on top of it.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use model::spikes::Spikes;
+use model::synapse::Synapse;
+use model::synapse::exponential::ExponentialSynapse;
+use model::synapse::conductance::ConductanceSynapse;
+use model::synapse::representation::{SynapseRepresentation, MatrixRepresentation, CsrRepresentation};
+
+use ndarray::{Array, Array1, Array2};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+
+const SIZES: [usize; 6] = [128, 256, 512, 1024, 2048, 4096];
+
+/// Fraction of possible connections that are present
+const DENSITY: f32 = 0.05;
+
+/// Fraction of neurons firing in a single step
+const FIRING_RATE: f32 = 0.02;
+
+/// Number of steps per iteration, each with a different spike pattern
+const STEPS: usize = 10;
+
+fn weights(n: usize, rng: &mut StdRng) -> Array2<f32> {
+    Array::from_shape_fn((n, n), |_| if rng.gen::<f32>() < DENSITY { rng.gen() } else { 0.0 })
+}
+
+fn neuron_type(n: usize, rng: &mut StdRng) -> Array1<f32> {
+    Array::from_shape_fn(n, |_| if rng.gen::<f32>() < 0.2 { -1.0 } else { 1.0 })
+}
+
+fn spikes(n: usize, rng: &mut StdRng) -> Vec<Spikes> {
+    (0..STEPS).map(|_| Spikes { data: Array::from_shape_fn(n, |_| rng.gen::<f32>() < FIRING_RATE) }).collect()
+}
+
+fn representation_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("synapse representation");
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let w = weights(n, &mut rng);
+        let t = neuron_type(n, &mut rng);
+        let input = spikes(n, &mut rng);
+
+        let mut dense = MatrixRepresentation::new(w.clone(), t.clone());
+        let mut csr = CsrRepresentation::new(w, t);
+
+        group.bench_with_input(BenchmarkId::new("dense", n), &input, |b, input| b.iter(||
+            for s in input { black_box(dense.step(s)); }
+        ));
+
+        group.bench_with_input(BenchmarkId::new("csr", n), &input, |b, input| b.iter(||
+            for s in input { black_box(csr.step(s)); }
+        ));
+    }
+
+    group.finish();
+}
+
+fn exponential_synapse_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("exponential synapse");
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut synapse = ExponentialSynapse::new(weights(n, &mut rng), neuron_type(n, &mut rng));
+        let input = spikes(n, &mut rng);
+        let v = Array::from_elem(n, -65.0);
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &input, |b, input| b.iter(||
+            for s in input { black_box(synapse.step(s, &v)); }
+        ));
+    }
+
+    group.finish();
+}
+
+fn conductance_synapse_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conductance synapse");
+
+    for n in SIZES {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut synapse = ConductanceSynapse::new(weights(n, &mut rng), neuron_type(n, &mut rng));
+        let input = spikes(n, &mut rng);
+        let v = Array::from_elem(n, -65.0);
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &input, |b, input| b.iter(||
+            for s in input { black_box(synapse.step(s, &v)); }
+        ));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, representation_benchmark, exponential_synapse_benchmark, conductance_synapse_benchmark);
+criterion_main!(benches);
@@ -3,15 +3,47 @@ pub mod op;
 
 use ndarray::{s, array, Array, Array1, Array2};
 
+use std::ops::{Add, Mul};
 use std::sync::Arc;
 
 use mask::Mask;
 
 
+/// The first value set of v is the weights. Delays and short-term plasticity are optional,
+/// and named, such that further value sets can be added to v without being mistaken for them.
 pub struct NetworkSet {
     pub m: Mask,
     pub v: Vec<ValueSet>,
-    pub d: Vec<NeuronSet>
+    pub d: Vec<NeuronSet>,
+
+    /// Transmission delays (ms)
+    pub delays: Option<ValueSet>,
+
+    /// Parameters of short-term plasticity
+    pub stp: Option<StpSet>,
+}
+
+impl NetworkSet {
+    pub fn new(m: Mask, v: Vec<ValueSet>, d: Vec<NeuronSet>) -> NetworkSet {
+        NetworkSet { m, v, d, delays: None, stp: None }
+    }
+
+    pub fn with_delays(mut self, delays: ValueSet) -> NetworkSet {
+        self.delays = Some(delays);
+        self
+    }
+
+    pub fn with_short_term_plasticity(mut self, stp: StpSet) -> NetworkSet {
+        self.stp = Some(stp);
+        self
+    }
+}
+
+/// Value sets of the U, tau_rec and tau_facil of Tsodyks-Markram short-term plasticity
+pub struct StpSet {
+    pub u: ValueSet,
+    pub tau_rec: ValueSet,
+    pub tau_facil: ValueSet,
 }
 
 pub struct ConnectionSet {
@@ -21,6 +53,13 @@ pub struct ConnectionSet {
 
 pub type ValueFn = Arc<dyn Fn(u32, u32) -> f32>;
 
+/// A value set is a function (i,j) -> R
+///
+/// Value sets can be combined by arithmetic, where ValueSet + ValueSet and ValueSet * ValueSet
+/// are taken element-wise, and a value set can be scaled or shifted by a scalar. Together with
+/// masked and the distance-dependent value sets of op::geometric, weights and delays can be
+/// written as expressions, such as (gaussian(2.0, d) * w_max).masked(m).
+#[derive(Clone)]
 pub struct ValueSet {
     pub f: ValueFn
 }
@@ -32,6 +71,13 @@ impl ValueSet {
         }
     }
 
+    /// The same value for every (i,j)
+    pub fn constant(v: f32) -> Self {
+        ValueSet {
+            f: Arc::new(move |_i, _j| v)
+        }
+    }
+
     pub fn matrix(&self, n: usize) -> Array2<f32> {
         let mut m = Array::zeros((n,n));
 
@@ -58,17 +104,67 @@ impl ValueSet {
         mx
     }
 
-    ///// Merge a value set of values R^a with a value set of values R^b into a value set
-    ///// of values R^(a+b), where the first values
-    ///// TODO: This can instead be accomplished by implementing Add for ValueSet
-    //pub fn merge(v1: ValueSet, v2: ValueSet) -> ValueSet {
-    //    let f1 = v1.f.clone();
-    //    let f2 = v2.f.clone();
+    /// The value set restricted to a mask, with the values where the mask is set and zero
+    /// elsewhere. A stochastic mask is drawn each time a value is evaluated.
+    pub fn masked(self, m: Mask) -> ValueSet {
+        let (f, mf) = (self.f, m.f);
+
+        ValueSet {
+            f: Arc::new(move |i,j| if mf(i,j) { f(i,j) } else { 0.0 })
+        }
+    }
+
+    /// Matrix of the given shape with the values of the given connections, and zero elsewhere,
+    /// such that only the values of the connections are evaluated
+    pub fn sparse_matrix(&self, connections: &[(u32, u32)], shape: (usize, usize)) -> Array2<f32> {
+        let mut m = Array::zeros(shape);
+
+        for (i, j) in connections {
+            m[[*i as usize, *j as usize]] = (self.f)(*i, *j);
+        }
 
-    //    ValueSet {
-    //        f: Arc::new(move |i,j| f1(i,j)  f2(i,j))
-    //    }
-    //}
+        m
+    }
+}
+
+impl Add for ValueSet {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (f1, f2) = (self.f, other.f);
+
+        ValueSet { f: Arc::new(move |i,j| f1(i,j) + f2(i,j)) }
+    }
+}
+
+impl Mul for ValueSet {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let (f1, f2) = (self.f, other.f);
+
+        ValueSet { f: Arc::new(move |i,j| f1(i,j) * f2(i,j)) }
+    }
+}
+
+impl Add<f32> for ValueSet {
+    type Output = Self;
+
+    fn add(self, x: f32) -> Self {
+        let f = self.f;
+
+        ValueSet { f: Arc::new(move |i,j| f(i,j) + x) }
+    }
+}
+
+impl Mul<f32> for ValueSet {
+    type Output = Self;
+
+    fn mul(self, x: f32) -> Self {
+        let f = self.f;
+
+        ValueSet { f: Arc::new(move |i,j| f(i,j) * x) }
+    }
 }
 
 pub type NeuronFn = Arc<dyn Fn(u32) -> Array1<f32>>;
@@ -104,6 +200,15 @@ pub struct NeuronMask {
     pub f: NeuronMaskFn
 }
 
+impl NeuronMask {
+    /// The neurons start..end
+    pub fn interval(start: u32, end: u32) -> Self {
+        NeuronMask {
+            f: Arc::new(move |i| start <= i && i < end)
+        }
+    }
+}
+
 impl Into<NeuronSet> for NeuronMask {
     fn into(self) -> NeuronSet {
         let f = self.f;
@@ -112,3 +217,25 @@ impl Into<NeuronSet> for NeuronMask {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_set_arithmetic() {
+        let v = ValueSet::from_value(array![[1.0, 2.0], [3.0, 4.0]]);
+
+        assert_eq!((v.clone() + v.clone()).matrix(2), array![[2.0, 4.0], [6.0, 8.0]]);
+        assert_eq!((v.clone() * v.clone()).matrix(2), array![[1.0, 4.0], [9.0, 16.0]]);
+        assert_eq!((v.clone() * 2.0 + 1.0).matrix(2), array![[3.0, 5.0], [7.0, 9.0]]);
+        assert_eq!((v * ValueSet::constant(0.5)).matrix(2), array![[0.5, 1.0], [1.5, 2.0]]);
+    }
+
+    #[test]
+    fn value_set_masked() {
+        let v = ValueSet::constant(2.0).masked(mask::one_to_one());
+
+        assert_eq!(v.matrix(2), array![[2.0, 0.0], [0.0, 2.0]]);
+    }
+}
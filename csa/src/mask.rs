@@ -2,49 +2,75 @@ use utils::random;
 
 use ndarray::{Array, Array2};
 
-use std::ops::{Add, Sub, BitAnd};
+use std::ops::{Add, Sub, BitAnd, Not};
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 
 pub type MaskFn = Arc<dyn Fn(u32, u32) -> bool>;
 
+/// Columns j < m of the connections of row i, in increasing order
+pub type RowFn = Arc<dyn Fn(u32, usize) -> Vec<u32>>;
+
+/// A value computed for the last number of columns it was computed for
+pub(crate) type ColumnCache<T> = RefCell<Option<(usize, Rc<T>)>>;
+
 /// A mask is a function (i,j) -> {0,1}
+///
+/// A mask can also enumerate the connections of each row, such that only its nonzero entries
+/// are evaluated; otherwise, each row is evaluated by f for every column. Stochastic masks draw
+/// a new sample each time they are evaluated, whether by f or by rows.
 #[derive(Clone)]
 pub struct Mask {
-    pub f: MaskFn
+    pub f: MaskFn,
+    pub rows: Option<RowFn>,
 }
 
 impl Mask {
-    // Output of this should be a u32 x [f32; n] matrix
-    pub fn matrix(&self, n: usize) -> Array2<u32> {
-        let mut m = Array::zeros((n,n));
+    pub fn new(f: MaskFn) -> Mask {
+        Mask { f, rows: None }
+    }
 
-        for (ix, v) in m.iter_mut().enumerate() {
-            let i = (ix / n) as u32;
-            let j = (ix % n) as u32;
+    /// Enumerate the connections of each row by rows, rather than by evaluating f
+    pub fn with_rows(mut self, rows: RowFn) -> Mask {
+        self.rows = Some(rows);
+        self
+    }
 
-            if (self.f)(i,j) {
-                *v = 1;
-            }
+    /// Columns j < m of the connections of row i, in increasing order
+    pub fn row(&self, i: u32, m: usize) -> Vec<u32> {
+        match &self.rows {
+            Some(rows) => rows(i, m),
+            None => (0..m as u32).filter(|j| (self.f)(i, *j)).collect()
         }
+    }
 
-        m
+    /// The connections (i, j) of the n x m mask, in row-major order
+    pub fn connections(&self, n: usize, m: usize) -> Vec<(u32, u32)> {
+        (0..n as u32).flat_map(|i| self.row(i, m).into_iter().map(move |j| (i, j))).collect()
     }
 
-    pub fn r_matrix(&self, n: usize, m: usize) -> Array2<u32> {
-        let mut mx = Array::zeros((n,m));
+    // Output of this should be a u32 x [f32; n] matrix
+    pub fn matrix(&self, n: usize) -> Array2<u32> {
+        self.r_matrix(n, n)
+    }
 
-        for (ix, v) in mx.iter_mut().enumerate() {
-            let i = (ix / m) as u32;
-            let j = (ix % m) as u32;
+    pub fn r_matrix(&self, n: usize, m: usize) -> Array2<u32> {
+        connection_matrix(&self.connections(n, m), (n, m))
+    }
+}
 
-            if (self.f)(i,j) {
-                *v = 1;
-            }
-        }
+/// Connection matrix of the given shape from a list of connections
+pub fn connection_matrix(connections: &[(u32, u32)], shape: (usize, usize)) -> Array2<u32> {
+    let mut m = Array::zeros(shape);
 
-        mx
+    for (i, j) in connections {
+        m[[*i as usize, *j as usize]] = 1;
     }
+
+    m
 }
 
 impl Add for Mask {
@@ -54,8 +80,18 @@ impl Add for Mask {
         let f1 = self.f.clone();
         let f2 = other.f.clone();
 
-        Mask {
-            f: Arc::new(move |i,j| f1(i,j) || f2(i,j))
+        let mask = Mask::new(Arc::new(move |i,j| f1(i,j) || f2(i,j)));
+
+        match (self.rows, other.rows) {
+            (Some(r1), Some(r2)) => mask.with_rows(Arc::new(move |i, m| {
+                let mut r = r1(i, m);
+                r.extend(r2(i, m));
+                r.sort_unstable();
+                r.dedup();
+
+                r
+            })),
+            _ => mask
         }
     }
 }
@@ -64,11 +100,22 @@ impl Sub for Mask {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        let f1 = self.f.clone();
-        let f2 = other.f.clone();
-
-        Mask {
-            f: Arc::new(move |i,j| f1(i,j) && !f2(i,j))
+        let f1 = self.f;
+        let f2 = other.f;
+
+        let mask = Mask::new(Arc::new({
+            let f2 = f2.clone();
+            move |i,j| f1(i,j) && !f2(i,j)
+        }));
+
+        match self.rows {
+            Some(r1) => mask.with_rows(Arc::new(move |i, m| {
+                let mut r = r1(i, m);
+                r.retain(|j| !f2(i, *j));
+
+                r
+            })),
+            None => mask
         }
     }
 }
@@ -77,37 +124,195 @@ impl BitAnd for Mask {
     type Output = Self;
 
     fn bitand(self, other: Self) -> Self {
-        let f1 = self.f.clone();
-        let f2 = other.f.clone();
+        let (f1, f2) = (self.f, other.f);
+
+        let mask = Mask::new(Arc::new({
+            let (f1, f2) = (f1.clone(), f2.clone());
+            move |i,j| f1(i,j) && f2(i,j)
+        }));
+
+        // The rows of one mask are filtered by the other
+        match (self.rows, other.rows) {
+            (Some(r1), _) => mask.with_rows(Arc::new(move |i, m| {
+                let mut r = r1(i, m);
+                r.retain(|j| f2(i, *j));
+
+                r
+            })),
+            (None, Some(r2)) => mask.with_rows(Arc::new(move |i, m| {
+                let mut r = r2(i, m);
+                r.retain(|j| f1(i, *j));
+
+                r
+            })),
+            (None, None) => mask
+        }
+    }
+}
+
+/// The complement of a mask
+impl Not for Mask {
+    type Output = Self;
 
-        Mask {
-            f: Arc::new(move |i,j| f1(i,j) && f2(i,j))
+    fn not(self) -> Self {
+        let f = self.f;
+        let mask = Mask::new(Arc::new(move |i,j| !f(i,j)));
+
+        match self.rows {
+            Some(r) => mask.with_rows(Arc::new(move |i, m| {
+                let row = r(i, m);
+
+                (0..m as u32).filter(|j| row.binary_search(j).is_err()).collect()
+            })),
+            None => mask
         }
     }
 }
 
 pub fn empty() -> Mask {
-    Mask { f: Arc::new(|_i, _j| false) }
+    Mask::new(Arc::new(|_i, _j| false))
+        .with_rows(Arc::new(|_i, _m| vec![]))
 }
 
 pub fn full() -> Mask {
-    Mask { f: Arc::new(|_i, _j| true) }
+    Mask::new(Arc::new(|_i, _j| true))
+        .with_rows(Arc::new(|_i, m| (0..m as u32).collect()))
 }
 
 pub fn one_to_one() -> Mask {
-    Mask { f: Arc::new(|i, j| i == j) }
+    Mask::new(Arc::new(|i, j| i == j))
+        .with_rows(Arc::new(|i, m| if (i as usize) < m { vec![i] } else { vec![] }))
 }
 
 pub fn random(p: f32) -> Mask {
-    Mask {f: Arc::new(
-        move |_i, _j| random::random_range((0.0, 1.0)) < p
-    )}
+    Mask::new(Arc::new(move |_i, _j| random::random_range((0.0, 1.0)) < p))
+        .with_rows(Arc::new(move |_i, m| sample(p, m).into_iter().map(|j| j as u32).collect()))
+}
+
+/// Fixed fan-in, where each neuron i has exactly k connections from distinct neurons of 0..m.
+///
+/// The connections of each neuron are drawn once, when first evaluated, such that all
+/// evaluations of the mask agree.
+pub fn random_fan_in(k: usize, m: usize) -> Mask {
+    assert!(k <= m, "fan-in of {k} from {m} neurons");
+
+    let draws = Draws::new(k, m);
+    let rows = draws.clone();
+
+    Mask::new(Arc::new(move |i, j| draws.get(i).binary_search(&j).is_ok()))
+        .with_rows(Arc::new(move |i, m| rows.get(i).iter().copied().filter(|j| (*j as usize) < m).collect()))
+}
+
+/// Fixed fan-out, where each neuron j has exactly k connections to distinct neurons of 0..n.
+///
+/// As for random_fan_in, the connections of each neuron are drawn once.
+pub fn random_fan_out(k: usize, n: usize) -> Mask {
+    assert!(k <= n, "fan-out of {k} to {n} neurons");
+
+    let draws = Draws::new(k, n);
+    let rows = draws.clone();
+
+    Mask::new(Arc::new(move |i, j| draws.get(j).binary_search(&i).is_ok()))
+        .with_rows(transposed_rows(Arc::new(
+            move |j, n| rows.get(j).iter().copied().filter(|i| (*i as usize) < n).collect()
+        ), n))
+}
+
+/// Rows of the transpose of the mask with the given rows, which has n columns.
+///
+/// Row i of the transpose is column i of the mask, so the connections of the m x n mask are
+/// enumerated once for each m and split by column. Stochastic masks are thus sampled once for
+/// each m, rather than for each row.
+pub(crate) fn transposed_rows(rows: RowFn, n: usize) -> RowFn {
+    let columns: ColumnCache<Vec<Vec<u32>>> = RefCell::new(None);
+
+    Arc::new(move |i, m| {
+        if i as usize >= n {
+            return vec![];
+        }
+
+        let mut columns = columns.borrow_mut();
+
+        if let Some((cm, c)) = &*columns {
+            if *cm == m {
+                return c[i as usize].clone();
+            }
+        }
+
+        let mut c: Vec<Vec<u32>> = vec![vec![]; n];
+        for j in 0..m as u32 {
+            for i in rows(j, n) {
+                c[i as usize].push(j);
+            }
+        }
+
+        let row = c[i as usize].clone();
+        *columns = Some((m, Rc::new(c)));
+
+        row
+    })
+}
+
+/// k distinct neurons of 0..n for each neuron, drawn when first requested
+#[derive(Clone)]
+struct Draws {
+    k: usize,
+    n: usize,
+    draws: Rc<RefCell<HashMap<u32, Rc<Vec<u32>>>>>,
+}
+
+impl Draws {
+    fn new(k: usize, n: usize) -> Draws {
+        Draws { k, n, draws: Rc::new(RefCell::new(HashMap::new())) }
+    }
+
+    /// The neurons drawn for neuron i, in increasing order
+    fn get(&self, i: u32) -> Rc<Vec<u32>> {
+        self.draws.borrow_mut().entry(i).or_insert_with(|| {
+            let mut d: Vec<u32> = random::with_rng(|rng| rand::seq::index::sample(rng, self.n, self.k))
+                .into_iter().map(|j| j as u32).collect();
+            d.sort_unstable();
+
+            Rc::new(d)
+        }).clone()
+    }
+}
+
+/// Indices in 0..len, each drawn with probability p, in increasing order.
+///
+/// The gaps between the drawn indices are geometrically distributed, so that only the drawn
+/// indices are visited.
+pub(crate) fn sample(p: f32, len: usize) -> Vec<usize> {
+    if p <= 0.0 {
+        return vec![];
+    }
+
+    if p >= 1.0 {
+        return (0..len).collect();
+    }
+
+    let log_q = (1.0 - p as f64).ln();
+
+    let mut s = vec![];
+    let mut k: usize = 0;
+
+    loop {
+        let u: f64 = random::random_range((0.0, 1.0));
+        k = k.saturating_add(((1.0 - u).ln() / log_q).floor() as usize);
+
+        if k >= len {
+            return s;
+        }
+
+        s.push(k);
+        k += 1;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ndarray::{array, s};
+    use ndarray::{array, s, Axis};
 
     use utils::math;
 
@@ -135,4 +340,59 @@ mod tests {
 
         assert!(matrix == id_m);
     }
+
+    #[test]
+    fn mask_rows_match_f() {
+        let masks = [
+            empty(),
+            full(),
+            one_to_one(),
+            full() - one_to_one(),
+            (full() & one_to_one()) + empty(),
+            Mask::new(Arc::new(|i, j| (i + j) % 3 == 0)) & full(),
+        ];
+
+        for m in masks {
+            assert_eq!(m.matrix(N), Mask::new(m.f.clone()).matrix(N));
+            assert_eq!(m.r_matrix(N, N / 2), Mask::new(m.f.clone()).r_matrix(N, N / 2));
+        }
+    }
+
+    #[test]
+    fn random_mask_rows() {
+        let n = 200;
+        let p = 0.2;
+
+        let c = random(p).connections(n, n);
+
+        let expected = p * (n * n) as f32;
+        assert!((c.len() as f32 - expected).abs() < 0.05 * expected);
+
+        assert!(c.windows(2).all(|x| x[0] < x[1]));
+
+        assert!(sample(0.0, n).is_empty());
+        assert_eq!(sample(1.0, n), (0..n).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn mask_complement() {
+        assert_eq!((!one_to_one()).matrix(N), (full() - one_to_one()).matrix(N));
+        assert_eq!((!empty()).matrix(N), full().matrix(N));
+
+        let m = !Mask::new(Arc::new(|i, j| i < j));
+        assert_eq!(m.matrix(3), array![[1, 0, 0], [1, 1, 0], [1, 1, 1]]);
+    }
+
+    #[test]
+    fn fixed_fan_in_and_fan_out() {
+        let fan_in = random_fan_in(3, N);
+
+        assert!(fan_in.matrix(N).sum_axis(Axis(1)).iter().all(|s| *s == 3));
+        assert_eq!(fan_in.matrix(N), Mask::new(fan_in.f.clone()).matrix(N));
+
+        let fan_out = random_fan_out(4, N);
+
+        assert!(fan_out.matrix(N).sum_axis(Axis(0)).iter().all(|s| *s == 4));
+        assert_eq!(fan_out.matrix(N), Mask::new(fan_out.f.clone()).matrix(N));
+    }
 }
@@ -1,8 +1,10 @@
-use crate::{ValueSet, NeuronSet};
-use crate::mask::Mask;
+use crate::{ValueSet, NeuronSet, NeuronMask};
+use crate::mask::{self, Mask, ColumnCache};
 
 use utils::random;
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 
@@ -11,24 +13,59 @@ pub type LabelFn = Arc<dyn Fn(u32) -> u32>;
 /// The operator version of \rho, operating on the value set of connection probabilities
 pub fn p(v: ValueSet) -> Mask {
     let f = v.f;
-    Mask {
-        f: Arc::new(
-                move |i, j| random::random_range((0.0, 1.0)) < f(i, j)
-            )
-    }
+    Mask::new(Arc::new(
+        move |i, j| random::random_range((0.0, 1.0)) < f(i, j)
+    ))
 }
 
 /// Expands each entry in c to a nxn block in a new matrix
 pub fn block(n: u32, m: Mask) -> Mask {
     let f1 = m.f.clone();
 
-    Mask {
-        f: Arc::new(
-               move |i, j| f1(i/n, j/n)
-           )
+    let mask = Mask::new(Arc::new(
+        move |i, j| f1(i/n, j/n)
+    ));
+
+    match m.rows {
+        Some(r) => mask.with_rows(Arc::new(move |i, m| {
+            r(i/n, m.div_ceil(n as usize)).into_iter()
+                .flat_map(|b| (b*n)..std::cmp::min((b+1)*n, m as u32))
+                .collect()
+        })),
+        None => mask
     }
 }
 
+/// The transpose of a mask with n columns, which connects j to i where m connects i to j
+pub fn transpose(m: Mask, n: usize) -> Mask {
+    let f = m.f;
+
+    let mask = Mask::new(Arc::new(
+        move |i, j| f(j, i)
+    ));
+
+    match m.rows {
+        Some(r) => mask.with_rows(mask::transposed_rows(r, n)),
+        None => mask
+    }
+}
+
+/// The Cartesian product of two neuron masks, which connects each neuron i of a
+/// to each neuron j of b
+pub fn cross(a: NeuronMask, b: NeuronMask) -> Mask {
+    let (fa, fb) = (a.f.clone(), b.f.clone());
+
+    Mask::new(Arc::new(
+        move |i, j| fa(i) && fb(j)
+    )).with_rows(Arc::new(move |i, m| {
+        if (a.f)(i) {
+            (0..m as u32).filter(|j| (b.f)(*j)).collect()
+        } else {
+            vec![]
+        }
+    }))
+}
+
 pub fn label(dist: Vec<usize>, k: usize) -> LabelFn {
     let mut dist_map = vec![];
     for l in 0..k {
@@ -42,14 +79,82 @@ pub fn label(dist: Vec<usize>, k: usize) -> LabelFn {
 /// the structures in the network set
 pub fn group(l: LabelFn, m: Mask) -> Mask {
     let f = m.f.clone();
+    let members = Members::new(l.clone());
 
-    Mask { f: Arc::new(
+    let mask = Mask::new(Arc::new(
         move |i, j| f(l(i), l(j))
-        )}
+    ));
+
+    match m.rows {
+        Some(r) => mask.with_rows(Arc::new(move |i, m| {
+            let labels = members.get(m);
+
+            let mut row: Vec<u32> = r((members.l)(i), labels.len()).into_iter()
+                .flat_map(|b| labels[b as usize].iter().copied())
+                .collect();
+            row.sort_unstable();
+
+            row
+        })),
+        None => mask
+    }
 }
 
+/// Stochastic block model, where entry (a, b) of cpm is the probability of a connection
+/// from a neuron of label a to a neuron of label b
 pub fn sbm(l: LabelFn, cpm: ValueSet) -> Mask {
-    group(l, p(cpm))
+    let f = cpm.f.clone();
+    let members = Members::new(l.clone());
+
+    group(l, p(cpm)).with_rows(Arc::new(move |i, m| {
+        let labels = members.get(m);
+        let a = (members.l)(i);
+
+        let mut row: Vec<u32> = labels.iter().enumerate()
+            .flat_map(|(b, js)| mask::sample(f(a, b as u32), js.len()).into_iter().map(|k| js[k]))
+            .collect();
+        row.sort_unstable();
+
+        row
+    }))
+}
+
+/// The neurons 0..m of each label, computed once for each number of neurons m
+struct Members {
+    l: LabelFn,
+    cache: ColumnCache<Vec<Vec<u32>>>,
+}
+
+impl Members {
+    fn new(l: LabelFn) -> Members {
+        Members { l, cache: RefCell::new(None) }
+    }
+
+    fn get(&self, m: usize) -> Rc<Vec<Vec<u32>>> {
+        let mut cache = self.cache.borrow_mut();
+
+        if let Some((n, labels)) = &*cache {
+            if *n == m {
+                return labels.clone();
+            }
+        }
+
+        let mut labels: Vec<Vec<u32>> = vec![];
+        for j in 0..m as u32 {
+            let b = (self.l)(j) as usize;
+
+            if b >= labels.len() {
+                labels.resize(b + 1, vec![]);
+            }
+
+            labels[b].push(j);
+        }
+
+        let labels = Rc::new(labels);
+        *cache = Some((m, labels.clone()));
+
+        labels
+    }
 }
 
 
@@ -60,18 +165,114 @@ pub fn n_group(l: LabelFn, n: NeuronSet) -> NeuronSet {
     NeuronSet { f: Arc::new( move |i| f(l(i))) }
 }
 
+/// Group operator on value sets, which assigns values by the labels of both neurons
+pub fn v_group(l: LabelFn, v: ValueSet) -> ValueSet {
+    let f = v.f.clone();
+
+    ValueSet { f: Arc::new( move |i, j| f(l(i), l(j))) }
+}
+
 
 pub mod geometric {
     use super::*;
 
+    use std::collections::HashMap;
+
     pub type Metric = Arc<dyn Fn(u32, u32) -> f32>;
     pub type CoordinateFn = Arc<dyn Fn(u32) -> (f32, f32)>;
 
-    /// Operates on a metric to restrict connectivity to neurons whose distance is less than r
+    /// Operates on a metric to restrict connectivity to neurons whose distance is less than r.
+    ///
+    /// As the metric is arbitrary, each row is evaluated for every column; where the metric is
+    /// the distance between fixed coordinates, coordinate_disc enumerates the rows instead.
     pub fn disc(r: f32, m: Metric) -> Mask {
-        Mask {
+        Mask::new(Arc::new(
+            move |i, j| m(i,j) < r
+        ))
+    }
+
+    /// Same as disc(r, distance_metric(g1, g2)), where the connections of each row are found
+    /// from a grid over the coordinates of the columns, rather than by the distance to every
+    /// column. The coordinates must be fixed, as those of random_coordinates.
+    pub fn coordinate_disc(r: f32, g1: CoordinateFn, g2: CoordinateFn) -> Mask {
+        // Columns by the grid cell of size r x r they are in, for the last number of columns
+        let grid: ColumnCache<HashMap<(i64, i64), Vec<u32>>> = RefCell::new(None);
+
+        let cell = move |(x, y): (f32, f32)| ((x / r).floor() as i64, (y / r).floor() as i64);
+
+        let (c1, c2) = (g1.clone(), g2.clone());
+
+        disc(r, distance_metric(g1, g2)).with_rows(Arc::new(move |i, m| {
+            if r <= 0.0 {
+                return vec![];
+            }
+
+            let cells = {
+                let mut grid = grid.borrow_mut();
+
+                match &*grid {
+                    Some((n, cells)) if *n == m => cells.clone(),
+                    _ => {
+                        let mut cells: HashMap<(i64, i64), Vec<u32>> = HashMap::new();
+                        for j in 0..m as u32 {
+                            cells.entry(cell(c2(j))).or_default().push(j);
+                        }
+
+                        let cells = Rc::new(cells);
+                        *grid = Some((m, cells.clone()));
+
+                        cells
+                    }
+                }
+            };
+
+            let (x, y) = c1(i);
+            let (cx, cy) = cell((x, y));
+
+            let mut row = vec![];
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for j in cells.get(&(cx + dx, cy + dy)).into_iter().flatten() {
+                        let (jx, jy) = c2(*j);
+
+                        if ((x - jx).powf(2.0) + (y - jy).powf(2.0)).sqrt() < r {
+                            row.push(*j);
+                        }
+                    }
+                }
+            }
+            row.sort_unstable();
+
+            row
+        }))
+    }
+
+    /// Value set of the distance between neurons
+    pub fn distance(m: Metric) -> ValueSet {
+        ValueSet { f: m }
+    }
+
+    /// Value set of transmission delays (ms) proportional to the distance between neurons,
+    /// where scale is the delay per unit of distance
+    pub fn distance_delay(scale: f32, m: Metric) -> ValueSet {
+        distance(m) * scale
+    }
+
+    /// Gaussian of the distance between neurons, exp(-d^2 / 2 sigma^2), which is 1 at distance 0
+    pub fn gaussian(sigma: f32, m: Metric) -> ValueSet {
+        ValueSet {
+            f: Arc::new(
+                   move |i, j| (-m(i,j).powf(2.0) / (2.0 * sigma.powf(2.0))).exp()
+               )
+        }
+    }
+
+    /// Exponential decay with the distance between neurons, exp(-d / lambda), where lambda
+    /// is the length constant
+    pub fn exponential_decay(lambda: f32, m: Metric) -> ValueSet {
+        ValueSet {
             f: Arc::new(
-                   move |i, j| m(i,j) < r
+                   move |i, j| (-m(i,j) / lambda).exp()
                )
         }
     }
@@ -106,6 +307,8 @@ pub mod geometric {
 mod tests {
     use super::*;
 
+    use crate::mask::Mask;
+
     use ndarray::{array, s, Array, Array2};
     use utils::math;
 
@@ -153,4 +356,142 @@ mod tests {
         assert!(c.iter().all(|x| *x == 1));
         assert!(d.iter().all(|x| *x == 0));
     }
+
+    #[test]
+    fn v_group_op() {
+        let labels = label(vec![2, 1], 2);
+
+        let v = v_group(labels, ValueSet::from_value(array![[0.0, 1.0],
+                                                             [2.0, 3.0]]));
+
+        assert_eq!(v.matrix(3), array![[0.0, 0.0, 1.0],
+                                       [0.0, 0.0, 1.0],
+                                       [2.0, 2.0, 3.0]]);
+    }
+
+    #[test]
+    fn distance_delay_op() {
+        use geometric::*;
+
+        let coords: CoordinateFn = Arc::new(|i| (i as f32, 0.0));
+        let delay = distance_delay(2.0, distance_metric(coords.clone(), coords)).matrix(3);
+
+        assert_eq!(delay, array![[0.0, 2.0, 4.0],
+                                 [2.0, 0.0, 2.0],
+                                 [4.0, 2.0, 0.0]]);
+    }
+
+    #[test]
+    fn sparse_ops_match_f() {
+        let labels = label(vec![3, 5, 2], 3);
+
+        let masks = [
+            block(3, crate::mask::one_to_one()),
+            group(labels.clone(), crate::mask::one_to_one()),
+            sbm(labels, ValueSet::from_value(array![[1.0, 0.0, 1.0],
+                                                      [0.0, 1.0, 0.0],
+                                                      [1.0, 1.0, 0.0]])),
+        ];
+
+        for m in masks {
+            assert!(m.rows.is_some());
+            assert_eq!(m.matrix(N), Mask::new(m.f.clone()).matrix(N));
+        }
+    }
+
+    #[test]
+    fn coordinate_disc_op() {
+        use geometric::*;
+
+        let coords = random_coordinates(0.0, 10.0, 100);
+
+        let m = coordinate_disc(2.0, coords.clone(), coords.clone());
+        let d = disc(2.0, distance_metric(coords.clone(), coords));
+
+        assert_eq!(m.matrix(100), d.matrix(100));
+    }
+
+    #[test]
+    fn disc_sparse_and_dense_connections() {
+        use geometric::*;
+
+        let g1 = random_coordinates(0.0, 10.0, 80);
+        let g2 = random_coordinates(0.0, 10.0, 50);
+
+        let m = coordinate_disc(1.5, g1, g2) & Mask::new(Arc::new(|i, j| (i + j) % 3 != 0));
+        let dense = Mask::new(m.f.clone());
+
+        assert!(m.rows.is_some());
+        assert_eq!(m.connections(80, 50), dense.connections(80, 50));
+    }
+
+    #[test]
+    fn transpose_op() {
+        let m = Mask::new(Arc::new(|i, j| i < j));
+
+        assert_eq!(transpose(m.clone(), N).matrix(N), m.matrix(N).t());
+
+        let m = mask::random_fan_in(3, N);
+        let t = transpose(m.clone(), N);
+
+        assert!(t.rows.is_some());
+        assert_eq!(t.r_matrix(N, 5), m.r_matrix(5, N).t());
+        assert_eq!(t.matrix(N), Mask::new(t.f.clone()).matrix(N));
+    }
+
+    #[test]
+    fn transpose_samples_random_mask_once() {
+        use std::cell::Cell;
+
+        let (n, m, p) = (200, 50, 0.3);
+
+        // Counts the rows enumerated of the random m x n mask
+        let calls = Rc::new(Cell::new(0));
+        let r = mask::random(p);
+        let rows = r.rows.clone().unwrap();
+
+        let counted = r.with_rows(Arc::new({
+            let calls = calls.clone();
+            move |i, m| { calls.set(calls.get() + 1); rows(i, m) }
+        }));
+
+        let t = transpose(counted, n).r_matrix(n, m);
+
+        assert_eq!(calls.get(), m);
+
+        let expected = p * (n * m) as f32;
+        assert!((t.sum() as f32 - expected).abs() < 0.1 * expected, "{} connections", t.sum());
+    }
+
+    #[test]
+    fn cross_op() {
+        let m = cross(NeuronMask::interval(0, 2), NeuronMask::interval(1, 3));
+
+        assert_eq!(m.matrix(4), array![[0, 1, 1, 0],
+                                        [0, 1, 1, 0],
+                                        [0, 0, 0, 0],
+                                        [0, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn distance_dependent_values() {
+        use geometric::*;
+
+        let coords: CoordinateFn = Arc::new(|i| (i as f32, 0.0));
+        let d = distance_metric(coords.clone(), coords);
+
+        let g = gaussian(1.0, d.clone()).matrix(3);
+        let e = exponential_decay(2.0, d.clone()).matrix(3);
+
+        assert_eq!(g[[0, 0]], 1.0);
+        assert!((g[[0, 2]] - (-2.0f32).exp()).abs() < 1e-6);
+        assert!((e[[2, 0]] - (-1.0f32).exp()).abs() < 1e-6);
+
+        // Weights that decay with distance, between neighbouring neurons only
+        let w = (gaussian(1.0, d.clone()) * 4.0).masked(disc(1.5, d) - crate::mask::one_to_one());
+
+        assert_eq!(w.matrix(3).mapv(|x| x > 0.0), array![[false, true, false],
+                                                          [true, false, true],
+                                                          [false, true, false]]);
+    }
 }
@@ -8,26 +8,29 @@ use serde::{Serialize, Deserialize};
 type Generation = (f32, f32, f32);
 
 
+/// Statistics of one or more optimization runs, where R is the representation of the evolved networks
 #[derive(Clone, Deserialize, Serialize)]
-pub struct OptimizationStatistics {
-    pub runs: Vec<Run>
+pub struct OptimizationStatistics<R = DefaultRepresentation> {
+    pub runs: Vec<Run<R>>
 }
 
-impl OptimizationStatistics {
-    pub fn new() -> OptimizationStatistics {
+impl<R> OptimizationStatistics<R> {
+    pub fn new() -> OptimizationStatistics<R> {
         OptimizationStatistics {
             runs: vec![Run::new()]
         }
     }
 
-    pub fn empty() -> OptimizationStatistics {
+    pub fn empty() -> OptimizationStatistics<R> {
         OptimizationStatistics {
             runs: vec![]
         }
     }
 
+    /// Logs a generation, where best_network is the best network of the generation, its
+    /// parameters and the seed it was developed from
     pub fn log_generation(&mut self, best: f32, mean: f32, std: f32,
-        best_network: (DefaultRepresentation, ParameterSet)) {
+        best_network: (R, ParameterSet, u64)) {
         self.runs.last_mut().unwrap().log(best, mean, std, best_network);
     }
 
@@ -43,11 +46,11 @@ impl OptimizationStatistics {
         self.runs.push(Run::new());
     }
 
-    pub fn push_run(&mut self, run: Run) {
+    pub fn push_run(&mut self, run: Run<R>) {
         self.runs.push(run);
     }
 
-    pub fn run(&self) -> &Run {
+    pub fn run(&self) -> &Run<R> {
         self.runs.last().unwrap()
     }
 
@@ -60,38 +63,46 @@ impl OptimizationStatistics {
     //    self.runs.iter().fold(vec![], |acc, x| [acc, x.best_fitness.clone()].concat())
     //}
 
-    pub fn best(&self) -> (f32, &DefaultRepresentation, &ParameterSet) {
+    pub fn best(&self) -> (f32, &R, &ParameterSet) {
         self.runs.iter().map(|r| r.best()).max_by(|a,b| a.0.partial_cmp(&b.0).expect("")).unwrap()
     }
 }
 
 #[derive(Clone, Deserialize, Serialize)]
-pub struct Run {
+pub struct Run<R = DefaultRepresentation> {
     pub generations: Vec<Generation>,
     pub validation: Vec<f32>,
     pub accuracy: Vec<f32>,
-    pub best_network: Option<(f32, DefaultRepresentation, ParameterSet)>
+    pub best_network: Option<(f32, R, ParameterSet)>,
+
+    /// Seed the best network was developed from, such that it can be developed
+    /// again from its parameters by Model::develop_with_seed
+    #[serde(default)]
+    pub best_seed: Option<u64>,
 }
 
-impl Run {
+impl<R> Run<R> {
     fn new() -> Self {
         Run {
             generations: Vec::new(),
             validation: Vec::new(),
             accuracy: Vec::new(),
-            best_network: None
+            best_network: None,
+            best_seed: None,
         }
     }
 
-    fn log(&mut self, best: f32, mean: f32, std: f32, network: (DefaultRepresentation, ParameterSet)) {
+    fn log(&mut self, best: f32, mean: f32, std: f32, network: (R, ParameterSet, u64)) {
         self.generations.push((best, mean, std));
 
-        if let Some((f, _, _)) = self.best_network {
-            if best > f {
-                self.best_network = Some((best, network.0, network.1));
-            }
-        } else {
+        let improved = match self.best_network {
+            Some((f, _, _)) => best > f,
+            None => true,
+        };
+
+        if improved {
             self.best_network = Some((best, network.0, network.1));
+            self.best_seed = Some(network.2);
         }
     }
 
@@ -104,7 +115,7 @@ impl Run {
     }
 
     // Return the best individual of the run
-    pub fn best(&self) -> (f32, &DefaultRepresentation, &ParameterSet) {
+    pub fn best(&self) -> (f32, &R, &ParameterSet) {
         match &self.best_network {
             Some((f, r, p)) => { (*f, &r, &p) },
             None => { panic!("best called on Run with no best network"); }
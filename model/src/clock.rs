@@ -0,0 +1,288 @@
+//! Simulation clock shared by the network, neurons and synapses.
+
+use crate::parallel::zip;
+
+use ndarray::{Array, Array1};
+
+use serde::{Serialize, Deserialize};
+
+
+/// Default duration of a single step (ms)
+pub const DEFAULT_DT: f32 = 1.0;
+
+/// Largest difference from 1 ms of the steps in a millisecond, allowing for rounding of dt
+const DT_TOLERANCE: f32 = 1e-4;
+
+/// The variables of a system of equations, one array per variable
+pub type State = Vec<Array1<f32>>;
+
+/// Coefficients (a, b) for each variable of a system, see Integrator
+pub type Linearization = Vec<(Array1<f32>, Array1<f32>)>;
+
+/// Networks are stepped a whole number of times per millisecond, so dt must divide 1 ms
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "ClockConfig")]
+pub struct Clock {
+    /// Duration of a single step (ms)
+    pub dt: f32,
+    pub integrator: Integrator,
+}
+
+impl Clock {
+    pub fn new(dt: f32, integrator: Integrator) -> Clock {
+        if let Err(e) = Self::validate(dt) {
+            panic!("{e}");
+        }
+
+        Clock {
+            dt,
+            integrator
+        }
+    }
+
+    fn validate(dt: f32) -> Result<(), String> {
+        if dt <= 0.0 || dt > 1.0 {
+            return Err(format!("dt must be in (0, 1] ms, got {dt}"));
+        }
+
+        if ((1.0 / dt).round() * dt - 1.0).abs() > DT_TOLERANCE {
+            return Err(format!("dt must divide 1 ms into a whole number of steps, got {dt}"));
+        }
+
+        Ok(())
+    }
+
+    /// Number of steps that make up a single millisecond
+    pub fn steps_per_ms(&self) -> usize {
+        if let Err(e) = Self::validate(self.dt) {
+            panic!("{e}");
+        }
+
+        (1.0 / self.dt).round() as usize
+    }
+
+    /// Factor by which a variable decaying with time constant tau (ms) is multiplied in a single step
+    pub fn decay(&self, tau: f32) -> f32 {
+        let x = self.integrator.step(&[Array::ones(1)], self.dt,
+            |_| vec![(Array::from_elem(1, -1.0 / tau), Array::zeros(1))]);
+
+        x[0][0]
+    }
+}
+
+/// A clock as given in a config, which is rejected when dt is outside (0, 1] ms
+/// or does not divide 1 ms
+#[derive(Deserialize)]
+struct ClockConfig {
+    dt: f32,
+    integrator: Integrator,
+}
+
+impl TryFrom<ClockConfig> for Clock {
+    type Error = String;
+
+    fn try_from(c: ClockConfig) -> Result<Clock, String> {
+        Self::validate(c.dt)?;
+
+        Ok(Clock { dt: c.dt, integrator: c.integrator })
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            dt: DEFAULT_DT,
+            integrator: Integrator::Euler,
+        }
+    }
+}
+
+/*
+ * Each variable x_i of a system is written on the form
+ *
+ *      dx_i/dt = a_i(x) * x_i + b_i(x)
+ *
+ * which allows the exponential Euler method to treat the linear part exactly,
+ * by holding a_i and b_i constant over the step:
+ *
+ *      x_i(t + dt) = x_i * exp(a_i * dt) + b_i / a_i * (exp(a_i * dt) - 1)
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Integrator {
+    #[serde(rename = "euler")]
+    Euler,
+    #[serde(rename = "exponential_euler")]
+    ExponentialEuler,
+    #[serde(rename = "rk4")]
+    RK4,
+}
+
+impl Integrator {
+    /// Advances the state x by dt, where f gives the coefficients (a, b) of each variable
+    pub fn step<F>(&self, x: &[Array1<f32>], dt: f32, f: F) -> State
+        where F: Fn(&[Array1<f32>]) -> Linearization
+    {
+        match self {
+            Integrator::Euler => euler(x, dt, &f),
+            Integrator::ExponentialEuler => exponential_euler(x, dt, &f),
+            Integrator::RK4 => rk4(x, dt, &f),
+        }
+    }
+}
+
+fn derivative(x: &[Array1<f32>], l: &Linearization) -> State {
+    x.iter().zip(l).map(|(x, (a, b))| {
+        let mut dx = Array::zeros(x.len());
+        zip!(x.len(), (dx in &mut dx, &x in x, &a in a, &b in b) {
+            *dx = a * x + b;
+        });
+
+        dx
+    }).collect()
+}
+
+fn offset(x: &[Array1<f32>], k: &State, h: f32) -> State {
+    x.iter().zip(k).map(|(x, k)| {
+        let mut y = Array::zeros(x.len());
+        zip!(x.len(), (y in &mut y, &x in x, &k in k) {
+            *y = x + h * k;
+        });
+
+        y
+    }).collect()
+}
+
+fn euler<F>(x: &[Array1<f32>], dt: f32, f: &F) -> State
+    where F: Fn(&[Array1<f32>]) -> Linearization
+{
+    let k = derivative(x, &f(x));
+
+    offset(x, &k, dt)
+}
+
+fn exponential_euler<F>(x: &[Array1<f32>], dt: f32, f: &F) -> State
+    where F: Fn(&[Array1<f32>]) -> Linearization
+{
+    x.iter().zip(f(x)).map(|(x, (a, b))| {
+        let mut y = Array::zeros(x.len());
+        zip!(x.len(), (y in &mut y, &x in x, &a in &a, &b in &b) {
+            *y = if a == 0.0 {
+                x + dt * b
+            } else {
+                let e = (a * dt).exp();
+
+                x * e + b / a * (e - 1.0)
+            };
+        });
+
+        y
+    }).collect()
+}
+
+fn rk4<F>(x: &[Array1<f32>], dt: f32, f: &F) -> State
+    where F: Fn(&[Array1<f32>]) -> Linearization
+{
+    let g = |y: &[Array1<f32>]| derivative(y, &f(y));
+
+    let k1 = g(x);
+    let k2 = g(&offset(x, &k1, dt / 2.0));
+    let k3 = g(&offset(x, &k2, dt / 2.0));
+    let k4 = g(&offset(x, &k3, dt));
+
+    x.iter().enumerate().map(|(i, x)| {
+        let mut y = Array::zeros(x.len());
+        zip!(x.len(), (y in &mut y, &x in x, &k1 in &k1[i], &k2 in &k2[i], &k3 in &k3[i], &k4 in &k4[i]) {
+            *y = x + dt / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4);
+        });
+
+        y
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integrates dx/dt = -x + 1 from x = 0 over 1 ms, and returns the absolute error
+    fn error(clock: Clock) -> f32 {
+        let mut x: State = vec![Array::zeros(1)];
+
+        for _ in 0..clock.steps_per_ms() {
+            x = clock.integrator.step(&x, clock.dt, |_| vec![(Array::from_elem(1, -1.0), Array::ones(1))]);
+        }
+
+        (x[0][0] - (1.0 - (-1.0f32).exp())).abs()
+    }
+
+    #[test]
+    fn test_parallel_integration_matches_serial() {
+        // Large enough to be integrated in parallel with the parallel feature
+        let n = crate::parallel::MIN_PARALLEL_LEN + 7;
+
+        let x: State = vec![Array::from_shape_fn(n, |i| i as f32 * 0.01)];
+        let f = |x: &[Array1<f32>]| vec![(Array::from_elem(x[0].len(), -0.5), x[0].mapv(f32::sin))];
+
+        for integrator in [Integrator::Euler, Integrator::ExponentialEuler, Integrator::RK4] {
+            crate::parallel::set_enabled(false);
+            let serial = integrator.step(&x, 0.1, f);
+
+            crate::parallel::set_enabled(true);
+            assert_eq!(integrator.step(&x, 0.1, f), serial, "{integrator:?}");
+        }
+    }
+
+    #[test]
+    fn test_integrators_converge() {
+        for integrator in [Integrator::Euler, Integrator::ExponentialEuler, Integrator::RK4] {
+            let coarse = error(Clock::new(0.1, integrator));
+            let fine = error(Clock::new(0.01, integrator));
+
+            assert!(fine <= coarse + 1e-6, "{integrator:?} - dt=0.1: {coarse}, dt=0.01: {fine}");
+            assert!(fine < 1e-2, "{integrator:?} - error: {fine}");
+        }
+    }
+
+    #[test]
+    fn test_exponential_euler_is_exact_for_linear_equations() {
+        assert!(error(Clock::new(1.0, Integrator::ExponentialEuler)) < 1e-6);
+    }
+
+    #[test]
+    fn test_rk4_is_more_accurate_than_euler() {
+        assert!(error(Clock::new(0.1, Integrator::RK4)) < error(Clock::new(0.1, Integrator::Euler)));
+    }
+
+    #[test]
+    fn test_clock_config_rejects_dt_above_1ms() {
+        for dt in [2.0, 0.0, -1.0] {
+            assert!(Clock::try_from(ClockConfig { dt, integrator: Integrator::Euler }).is_err());
+        }
+
+        assert_eq!(Clock::try_from(ClockConfig { dt: 0.5, integrator: Integrator::Euler }).unwrap().steps_per_ms(), 2);
+    }
+
+    #[test]
+    fn test_clock_config_rejects_dt_not_dividing_1ms() {
+        for dt in [0.3, 0.4, 0.75] {
+            assert!(Clock::try_from(ClockConfig { dt, integrator: Integrator::Euler }).is_err(), "dt = {dt}");
+        }
+
+        for (dt, steps) in [(0.1, 10), (0.2, 5), (0.25, 4), (1.0 / 3.0, 3)] {
+            assert_eq!(Clock::try_from(ClockConfig { dt, integrator: Integrator::Euler }).unwrap().steps_per_ms(), steps);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clock_rejects_dt_above_1ms() {
+        Clock::new(2.0, Integrator::Euler);
+    }
+
+    #[test]
+    fn test_decay() {
+        let tau = 10.0;
+
+        assert_eq!(Clock::new(1.0, Integrator::Euler).decay(tau), 1.0 - 1.0 / tau);
+        assert!((Clock::new(1.0, Integrator::ExponentialEuler).decay(tau) - (-1.0 / tau).exp()).abs() < 1e-6);
+    }
+}
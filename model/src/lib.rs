@@ -3,6 +3,11 @@ pub mod neuron;
 pub mod synapse;
 pub mod spikes;
 pub mod record;
+pub mod clock;
+pub mod plasticity;
+pub mod noise;
+pub mod readout;
+pub mod parallel;
 
 use network::SpikingNetwork;
 use network::representation::{NetworkRepresentation, NeuronDescription};
@@ -11,6 +16,7 @@ use neuron::izhikevich::Izhikevich;
 
 use synapse::exponential::ExponentialSynapse;
 
+use utils::random;
 use utils::config::Configurable;
 use utils::parameters::ParameterSet;
 use utils::environment::Environment;
@@ -26,5 +32,11 @@ pub trait Model<N=DefaultNeuron>: Configurable + Sync {
 
     fn develop(&self) -> NetworkRepresentation<N>;
 
+    /// Develops the network given by seed, such that the same seed gives the same network.
+    /// The random stream of the current thread is left as it was.
+    fn develop_with_seed(&self, seed: u64) -> NetworkRepresentation<N> {
+        random::with_seed(seed, || self.develop())
+    }
+
     fn params(config: &Self::Config, env: &Environment) -> ParameterSet;
 }
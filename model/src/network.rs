@@ -1,16 +1,23 @@
 pub mod representation;
 pub mod builder;
+pub mod populations;
+pub mod error;
+pub mod weights;
 
 use crate::neuron::NeuronModel;
 use crate::spikes::Spikes;
 use crate::synapse::Synapse;
-use crate::record::{Record, RecordType};
+use crate::record::{Record, RecordType, RecordConfig, RecordSink};
+use crate::clock::Clock;
+use crate::plasticity::LearningRule;
+use crate::noise::{Noise, CurrentNoise, PoissonInput};
+use crate::network::populations::Populations;
 
 use utils::environment::Environment;
 
-use ndarray::{s, Array1};
+use ndarray::{Array, Array1, Axis};
 
-use std::ops::AddAssign;
+use serde::{Serialize, Deserialize};
 
 
 /// The effect of a spike is multiplied by this; determines the impact of a single spike.
@@ -20,6 +27,9 @@ pub trait Network {
     fn step(&mut self, input: Spikes) -> Spikes;
 
     fn reset_state(&mut self);
+
+    /// Delivers a reward signal to the network, used by reward-modulated plasticity
+    fn reward(&mut self, _r: f32) { }
 }
 
 /// A runnable spiking network, defined by a NeuronModel and a Synapse
@@ -30,12 +40,38 @@ pub struct SpikingNetwork<N: NeuronModel, S: Synapse> {
 
     pub input_synapse: S,
 
+    /// Neurons receiving input and read out as output
+    pub populations: Populations,
+
+    /// Neurons of all readouts, in the order of the output
+    outputs: Vec<usize>,
+
     pub record: Record,
     pub recording: bool,
 
+    /// Selects what is recorded
+    pub record_config: RecordConfig,
+
+    /// Receives the records instead of record, if set
+    sink: Option<Box<dyn RecordSink>>,
+
     pub synaptic_coefficient: f32,
 
+    /// Learning rule applied to the weights of the synapse on each step, if any
+    pub plasticity: Option<Box<dyn LearningRule>>,
+
+    /// Noise currents added to the input of each neuron
+    pub noise: Vec<CurrentNoise>,
+
+    /// Poisson spike trains, each delivered through its own synapse
+    pub background: Vec<(PoissonInput, S)>,
+
+    clock: Clock,
+
     network_state: Spikes,
+
+    /// Number of steps since the last reset
+    t: usize,
 }
 
 impl<N: NeuronModel, S: Synapse> Network for SpikingNetwork<N, S> {
@@ -43,31 +79,42 @@ impl<N: NeuronModel, S: Synapse> Network for SpikingNetwork<N, S> {
     fn step(&mut self, input: Spikes) -> Spikes {
         assert!(input.len() == self.env.inputs);
 
-        let external_input = self.input_synapse.step(&input);
+        let v = self.neurons.potentials();
 
-        let mut synaptic_input = self.synapse.step(&self.network_state);
+        let external_input = self.input_synapse.step(&input, &v.select(Axis(0), &self.populations.input));
 
-        let n_in = external_input.shape()[0];
+        let mut synaptic_input = self.synapse.step(&self.network_state, &v);
+
+        for (i, x) in self.populations.input.iter().zip(&external_input) {
+            synaptic_input[*i] += x;
+        }
+
+        for (source, synapse) in &mut self.background {
+            synaptic_input += &synapse.step(&source.step(), &v);
+        }
 
-        synaptic_input.slice_mut(s![..n_in]).add_assign(&external_input);
         synaptic_input = synaptic_input * self.synaptic_coefficient;
 
+        for source in &mut self.noise {
+            synaptic_input += &source.step();
+        }
+
         log::trace!("external:{external_input}, synaptic_input: {synaptic_input}, state: {}", self.network_state);
 
-        self.network_state = self.neurons.step(synaptic_input.clone());
+        let pre = std::mem::replace(&mut self.network_state, self.neurons.step(synaptic_input.clone()));
 
-        let state: Array1<u32> = (&self.network_state).into();
+        if let (Some(rule), Some(weights)) = (&mut self.plasticity, self.synapse.weights_mut()) {
+            rule.step(&pre, &self.network_state, weights);
+        }
 
-        let output: Spikes = state.slice(s![-(self.env.outputs as i32)..]).to_owned().into();
+        let output = self.select(&self.outputs);
 
-        if self.recording {
-            self.record.log(RecordType::Potentials, self.neurons.potentials());
-            self.record.log(RecordType::Spikes, (&self.network_state).into());
-            self.record.log(RecordType::InputSpikes, (&input).into());
-            self.record.log(RecordType::OutputSpikes, (&output).into());
-            self.record.log(RecordType::SynapticCurrent, synaptic_input);
+        if self.recording && self.record_config.is_recorded_step(self.t) {
+            self.log(&input, &output, synaptic_input);
         }
 
+        self.t += 1;
+
         output
     }
 
@@ -75,17 +122,50 @@ impl<N: NeuronModel, S: Synapse> Network for SpikingNetwork<N, S> {
         self.neurons.reset();
         self.synapse.reset();
 
+        for source in &mut self.noise {
+            source.reset();
+        }
+
+        for (source, synapse) in &mut self.background {
+            source.reset();
+            synapse.reset();
+        }
+
+        if let Some(rule) = &mut self.plasticity {
+            rule.reset();
+        }
+
         self.network_state = Spikes::new(self.neurons.len());
+        self.t = 0;
 
         self.record = Record::new();
     }
+
+    fn reward(&mut self, r: f32) {
+        if let Some(rule) = &mut self.plasticity {
+            rule.reward(r);
+        }
+    }
 }
 
 impl<N: NeuronModel, S: Synapse> SpikingNetwork<N, S> {
     pub fn new(neurons: N, synapse: S, input_synapse: S, env: Environment) -> SpikingNetwork<N, S> {
+        let populations = Populations::default_layout(neurons.len(), &env);
+
+        SpikingNetwork::new_with_populations(neurons, synapse, input_synapse, env, populations)
+    }
+
+    /// Creates a network where input reaches populations.input, and the output is made up
+    /// of the spikes of the readouts
+    pub fn new_with_populations(neurons: N, synapse: S, input_synapse: S, env: Environment,
+        populations: Populations) -> SpikingNetwork<N, S> {
         let network_size = neurons.len();
 
-        assert!(input_synapse.shape() == [network_size - env.outputs, env.inputs].into(),
+        if let Err(e) = populations.validate(network_size, &env) {
+            panic!("{e}");
+        }
+
+        assert!(input_synapse.shape() == [populations.input.len(), env.inputs].into(),
         "Input matrix has wrong shape");
 
         SpikingNetwork {
@@ -95,16 +175,206 @@ impl<N: NeuronModel, S: Synapse> SpikingNetwork<N, S> {
 
             input_synapse,
 
+            outputs: populations.outputs(),
+            populations,
+
             network_state: Spikes::new(network_size),
 
             synaptic_coefficient: DEFAULT_SYNAPTIC_COEFFICIENT,
 
+            plasticity: None,
+
+            noise: Vec::new(),
+            background: Vec::new(),
+
+            clock: Clock::default(),
+
             record: Record::new(),
-            recording: false
+            recording: false,
+            record_config: RecordConfig::default(),
+            sink: None,
+
+            t: 0,
+        }
+    }
+
+    /// Spikes of the previous step of the named readout
+    pub fn readout(&self, name: &str) -> Option<Spikes> {
+        self.populations.readout(name).map(|r| self.select(&r.neurons))
+    }
+
+    fn select(&self, neurons: &[usize]) -> Spikes {
+        Spikes {
+            data: self.network_state.data.select(Axis(0), neurons)
         }
     }
 
     pub fn enable_recording(&mut self) {
         self.recording = true;
     }
+
+    /// Passes records to the sink as they are produced, rather than keeping them in memory
+    pub fn record_to(&mut self, sink: Box<dyn RecordSink>) {
+        self.sink = Some(sink);
+        self.recording = true;
+    }
+
+    /// Writes any records buffered by the sink
+    pub fn flush_record(&mut self) {
+        if let Some(sink) = &mut self.sink {
+            sink.flush().unwrap_or_else(|e| panic!("Failed to flush record: {e}"));
+        }
+    }
+
+    fn log(&mut self, input: &Spikes, output: &Spikes, synaptic_input: Array1<f32>) {
+        for record_type in self.record_config.types.clone() {
+            let data = match record_type {
+                RecordType::Potentials => self.neurons.potentials(),
+                RecordType::Spikes => (&self.network_state).into(),
+                RecordType::InputSpikes => input.into(),
+                RecordType::OutputSpikes => output.into(),
+                RecordType::SynapticCurrent => synaptic_input.clone(),
+            };
+
+            let data = self.record_config.select(record_type, data);
+
+            match &mut self.sink {
+                Some(sink) => sink.write(self.t, record_type, &data)
+                    .unwrap_or_else(|e| panic!("Failed to write record: {e}")),
+                None => self.record.log(record_type, data),
+            }
+        }
+    }
+
+    /// Sets the step size and integrator of the neurons and synapses
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.neurons.set_clock(clock);
+        self.synapse.set_clock(clock);
+        self.input_synapse.set_clock(clock);
+
+        if let Some(rule) = &mut self.plasticity {
+            rule.set_clock(clock);
+        }
+
+        for source in &mut self.noise {
+            source.set_clock(clock);
+        }
+
+        for (source, synapse) in &mut self.background {
+            source.set_clock(clock);
+            synapse.set_clock(clock);
+        }
+
+        self.clock = clock;
+    }
+
+    /// Adds a source of noise to every neuron, seeded from utils::random
+    pub fn add_noise(&mut self, noise: Noise) {
+        let n = self.neurons.len();
+
+        match noise {
+            Noise::Poisson { rate, weight } => {
+                let mut source = PoissonInput::new(rate, n);
+                let mut synapse = S::new(Array::from_diag(&Array::from_elem(n, weight)), Array::ones(n));
+
+                source.set_clock(self.clock);
+                synapse.set_clock(self.clock);
+
+                self.background.push((source, synapse));
+            },
+            noise => {
+                let mut source = CurrentNoise::new(noise, n);
+                source.set_clock(self.clock);
+
+                self.noise.push(source);
+            }
+        }
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.clock
+    }
+
+    /// Captures the state of the neurons and synapses, so that the network can later be returned to it
+    pub fn snapshot(&self) -> NetworkSnapshot<N, S> {
+        NetworkSnapshot {
+            neurons: self.neurons.snapshot(),
+            synapse: self.synapse.snapshot(),
+            input_synapse: self.input_synapse.snapshot(),
+
+            noise: self.noise.iter().map(|source| source.current().clone()).collect(),
+            background: self.background.iter().map(|(_, synapse)| synapse.snapshot()).collect(),
+
+            network_state: self.network_state.clone(),
+            t: self.t,
+        }
+    }
+
+    /// Returns the network to a snapshot taken from a network with the same structure and clock.
+    ///
+    /// Learning rules are reset, as their traces are not part of the snapshot. Noise continues
+    /// from its current stream, so that trials branched from the same snapshot differ in their noise.
+    pub fn restore(&mut self, snapshot: &NetworkSnapshot<N, S>) {
+        assert!(snapshot.network_state.len() == self.neurons.len(),
+            "snapshot has {} neurons, expected {}", snapshot.network_state.len(), self.neurons.len());
+
+        self.neurons.restore(&snapshot.neurons);
+        self.synapse.restore(&snapshot.synapse);
+        self.input_synapse.restore(&snapshot.input_synapse);
+
+        assert!(snapshot.noise.len() == self.noise.len() && snapshot.background.len() == self.background.len(),
+            "snapshot has different noise sources");
+
+        for (source, i) in self.noise.iter_mut().zip(&snapshot.noise) {
+            source.set_current(i);
+        }
+
+        for ((_, synapse), state) in self.background.iter_mut().zip(&snapshot.background) {
+            synapse.restore(state);
+        }
+
+        if let Some(rule) = &mut self.plasticity {
+            rule.reset();
+        }
+
+        self.network_state = snapshot.network_state.clone();
+        self.t = snapshot.t;
+
+        self.record = Record::new();
+    }
+}
+
+/// The state of a SpikingNetwork at some point during a run
+#[derive(Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct NetworkSnapshot<N: NeuronModel, S: Synapse> {
+    pub neurons: N::State,
+    pub synapse: S::State,
+    pub input_synapse: S::State,
+
+    pub noise: Vec<Array1<f32>>,
+    pub background: Vec<S::State>,
+
+    /// Spikes of the previous step, which are input to the next
+    pub network_state: Spikes,
+
+    /// Number of steps taken by the network, which decides the recorded steps
+    #[serde(default)]
+    pub t: usize,
+}
+
+impl<N: NeuronModel, S: Synapse> Clone for NetworkSnapshot<N, S> {
+    fn clone(&self) -> Self {
+        NetworkSnapshot {
+            neurons: self.neurons.clone(),
+            synapse: self.synapse.clone(),
+            input_synapse: self.input_synapse.clone(),
+
+            noise: self.noise.clone(),
+            background: self.background.clone(),
+
+            network_state: self.network_state.clone(),
+            t: self.t,
+        }
+    }
 }
@@ -4,10 +4,11 @@
 
 use crate::network::SpikingNetwork;
 use crate::network::representation::{NetworkRepresentation, NeuronDescription};
+use crate::network::error::InvalidRepresentation;
 use crate::neuron::NeuronModel;
 use crate::synapse::{Synapse, NeuronType};
 
-use ndarray::{s, Array};
+use ndarray::Array;
 
 
 pub struct NetworkBuilder;
@@ -15,22 +16,50 @@ pub struct NetworkBuilder;
 impl NetworkBuilder {
     pub fn build<N: NeuronModel, S: Synapse>(desc: &NetworkRepresentation<NeuronDescription<N>>)
         -> SpikingNetwork<N, S> {
+        Self::try_build(desc).unwrap_or_else(|e| panic!("Building network from {e}"))
+    }
+
+    /// Builds the network, if the representation is valid
+    pub fn try_build<N: NeuronModel, S: Synapse>(desc: &NetworkRepresentation<NeuronDescription<N>>)
+        -> Result<SpikingNetwork<N, S>, InvalidRepresentation> {
+        desc.validate()?;
+
         let neuron_params = Self::parse_neuron_params(desc);
         let neuron_types = Self::parse_neuron_types(desc);
 
-        // ensure output neurons are excitatory
-        assert!(neuron_types.slice(s![-(desc.env.outputs as i32)..]).iter().all(|x| *x == 1.0),
-        "Building network with inhibitory output neurons");
+        let populations = desc.populations();
 
         let model = N::new(desc.n, neuron_params);
 
         let synapse_matrix = &(desc.network_cm.mapv(|v| v as f32)) * &desc.network_w;
-        let synapse = S::new(synapse_matrix, neuron_types);
+        let mut synapse = S::new(synapse_matrix, neuron_types);
+
+        if let Some(delays) = &desc.network_delay {
+            synapse.set_delays(delays);
+        }
+
+        if let Some(stp) = &desc.network_stp {
+            synapse.set_short_term_plasticity(stp);
+        }
 
         let input_matrix = &(desc.input_cm.mapv(|v| v as f32)) * &desc.input_w;
         let input_synapse = S::new(input_matrix, Array::ones(desc.env.inputs));
 
-        SpikingNetwork::new(model, synapse, input_synapse, desc.env.clone())
+        let mut network = SpikingNetwork::new_with_populations(model, synapse, input_synapse, desc.env.clone(), populations);
+
+        if let Some(plasticity) = &desc.plasticity {
+            if network.synapse.weights_mut().is_some() {
+                network.plasticity = Some(plasticity.build());
+            } else {
+                log::warn!("Synapse does not support plasticity, ignoring {plasticity:?}");
+            }
+        }
+
+        for noise in &desc.noise {
+            network.add_noise(*noise);
+        }
+
+        Ok(network)
     }
 
     fn parse_neuron_types<N: NeuronModel> (desc: &NetworkRepresentation<NeuronDescription<N>>) -> NeuronType {
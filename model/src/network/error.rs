@@ -0,0 +1,115 @@
+//! Errors describing why a network representation is not valid, or could not be loaded.
+
+use std::fmt;
+use std::io;
+
+
+/// A single violation of the structure of a NetworkRepresentation
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepresentationError {
+    /// n differs from the number of neurons
+    NeuronCount { n: usize, neurons: usize },
+
+    /// A matrix has the wrong shape
+    Shape { field: &'static str, expected: Vec<usize>, found: Vec<usize> },
+
+    /// A weight matrix contains negative entries
+    NegativeWeights { field: &'static str, count: usize },
+
+    /// A population contains a neuron that is not in the network
+    NeuronOutOfRange { field: String, neuron: usize, n: usize },
+
+    /// A neuron is listed more than once in the input population
+    DuplicateInput { neuron: usize },
+
+    /// Two readouts have the same name
+    DuplicateReadout { name: String },
+
+    /// The readouts have a different number of neurons than the environment has outputs
+    OutputCount { expected: usize, found: usize },
+
+    /// A readout neuron is inhibitory, without inhibitory readouts being allowed
+    InhibitoryOutput { neuron: usize },
+}
+
+impl fmt::Display for RepresentationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepresentationError::NeuronCount { n, neurons } =>
+                write!(f, "n: {n} != # neurons ({neurons})"),
+            RepresentationError::Shape { field, expected, found } =>
+                write!(f, "{field}: expected shape {expected:?}, found {found:?}"),
+            RepresentationError::NegativeWeights { field, count } =>
+                write!(f, "{field}: contains {count} negative entries"),
+            RepresentationError::NeuronOutOfRange { field, neuron, n } =>
+                write!(f, "{field}: neuron {neuron} is outside of network of size {n}"),
+            RepresentationError::DuplicateInput { neuron } =>
+                write!(f, "populations.input: neuron {neuron} is listed more than once"),
+            RepresentationError::DuplicateReadout { name } =>
+                write!(f, "populations.readouts: more than one readout is named {name}"),
+            RepresentationError::OutputCount { expected, found } =>
+                write!(f, "populations.readouts: have {found} neurons, expected {expected} outputs"),
+            RepresentationError::InhibitoryOutput { neuron } =>
+                write!(f, "neurons: output neuron {neuron} is inhibitory"),
+        }
+    }
+}
+
+/// All violations found when validating a representation
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidRepresentation {
+    pub errors: Vec<RepresentationError>,
+}
+
+impl InvalidRepresentation {
+    /// Ok if there are no errors
+    pub fn check(errors: Vec<RepresentationError>) -> Result<(), InvalidRepresentation> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidRepresentation { errors })
+        }
+    }
+}
+
+impl fmt::Display for InvalidRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid network representation")?;
+
+        for e in &self.errors {
+            write!(f, "\n  {e}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidRepresentation {}
+
+/// Failure to load a representation from a file
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read, or is not a representation
+    Read { path: String, error: io::Error },
+
+    /// The file holds a representation that is not valid
+    Invalid { path: String, error: InvalidRepresentation },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read { path, error } => write!(f, "{path}: {error}"),
+            LoadError::Invalid { path, error } => write!(f, "{path}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Read { error, .. } => Some(error),
+            LoadError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Named groups of neurons, defining where input arrives and where output is read out.
+//!
+//! By default, the input reaches the first n - outputs neurons, and the output is read
+//! from the last outputs neurons. Populations allow any subset of the neurons to be used
+//! for either, and the output to be split into several named readouts.
+
+use crate::network::error::{RepresentationError, InvalidRepresentation};
+
+use utils::environment::Environment;
+
+use serde::{Serialize, Deserialize};
+
+use std::collections::HashSet;
+
+
+/// Name of the readout of the default layout
+pub const DEFAULT_READOUT: &str = "output";
+
+/// A named subset of the neurons of a network
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Population {
+    pub name: String,
+    pub neurons: Vec<usize>,
+}
+
+impl Population {
+    pub fn new(name: &str, neurons: Vec<usize>) -> Population {
+        Population {
+            name: name.to_string(),
+            neurons,
+        }
+    }
+}
+
+/// Input and readout populations of a network. Neurons in neither are hidden.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Populations {
+    /// Neurons receiving input, where row i of the input matrix targets neuron input[i]
+    pub input: Vec<usize>,
+
+    /// Populations whose spikes make up the output of the network, in order
+    pub readouts: Vec<Population>,
+
+    /// Whether readout neurons may be inhibitory
+    #[serde(default)]
+    pub inhibitory_readouts: bool,
+}
+
+impl Populations {
+    pub fn new(input: Vec<usize>, readouts: Vec<Population>) -> Populations {
+        Populations {
+            input,
+            readouts,
+            inhibitory_readouts: false,
+        }
+    }
+
+    /// The layout used when no populations are given: the first n - outputs neurons
+    /// receive input, and the last outputs neurons form a single readout.
+    pub fn default_layout(n: usize, env: &Environment) -> Populations {
+        let n_rec = n.saturating_sub(env.outputs);
+
+        Populations::new((0..n_rec).collect(), vec![Population::new(DEFAULT_READOUT, (n_rec..n).collect())])
+    }
+
+    pub fn allow_inhibitory_readouts(mut self) -> Populations {
+        self.inhibitory_readouts = true;
+        self
+    }
+
+    /// Neurons of all readouts, in the order of the output
+    pub fn outputs(&self) -> Vec<usize> {
+        self.readouts.iter().flat_map(|r| r.neurons.iter().copied()).collect()
+    }
+
+    /// Neurons that neither receive input nor are read out, for a network of n neurons
+    pub fn hidden(&self, n: usize) -> Vec<usize> {
+        let visible: HashSet<usize> = self.input.iter().chain(&self.outputs()).copied().collect();
+
+        (0..n).filter(|i| !visible.contains(i)).collect()
+    }
+
+    pub fn readout(&self, name: &str) -> Option<&Population> {
+        self.readouts.iter().find(|r| r.name == name)
+    }
+
+    /// Ensures that the populations are consistent with a network of n neurons in env
+    pub fn validate(&self, n: usize, env: &Environment) -> Result<(), InvalidRepresentation> {
+        InvalidRepresentation::check(self.errors(n, env))
+    }
+
+    /// All inconsistencies with a network of n neurons in env
+    pub fn errors(&self, n: usize, env: &Environment) -> Vec<RepresentationError> {
+        let mut errors = Vec::new();
+
+        let populations = std::iter::once(("populations.input".to_string(), &self.input))
+            .chain(self.readouts.iter().map(|r| (format!("populations.readouts.{}", r.name), &r.neurons)));
+
+        for (field, neurons) in populations {
+            if let Some(neuron) = neurons.iter().find(|i| **i >= n) {
+                errors.push(RepresentationError::NeuronOutOfRange { field, neuron: *neuron, n });
+            }
+        }
+
+        let mut input = HashSet::new();
+        for i in &self.input {
+            if !input.insert(i) {
+                errors.push(RepresentationError::DuplicateInput { neuron: *i });
+            }
+        }
+
+        let mut names = HashSet::new();
+        for r in &self.readouts {
+            if !names.insert(&r.name) {
+                errors.push(RepresentationError::DuplicateReadout { name: r.name.clone() });
+            }
+        }
+
+        if self.outputs().len() != env.outputs {
+            errors.push(RepresentationError::OutputCount { expected: env.outputs, found: self.outputs().len() });
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_layout() {
+        let env = Environment { inputs: 2, outputs: 3 };
+        let populations = Populations::default_layout(10, &env);
+
+        assert!(populations.validate(10, &env).is_ok());
+
+        assert_eq!(populations.input, (0..7).collect::<Vec<_>>());
+        assert_eq!(populations.outputs(), vec![7, 8, 9]);
+        assert!(populations.hidden(10).is_empty());
+    }
+
+    #[test]
+    fn test_multiple_readouts() {
+        let env = Environment { inputs: 2, outputs: 4 };
+        let populations = Populations::new(vec![0, 1], vec![
+            Population::new("left", vec![5, 2]),
+            Population::new("right", vec![9, 3]),
+        ]);
+
+        assert!(populations.validate(10, &env).is_ok());
+
+        assert_eq!(populations.outputs(), vec![5, 2, 9, 3]);
+        assert_eq!(populations.hidden(10), vec![4, 6, 7, 8]);
+        assert_eq!(populations.readout("right").unwrap().neurons, vec![9, 3]);
+    }
+
+    #[test]
+    fn test_invalid_populations() {
+        let env = Environment { inputs: 2, outputs: 3 };
+
+        let populations = Populations::new(vec![0, 0], vec![
+            Population::new("output", vec![1, 7]),
+            Population::new("output", vec![]),
+        ]);
+
+        assert_eq!(populations.errors(5, &env), vec![
+            RepresentationError::NeuronOutOfRange { field: "populations.readouts.output".to_string(), neuron: 7, n: 5 },
+            RepresentationError::DuplicateInput { neuron: 0 },
+            RepresentationError::DuplicateReadout { name: "output".to_string() },
+            RepresentationError::OutputCount { expected: 3, found: 2 },
+        ]);
+    }
+}
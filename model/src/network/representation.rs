@@ -2,15 +2,28 @@
 
 use crate::neuron::NeuronModel;
 use crate::neuron::izhikevich::Izhikevich;
+use crate::plasticity::Plasticity;
+use crate::synapse::tsodyks_markram::ShortTermPlasticity;
+use crate::noise::Noise;
+use crate::network::populations::Populations;
+use crate::network::weights::WeightRule;
+use crate::network::error::{RepresentationError, InvalidRepresentation, LoadError};
 
 use utils::environment::Environment;
+use utils::npy::NpzWriter;
+use utils::data;
 
-use ndarray::{Array1, Array2};
+use ndarray::{array, Array1, Array2};
 
 use serde::{Serialize, Deserialize};
 
+use std::io;
 
-pub type DefaultRepresentation = NetworkRepresentation<NeuronDescription<Izhikevich>>;
+
+pub type DefaultRepresentation = Representation<Izhikevich>;
+
+/// Representation of a network of neurons of type N
+pub type Representation<N> = NetworkRepresentation<NeuronDescription<N>>;
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct NetworkRepresentation<N> {
@@ -20,9 +33,35 @@ pub struct NetworkRepresentation<N> {
     pub network_cm: Array2<u32>,
     pub network_w: Array2<f32>,
 
+    /// Transmission delay (ms) of each connection, where entry D_jk is the delay from neuron k to neuron j.
+    /// If None, spikes arrive on the next step.
+    #[serde(default)]
+    pub network_delay: Option<Array2<u32>>,
+
+    /// Short-term plasticity of each connection, used by synapses that support it
+    #[serde(default)]
+    pub network_stp: Option<ShortTermPlasticity>,
+
+    /// Learning rule applied to the network connections as the network runs
+    #[serde(default)]
+    pub plasticity: Option<Plasticity>,
+
+    /// Sources of background activity
+    #[serde(default)]
+    pub noise: Vec<Noise>,
+
+    /// Constraints applied to the network weights by with_weight_rule, in order
+    #[serde(default)]
+    pub weight_rules: Vec<WeightRule>,
+
     pub input_cm: Array2<u32>,
     pub input_w: Array2<f32>,
 
+    /// Neurons receiving input and read out as output. If None, the input reaches the first
+    /// n - outputs neurons and the output is read from the last outputs neurons.
+    #[serde(default)]
+    pub populations: Option<Populations>,
+
     pub env: Environment,
 }
 
@@ -38,38 +77,173 @@ impl<N> NetworkRepresentation<N> {
         env: Environment)
         -> NetworkRepresentation<N>
     {
-        // Ensure weights are non-negative
-        assert!(network_w.iter().filter(|x| **x < 0.0).collect::<Vec<&f32>>().len() == 0,
-            "network weight matrix contains negative entries");
-        assert!(input_w.iter().filter(|x| **x < 0.0).collect::<Vec<&f32>>().len() == 0,
-            "input weight matrix contains negative entries");
+        Self::try_new(neurons, network_cm, network_w, input_cm, input_w, env)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        let n = neurons.shape()[0];
+    /// Creates a representation, or describes every way in which the parts are inconsistent
+    pub fn try_new(
+        neurons: Array1<N>,
+        network_cm: Array2<u32>,
+        network_w: Array2<f32>,
 
-        // |n| == |cm|
-        assert!(network_cm.shape() == [n,n],
-            "# neurons ({:?}) != network connection matrix: ({:?})", neurons.shape()[0], network_cm.shape());
+        input_cm: Array2<u32>,
+        input_w: Array2<f32>,
 
-        let n_rec = n - env.outputs;
+        env: Environment)
+        -> Result<NetworkRepresentation<N>, InvalidRepresentation>
+    {
+        Self::create(neurons, network_cm, network_w, input_cm, input_w, env, None)
+    }
 
-        // |input_cm| == [n_rec x n_in]
-        assert!(input_cm.shape() == [n_rec, env.inputs]);
+    /// Creates a representation where the neurons receiving input and the readouts are given
+    /// by populations, rather than by the position of the neurons.
+    pub fn new_with_populations(
+        neurons: Array1<N>,
+        network_cm: Array2<u32>,
+        network_w: Array2<f32>,
 
-        // |cm| == |w|
-        assert!(network_cm.shape() == network_w.shape());
-        assert!(input_cm.shape() == input_w.shape());
+        input_cm: Array2<u32>,
+        input_w: Array2<f32>,
+
+        env: Environment,
+        populations: Populations)
+        -> NetworkRepresentation<N>
+    {
+        Self::try_new_with_populations(neurons, network_cm, network_w, input_cm, input_w, env, populations)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        NetworkRepresentation {
-            n,
+    pub fn try_new_with_populations(
+        neurons: Array1<N>,
+        network_cm: Array2<u32>,
+        network_w: Array2<f32>,
+
+        input_cm: Array2<u32>,
+        input_w: Array2<f32>,
+
+        env: Environment,
+        populations: Populations)
+        -> Result<NetworkRepresentation<N>, InvalidRepresentation>
+    {
+        Self::create(neurons, network_cm, network_w, input_cm, input_w, env, Some(populations))
+    }
+
+    fn create(
+        neurons: Array1<N>,
+        network_cm: Array2<u32>,
+        network_w: Array2<f32>,
+
+        input_cm: Array2<u32>,
+        input_w: Array2<f32>,
+
+        env: Environment,
+        populations: Option<Populations>)
+        -> Result<NetworkRepresentation<N>, InvalidRepresentation>
+    {
+        let representation = NetworkRepresentation {
+            n: neurons.len(),
             neurons,
             network_cm,
             network_w,
+            network_delay: None,
+            network_stp: None,
+            plasticity: None,
+            noise: Vec::new(),
+            weight_rules: Vec::new(),
 
             input_cm,
             input_w,
 
+            populations,
+
             env,
+        };
+
+        InvalidRepresentation::check(representation.structure_errors())?;
+
+        Ok(representation)
+    }
+
+    /// Violations of the shapes of the matrices, the weights and the populations
+    fn structure_errors(&self) -> Vec<RepresentationError> {
+        let mut errors = Vec::new();
+
+        let n = self.neurons.len();
+
+        if self.n != n {
+            errors.push(RepresentationError::NeuronCount { n: self.n, neurons: n });
+        }
+
+        let layout = self.populations.clone().unwrap_or_else(|| Populations::default_layout(n, &self.env));
+        let n_in = layout.input.len();
+
+        let mut shape = |field, found: &[usize], expected: [usize; 2]| {
+            if found != expected {
+                errors.push(RepresentationError::Shape { field, expected: expected.to_vec(), found: found.to_vec() });
+            }
+        };
+
+        shape("network_cm", self.network_cm.shape(), [n, n]);
+        shape("network_w", self.network_w.shape(), [n, n]);
+
+        if let Some(delay) = &self.network_delay {
+            shape("network_delay", delay.shape(), [n, n]);
+        }
+
+        if let Some(stp) = &self.network_stp {
+            shape("network_stp.u", stp.u.shape(), [n, n]);
+            shape("network_stp.tau_rec", stp.tau_rec.shape(), [n, n]);
+            shape("network_stp.tau_facil", stp.tau_facil.shape(), [n, n]);
         }
+
+        // |input_cm| == [|input| x n_in]
+        shape("input_cm", self.input_cm.shape(), [n_in, self.env.inputs]);
+        shape("input_w", self.input_w.shape(), [n_in, self.env.inputs]);
+
+        // Ensure weights are non-negative
+        for (field, w) in [("network_w", &self.network_w), ("input_w", &self.input_w)] {
+            let count = w.iter().filter(|x| **x < 0.0).count();
+
+            if count > 0 {
+                errors.push(RepresentationError::NegativeWeights { field, count });
+            }
+        }
+
+        errors.extend(layout.errors(n, &self.env));
+
+        errors
+    }
+
+    pub fn with_delays(mut self, network_delay: Array2<u32>) -> NetworkRepresentation<N> {
+        assert!(network_delay.shape() == [self.n, self.n],
+            "# neurons ({:?}) != network delay matrix: ({:?})", self.n, network_delay.shape());
+
+        self.network_delay = Some(network_delay);
+        self
+    }
+
+    pub fn with_short_term_plasticity(mut self, stp: ShortTermPlasticity) -> NetworkRepresentation<N> {
+        assert!(stp.u.shape() == [self.n, self.n],
+            "# neurons ({:?}) != short-term plasticity matrix: ({:?})", self.n, stp.u.shape());
+
+        self.network_stp = Some(stp);
+        self
+    }
+
+    pub fn with_plasticity(mut self, plasticity: Plasticity) -> NetworkRepresentation<N> {
+        self.plasticity = Some(plasticity);
+        self
+    }
+
+    pub fn with_noise(mut self, noise: Noise) -> NetworkRepresentation<N> {
+        self.noise.push(noise);
+        self
+    }
+
+    /// The populations of the network, or the default layout if none are given
+    pub fn populations(&self) -> Populations {
+        self.populations.clone().unwrap_or_else(|| Populations::default_layout(self.n, &self.env))
     }
 
     pub fn edges(&self) -> Vec<(u32, u32)> {
@@ -88,6 +262,76 @@ impl<N> NetworkRepresentation<N> {
     }
 }
 
+impl<N: NeuronModel> Representation<N> {
+    /// Applies the rule to the weights of the network connections
+    pub fn with_weight_rule(mut self, rule: WeightRule) -> Representation<N> {
+        let neuron_types = self.neurons.mapv(|n| if n.inhibitory { -1.0 } else { 1.0 });
+
+        self.network_w = &(self.network_cm.mapv(|v| v as f32)) * &self.network_w;
+        rule.apply(&mut self.network_w, &neuron_types);
+
+        self.weight_rules.push(rule);
+        self
+    }
+
+    /// Describes every violation of the representation, which must be valid to be built
+    pub fn validate(&self) -> Result<(), InvalidRepresentation> {
+        let mut errors = self.structure_errors();
+
+        let populations = self.populations();
+
+        if !populations.inhibitory_readouts {
+            for i in populations.outputs() {
+                if self.neurons.get(i).is_some_and(|n| n.inhibitory) {
+                    errors.push(RepresentationError::InhibitoryOutput { neuron: i });
+                }
+            }
+        }
+
+        InvalidRepresentation::check(errors)
+    }
+
+    /// Loads a saved representation, ensuring that it is valid
+    pub fn load(path: &str) -> Result<Representation<N>, LoadError> {
+        let representation: Representation<N> = data::load(path)
+            .map_err(|error| LoadError::Read { path: path.to_string(), error })?;
+
+        representation.validate()
+            .map_err(|error| LoadError::Invalid { path: path.to_string(), error })?;
+
+        Ok(representation)
+    }
+
+    /// Writes the connectivity of the network as a .npz archive, with arrays named as the fields
+    /// of the representation. Neurons are given by their type in inhibitory, and env holds
+    /// the number of inputs and outputs. input_neurons and output_neurons give the neurons
+    /// receiving input and read out, in order.
+    pub fn save_npz(&self, path: &str) -> io::Result<()> {
+        let mut npz = NpzWriter::create(path)?;
+
+        npz.add_array("network_cm", &self.network_cm)?;
+        npz.add_array("network_w", &self.network_w)?;
+
+        if let Some(delay) = &self.network_delay {
+            npz.add_array("network_delay", delay)?;
+        }
+
+        npz.add_array("input_cm", &self.input_cm)?;
+        npz.add_array("input_w", &self.input_w)?;
+
+        npz.add_array("inhibitory", &self.neurons.mapv(|n| n.inhibitory))?;
+        let populations = self.populations();
+        npz.add_array("input_neurons", &populations.input.iter().map(|i| *i as u32).collect::<Array1<u32>>())?;
+        npz.add_array("output_neurons", &populations.outputs().iter().map(|i| *i as u32).collect::<Array1<u32>>())?;
+
+        npz.add_array("env", &array![self.env.inputs as u32, self.env.outputs as u32])?;
+
+        npz.finish()?;
+
+        Ok(())
+    }
+}
+
 #[derive(Copy, Debug, Deserialize, Serialize)]
 pub struct NeuronDescription<N: NeuronModel> {
     pub id: u32,
@@ -0,0 +1,107 @@
+//! Post-processing of the network weights, applied to a representation by with_weight_rule.
+//!
+//! Weights are non-negative magnitudes, signed by the type of the presynaptic neuron when the
+//! network runs, so that every neuron is either excitatory or inhibitory (Dale's law). The rules
+//! operate on these magnitudes, where w[j, k] is the weight of the connection from k to j.
+
+use crate::synapse::NeuronType;
+
+use ndarray::{Array2, Axis};
+
+use serde::{Serialize, Deserialize};
+
+
+/// A constraint on the network weights, applied after the network is developed
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum WeightRule {
+    /// Clips each weight to at most max
+    #[serde(rename = "clip")]
+    Clip { max: f32 },
+
+    /// Scales the incoming weights of each neuron to sum to total (synaptic scaling)
+    #[serde(rename = "normalize")]
+    Normalize { total: f32 },
+
+    /// Scales the incoming inhibitory weights of each neuron to sum to ratio times
+    /// its incoming excitatory weights
+    #[serde(rename = "balance")]
+    Balance { ratio: f32 },
+}
+
+impl WeightRule {
+    pub fn apply(&self, w: &mut Array2<f32>, neuron_type: &NeuronType) {
+        assert!(w.ncols() == neuron_type.len(),
+            "weights from {} neurons, expected {}", w.ncols(), neuron_type.len());
+
+        match *self {
+            WeightRule::Clip { max } => {
+                w.mapv_inplace(|x| x.min(max));
+            },
+            WeightRule::Normalize { total } => {
+                for mut row in w.axis_iter_mut(Axis(0)) {
+                    let sum = row.sum();
+
+                    if sum > 0.0 {
+                        row *= total / sum;
+                    }
+                }
+            },
+            WeightRule::Balance { ratio } => {
+                for mut row in w.axis_iter_mut(Axis(0)) {
+                    let (mut exc, mut inh) = (0.0, 0.0);
+
+                    for (x, t) in row.iter().zip(neuron_type) {
+                        if *t < 0.0 { inh += x } else { exc += x }
+                    }
+
+                    // Neurons without both types of input are left as they are
+                    if exc > 0.0 && inh > 0.0 {
+                        let scale = ratio * exc / inh;
+
+                        for (x, t) in row.iter_mut().zip(neuron_type) {
+                            if *t < 0.0 {
+                                *x *= scale;
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    #[test]
+    fn test_clip() {
+        let mut w = array![[0.5, 3.0], [2.0, 0.0]];
+
+        WeightRule::Clip { max: 1.0 }.apply(&mut w, &array![1.0, 1.0]);
+
+        assert_eq!(w, array![[0.5, 1.0], [1.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut w = array![[1.0, 3.0], [0.0, 0.0]];
+
+        WeightRule::Normalize { total: 2.0 }.apply(&mut w, &array![1.0, -1.0]);
+
+        // Neurons without input are unchanged
+        assert_eq!(w, array![[0.5, 1.5], [0.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_balance() {
+        let mut w = array![[1.0, 2.0, 1.0], [0.0, 2.0, 1.0], [1.0, 0.0, 4.0]];
+
+        WeightRule::Balance { ratio: 2.0 }.apply(&mut w, &array![1.0, 1.0, -1.0]);
+
+        assert_eq!(w, array![[1.0, 2.0, 6.0], [0.0, 2.0, 4.0], [1.0, 0.0, 2.0]]);
+    }
+}
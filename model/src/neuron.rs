@@ -1,7 +1,11 @@
 pub mod izhikevich;
 pub mod lapicque;
+pub mod lif;
+pub mod adex;
+pub mod mixed;
 
 use crate::spikes::Spikes;
+use crate::clock::Clock;
 
 use ndarray::Array1;
 
@@ -11,13 +15,22 @@ use serde::de::DeserializeOwned;
 
 /// Defines a model of a neuron
 pub trait NeuronModel {
-    type Parameters: Default + Copy + Clone + Serialize + DeserializeOwned;
+    type Parameters: Default + Copy + Clone + Serialize + DeserializeOwned + Send + Sync;
+
+    /// The dynamic state of the neurons, such as membrane potentials and recovery variables
+    type State: Clone + Serialize + DeserializeOwned;
 
     fn new(n: usize, params: Vec<Self::Parameters>) -> Self;
     fn step(&mut self, input: Array1<f32>) -> Spikes;
     fn reset(&mut self);
+
+    fn snapshot(&self) -> Self::State;
+    fn restore(&mut self, state: &Self::State);
     fn potentials(&self) -> Array1<f32>;
 
+    /// Sets the step size and integrator used by the model
+    fn set_clock(&mut self, clock: Clock);
+
     /// Returns an instance of the model with n neurons initialized with the default parameters
     fn n_default(n: usize) -> Self where Self: Sized {
         let default = Self::Parameters::default();
@@ -0,0 +1,323 @@
+//! Adaptive exponential integrate-and-fire neuron (Brette & Gerstner, 2005).
+
+use crate::spikes::Spikes;
+use crate::neuron::NeuronModel;
+use crate::clock::{Clock, Linearization};
+
+use ndarray::{Array, Array1};
+
+use serde::{Serialize, Deserialize};
+
+
+/// The exponential term is stiff, so steps longer than this (ms) are split into several
+const MAX_INTEGRATION_STEP: f32 = 0.1;
+
+/*
+ * The model is described by the equations
+ *
+ *      C dv/dt = -g_l(v - e_l) + g_l * delta_t * exp((v - v_t) / delta_t) - w + I
+ *      tau_w dw/dt = a(v - e_l) - w
+ *
+ * A neuron fires if v >= v_peak. Upon firing, the neuron is reset according to
+ *
+ *      v = v_r
+ *      w = w + b
+ *
+ * Units are pF, nS, mV, ms and pA, so that the input current is given in pA.
+ */
+pub struct AdEx {
+    v: Array1<f32>,
+    w: Array1<f32>,
+
+    c: Array1<f32>,
+    g_l: Array1<f32>,
+    e_l: Array1<f32>,
+    v_t: Array1<f32>,
+    delta_t: Array1<f32>,
+    a: Array1<f32>,
+    tau_w: Array1<f32>,
+    b: Array1<f32>,
+    v_r: Array1<f32>,
+    v_peak: Array1<f32>,
+
+    clock: Clock,
+}
+
+impl NeuronModel for AdEx {
+    type Parameters = AdExParameters;
+    type State = AdExState;
+
+    fn new(n: usize, params: Vec<AdExParameters>) -> AdEx {
+        assert!(params.len() == n, "expected {n} params, got {:?}", params.len());
+
+        let e_l: Array1<f32> = params.iter().map(|p| p.e_l).collect();
+
+        AdEx {
+            v: e_l.clone(),
+            w: Array::zeros(n),
+
+            c: params.iter().map(|p| p.c).collect(),
+            g_l: params.iter().map(|p| p.g_l).collect(),
+            e_l,
+            v_t: params.iter().map(|p| p.v_t).collect(),
+            delta_t: params.iter().map(|p| p.delta_t).collect(),
+            a: params.iter().map(|p| p.a).collect(),
+            tau_w: params.iter().map(|p| p.tau_w).collect(),
+            b: params.iter().map(|p| p.b).collect(),
+            v_r: params.iter().map(|p| p.v_r).collect(),
+            v_peak: params.iter().map(|p| p.v_peak).collect(),
+
+            clock: Clock::default(),
+        }
+    }
+
+    fn step(&mut self, input: Array1<f32>) -> Spikes {
+        assert!(input.shape()[0] == self.v.shape()[0]);
+
+        self.reset_spiking();
+
+        let n_steps = (self.clock.dt / MAX_INTEGRATION_STEP).ceil() as usize;
+        let dt = self.clock.dt / n_steps as f32;
+
+        for _ in 0..n_steps {
+            let mut x = self.clock.integrator.step(&[self.v.to_owned(), self.w.to_owned()], dt,
+                |x| self.linearize(x, &input));
+
+            let (v, w) = (x.remove(0), x.remove(0));
+
+            for i in 0..self.v.shape()[0] {
+                // Stop integrating once the spike is initiated; the
+                // neuron is reset at the beginning of the next step.
+                if self.v[i] < self.v_peak[i] {
+                    self.v[i] = v[i].min(self.v_peak[i]);
+                    self.w[i] = w[i];
+                }
+            }
+        }
+
+        Spikes {
+            data: self.v.iter().zip(&self.v_peak).map(|(v, peak)| v >= peak).collect()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.v = self.e_l.to_owned();
+        self.w = Array::zeros(self.v.shape()[0]);
+    }
+
+    fn snapshot(&self) -> AdExState {
+        AdExState {
+            v: self.v.clone(),
+            w: self.w.clone(),
+        }
+    }
+
+    fn restore(&mut self, state: &AdExState) {
+        assert!(state.v.len() == self.v.len(), "state has {} neurons, expected {}", state.v.len(), self.v.len());
+
+        self.v.assign(&state.v);
+        self.w.assign(&state.w);
+    }
+
+    fn potentials(&self) -> Array1<f32> {
+        self.v.to_owned()
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+}
+
+impl AdEx {
+    fn linearize(&self, x: &[Array1<f32>], input: &Array1<f32>) -> Linearization {
+        // Bound v by the spike cutoff, so that intermediate states cannot overflow the exponential
+        let v = ndarray::Zip::from(&x[0]).and(&self.v_peak).map_collect(|v, peak| v.min(*peak));
+        let w = &x[1];
+
+        let spike = &self.g_l * &self.delta_t * ((&v - &self.v_t) / &self.delta_t).mapv(f32::exp);
+
+        vec![
+            (-&self.g_l / &self.c, (&self.g_l * &self.e_l + spike - w + input) / &self.c),
+            (-1.0 / &self.tau_w, &self.a * (&v - &self.e_l) / &self.tau_w),
+        ]
+    }
+
+    fn reset_spiking(&mut self) {
+        for i in 0..self.v.shape()[0] {
+            if self.v[i] >= self.v_peak[i] {
+                self.v[i] = self.v_r[i];
+                self.w[i] += self.b[i];
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdExState {
+    pub v: Array1<f32>, // membrane potential (mV)
+    pub w: Array1<f32>, // adaptation current (pA)
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct AdExParameters {
+    pub c: f32,         // membrane capacitance (pF)
+    pub g_l: f32,       // leak conductance (nS)
+    pub e_l: f32,       // leak reversal potential (mV)
+    pub v_t: f32,       // threshold slope factor midpoint (mV)
+    pub delta_t: f32,   // slope factor (mV)
+    pub a: f32,         // subthreshold adaptation (nS)
+    pub tau_w: f32,     // adaptation time constant (ms)
+    pub b: f32,         // spike-triggered adaptation (pA)
+    pub v_r: f32,       // reset potential (mV)
+    pub v_peak: f32,    // spike cutoff (mV)
+}
+
+/// Firing patterns from Naud et al. (2008)
+impl AdExParameters {
+    pub const TONIC: AdExParameters = AdExParameters {
+        c: 200.0, g_l: 10.0, e_l: -70.0, v_t: -50.0, delta_t: 2.0,
+        a: 2.0, tau_w: 30.0, b: 0.0, v_r: -58.0, v_peak: 0.0,
+    };
+
+    pub const ADAPTING: AdExParameters = AdExParameters {
+        c: 200.0, g_l: 12.0, e_l: -70.0, v_t: -50.0, delta_t: 2.0,
+        a: 2.0, tau_w: 300.0, b: 60.0, v_r: -58.0, v_peak: 0.0,
+    };
+
+    pub const INITIAL_BURST: AdExParameters = AdExParameters {
+        c: 130.0, g_l: 18.0, e_l: -58.0, v_t: -50.0, delta_t: 2.0,
+        a: 4.0, tau_w: 150.0, b: 120.0, v_r: -50.0, v_peak: 0.0,
+    };
+
+    pub const BURSTING: AdExParameters = AdExParameters {
+        c: 200.0, g_l: 10.0, e_l: -58.0, v_t: -50.0, delta_t: 2.0,
+        a: 2.0, tau_w: 120.0, b: 100.0, v_r: -46.0, v_peak: 0.0,
+    };
+
+    pub const DELAYED_ACCELERATING: AdExParameters = AdExParameters {
+        c: 200.0, g_l: 12.0, e_l: -70.0, v_t: -50.0, delta_t: 2.0,
+        a: -10.0, tau_w: 300.0, b: 0.0, v_r: -58.0, v_peak: 0.0,
+    };
+
+    pub const DEFAULT: AdExParameters = Self::TONIC;
+
+    /// Regular-spiking, adapting excitatory neuron
+    pub const EXCITATORY: AdExParameters = Self::ADAPTING;
+
+    /// Fast-spiking interneuron, without adaptation
+    pub const INHIBITORY: AdExParameters = Self::TONIC;
+
+    /// Smallest constant input that makes the neuron fire, valid when there is no
+    /// subthreshold adaptation (a = 0).
+    pub fn rheobase(&self) -> f32 {
+        self.g_l * (self.v_t - self.e_l - self.delta_t)
+    }
+}
+
+impl Default for AdExParameters {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Simulated time (ms)
+    const T: usize = 3000;
+    const T_FINE: usize = 500;
+
+    fn spike_times(p: AdExParameters, input: f32) -> Vec<usize> {
+        let mut adex = AdEx::new(1, vec![p]);
+
+        (0..T).filter(|_| adex.step(Array::ones(1) * input).data[0]).collect()
+    }
+
+    fn mean_isi(spike_times: &[usize], dt: f32) -> f32 {
+        let span = (spike_times[spike_times.len() - 1] - spike_times[0]) as f32;
+
+        span * dt / (spike_times.len() - 1) as f32
+    }
+
+    // Without adaptation, the inter-spike interval is given by the integral
+    //      T = int_{v_r}^{v_peak} C / (I - g_l(v - e_l) + g_l delta_t exp((v - v_t)/delta_t)) dv
+    fn analytical_isi(p: AdExParameters, input: f32) -> f32 {
+        const N: usize = 100000;
+
+        let (c, g_l, e_l, v_t, delta_t) = (p.c as f64, p.g_l as f64, p.e_l as f64, p.v_t as f64, p.delta_t as f64);
+        let f = |v: f64| c / (input as f64 - g_l * (v - e_l) + g_l * delta_t * ((v - v_t) / delta_t).exp());
+
+        // Simpson's rule
+        let h = (p.v_peak - p.v_r) as f64 / N as f64;
+        let s: f64 = (0..=N).map(|k| {
+            let w = if k == 0 || k == N { 1.0 } else if k % 2 == 1 { 4.0 } else { 2.0 };
+            w * f(p.v_r as f64 + k as f64 * h)
+        }).sum();
+
+        (s * h / 3.0) as f32
+    }
+
+    fn non_adapting() -> AdExParameters {
+        AdExParameters { a: 0.0, b: 0.0, ..AdExParameters::TONIC }
+    }
+
+    #[test]
+    fn test_adex_rheobase() {
+        let p = non_adapting();
+
+        assert!(spike_times(p, 0.95 * p.rheobase()).is_empty());
+        assert!(!spike_times(p, 1.05 * p.rheobase()).is_empty());
+    }
+
+    #[test]
+    fn test_adex_fi_curve() {
+        let p = non_adapting();
+
+        for k in [1.1, 1.5, 2.0, 3.0] {
+            let input = k * p.rheobase();
+
+            let expected = analytical_isi(p, input);
+            let isi = mean_isi(&spike_times(p, input), 1.0);
+
+            // Allow for the spike being registered at the end of the step, and for the integration error
+            assert!(isi >= 0.95 * expected && isi <= 1.05 * expected + 1.0,
+                "input: {input}, expected isi: {expected}, got {isi}");
+        }
+    }
+
+    #[test]
+    fn test_adex_integrators_agree() {
+        use crate::clock::Integrator;
+
+        let p = non_adapting();
+        let input = 2.0 * p.rheobase();
+
+        let expected = analytical_isi(p, input);
+
+        for integrator in [Integrator::Euler, Integrator::ExponentialEuler, Integrator::RK4] {
+            let clock = Clock::new(0.1, integrator);
+
+            let mut adex = AdEx::new(1, vec![p]);
+            adex.set_clock(clock);
+
+            let s: Vec<usize> = (0..T_FINE * clock.steps_per_ms())
+                .filter(|_| adex.step(Array::ones(1) * input).data[0]).collect();
+
+            let isi = mean_isi(&s, clock.dt);
+
+            assert!(isi >= 0.95 * expected && isi <= 1.05 * expected + clock.dt,
+                "{integrator:?} - expected isi: {expected}, got {isi}");
+        }
+    }
+
+    #[test]
+    fn test_adex_adaptation() {
+        let s = spike_times(AdExParameters::ADAPTING, 500.0);
+
+        let first = (s[1] - s[0]) as f32;
+        let last = (s[s.len() - 1] - s[s.len() - 2]) as f32;
+
+        assert!(last > first, "expected spike frequency adaptation, first isi: {first}, last isi: {last}");
+    }
+}
@@ -1,5 +1,7 @@
 use crate::neuron::NeuronModel;
 use crate::spikes::Spikes;
+use crate::clock::{Clock, Integrator};
+use crate::parallel::zip;
 
 use ndarray::Array1;
 
@@ -28,11 +30,14 @@ pub struct Izhikevich {
     b: Array1<f32>,
     c: Array1<f32>,
     d: Array1<f32>,
+
+    clock: Clock,
 }
 
 
 impl NeuronModel for Izhikevich {
     type Parameters = IzhikevichParameters;
+    type State = IzhikevichState;
 
     fn new(n: usize, params: Vec<IzhikevichParameters>) -> Izhikevich {
         assert!(params.len() == n, "expected {n} params, got {:?}", params.len());
@@ -52,7 +57,9 @@ impl NeuronModel for Izhikevich {
             a,
             b,
             c,
-            d
+            d,
+
+            clock: Clock::default(),
         }
     }
 
@@ -61,15 +68,44 @@ impl NeuronModel for Izhikevich {
 
         self.reset_spiking();
 
-        *&mut self.v = &self.v + 0.5 * (0.04 * (&self.v * &self.v) + 5.0 * &self.v + 140.0 - &self.u + &input);
-        *&mut self.v = &self.v + 0.5 * (0.04 * (&self.v * &self.v) + 5.0 * &self.v + 140.0 - &self.u + &input);
-
-        *&mut self.u = &self.u + &self.a * (&self.b * &self.v - &self.u);
+        let dt = self.clock.dt;
+
+        match self.clock.integrator {
+            // The scheme used by Izhikevich (2003), where v is integrated
+            // in two half-steps for numerical stability.
+            Integrator::Euler => {
+                zip!(input.len(), (v in &mut self.v, u in &mut self.u, &a in &self.a, &b in &self.b, &i in &input) {
+                    *v = *v + 0.5 * dt * (0.04 * (*v * *v) + 5.0 * *v + 140.0 - *u + i);
+                    *v = *v + 0.5 * dt * (0.04 * (*v * *v) + 5.0 * *v + 140.0 - *u + i);
+
+                    *u = *u + dt * a * (b * *v - *u);
+                });
+            },
+            integrator => {
+                let (a, b) = (&self.a, &self.b);
+
+                let mut x = integrator.step(&[self.v.to_owned(), self.u.to_owned()], dt, |x| {
+                    let (v, u) = (&x[0], &x[1]);
+
+                    vec![
+                        (0.04 * v + 5.0, 140.0 - u + &input),
+                        (-a, a * b * v),
+                    ]
+                });
+
+                self.u = x.remove(1);
+                self.v = x.remove(0);
+            }
+        }
 
         // Ensure potentials do not exceed the threshold value.
         // This has no effect on the model, but is necessary when using
         // the potentials in other contexts
-        *&mut self.v = self.v.iter().map(|p| if *p > Self::THRESHOLD {Self::THRESHOLD} else {*p} ).collect();
+        zip!(self.v.len(), (v in &mut self.v) {
+            if *v > Self::THRESHOLD {
+                *v = Self::THRESHOLD;
+            }
+        });
 
         Spikes {
             data: self.v.mapv(|i| if i >= Self::THRESHOLD { true } else { false })
@@ -82,9 +118,27 @@ impl NeuronModel for Izhikevich {
         self.u = (&self.b + &self.v).to_owned();
     }
 
+    fn snapshot(&self) -> IzhikevichState {
+        IzhikevichState {
+            v: self.v.clone(),
+            u: self.u.clone(),
+        }
+    }
+
+    fn restore(&mut self, state: &IzhikevichState) {
+        assert!(state.v.len() == self.v.len(), "state has {} neurons, expected {}", state.v.len(), self.v.len());
+
+        self.v.assign(&state.v);
+        self.u.assign(&state.u);
+    }
+
     fn potentials(&self) -> Array1<f32> {
         self.v.to_owned()
     }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
 }
 
 
@@ -96,16 +150,21 @@ impl Izhikevich {
          * Reset neurons with v >= 30
          */
 
-        // TODO: This can be parallelized w/ rayon
-        for i in 0..self.v.shape()[0] {
-            if self.v[i] >= Self::THRESHOLD {
-                *&mut self.v[i] = self.c[i];
-                *&mut self.u[i] = self.u[i] + self.d[i];
+        zip!(self.v.len(), (v in &mut self.v, u in &mut self.u, &c in &self.c, &d in &self.d) {
+            if *v >= Self::THRESHOLD {
+                *v = c;
+                *u += d;
             }
-        }
+        });
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct IzhikevichState {
+    pub v: Array1<f32>, // membrane potential (mV)
+    pub u: Array1<f32>, // recovery variable
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 pub struct IzhikevichParameters {
     pub a: f32,
@@ -3,6 +3,7 @@
 
 use crate::spikes::Spikes;
 use crate::neuron::NeuronModel;
+use crate::clock::Clock;
 
 use ndarray::{Array, Array1};
 
@@ -12,7 +13,6 @@ use serde::{Serialize, Deserialize};
 const DEFAULT_R: f32 = 5f32;
 const DEFAULT_C: f32 = 1e-3f32;
 
-const TIME_STEP: f32 = 0.001;
 const FIRING_THRESHOLD: f32 = 1.0;
 
 pub struct Lapicque {
@@ -22,10 +22,13 @@ pub struct Lapicque {
 
     r: Array1<f32>,
     c: Array1<f32>,
+
+    clock: Clock,
 }
 
 impl NeuronModel for Lapicque {
     type Parameters = LapicqueParameters;
+    type State = LapicqueState;
 
     fn new(n: usize, params: Vec<LapicqueParameters>) -> Self {
         Lapicque {
@@ -34,6 +37,8 @@ impl NeuronModel for Lapicque {
 
             r: params.iter().map(|p| p.r).collect(),
             c: params.iter().map(|p| p.c).collect(),
+
+            clock: Clock::default(),
         }
     }
 
@@ -43,12 +48,15 @@ impl NeuronModel for Lapicque {
         // Reset neurons with v >= firing threshold
         self.v = self.v.mapv(|p| if p >= FIRING_THRESHOLD { 0.0 } else { p });
 
+        // Parameters are given in SI units, so the step is converted from ms to s
+        let dt = self.clock.dt / 1000.0;
+
         let tau = &self.r * &self.c;
 
-        const INTEGRATION_STEPS: usize = 2;
-        for _ in 0..INTEGRATION_STEPS {
-            self.v = &self.v + (1.0 / INTEGRATION_STEPS as f32) * (TIME_STEP / &tau) * (-&self.v + &input * &self.r);
-        }
+        let a = -1.0 / &tau;
+        let b = &input * &self.r / &tau;
+
+        self.v = self.clock.integrator.step(&[self.v.to_owned()], dt, |_| vec![(a.to_owned(), b.to_owned())]).remove(0);
 
         Spikes {
             data: self.v.mapv(|i| if i >= FIRING_THRESHOLD { true } else { false })
@@ -59,9 +67,30 @@ impl NeuronModel for Lapicque {
         self.v = Array::zeros(self.n);
     }
 
+    fn snapshot(&self) -> LapicqueState {
+        LapicqueState {
+            v: self.v.clone(),
+        }
+    }
+
+    fn restore(&mut self, state: &LapicqueState) {
+        assert!(state.v.len() == self.v.len(), "state has {} neurons, expected {}", state.v.len(), self.v.len());
+
+        self.v.assign(&state.v);
+    }
+
     fn potentials(&self) -> Array1<f32> {
         self.v.clone()
     }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LapicqueState {
+    pub v: Array1<f32>, // membrane potential
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
@@ -70,11 +99,21 @@ pub struct LapicqueParameters {
     pub c: f32,
 }
 
+impl LapicqueParameters {
+    pub const EXCITATORY: LapicqueParameters = LapicqueParameters {
+        r: DEFAULT_R,
+        c: DEFAULT_C,
+    };
+
+    /// Interneuron with half the membrane time constant (r * c) of the excitatory neuron
+    pub const INHIBITORY: LapicqueParameters = LapicqueParameters {
+        r: DEFAULT_R,
+        c: DEFAULT_C / 2.0,
+    };
+}
+
 impl Default for LapicqueParameters {
     fn default() -> Self {
-        LapicqueParameters {
-            r: DEFAULT_R,
-            c: DEFAULT_C,
-        }
+        Self::EXCITATORY
     }
 }
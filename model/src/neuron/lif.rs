@@ -0,0 +1,261 @@
+//! Current-based leaky integrate-and-fire neuron with an absolute refractory period.
+
+use crate::spikes::Spikes;
+use crate::neuron::NeuronModel;
+use crate::clock::Clock;
+use crate::parallel::zip;
+
+use ndarray::{Array, Array1};
+
+use serde::{Serialize, Deserialize};
+
+
+/*
+ * The membrane potential follows
+ *
+ *      tau_m * dv/dt = -(v - v_rest) + r * I
+ *
+ * A neuron fires when v >= v_th, after which it is held at v_reset for t_ref ms.
+ * With r = 1.0 the input current is expressed directly in mV.
+ */
+pub struct Lif {
+    v: Array1<f32>,
+
+    /// Remaining refractory time (ms)
+    refractory: Array1<f32>,
+
+    tau_m: Array1<f32>,
+    v_rest: Array1<f32>,
+    v_reset: Array1<f32>,
+    v_th: Array1<f32>,
+    r: Array1<f32>,
+    t_ref: Array1<f32>,
+
+    clock: Clock,
+}
+
+impl NeuronModel for Lif {
+    type Parameters = LifParameters;
+    type State = LifState;
+
+    fn new(n: usize, params: Vec<LifParameters>) -> Lif {
+        assert!(params.len() == n, "expected {n} params, got {:?}", params.len());
+
+        let v_rest: Array1<f32> = params.iter().map(|p| p.v_rest).collect();
+
+        Lif {
+            v: v_rest.clone(),
+            refractory: Array::zeros(n),
+
+            tau_m: params.iter().map(|p| p.tau_m).collect(),
+            v_rest,
+            v_reset: params.iter().map(|p| p.v_reset).collect(),
+            v_th: params.iter().map(|p| p.v_th).collect(),
+            r: params.iter().map(|p| p.r).collect(),
+            t_ref: params.iter().map(|p| p.t_ref).collect(),
+
+            clock: Clock::default(),
+        }
+    }
+
+    fn step(&mut self, input: Array1<f32>) -> Spikes {
+        assert!(input.shape()[0] == self.v.shape()[0]);
+
+        self.reset_spiking();
+
+        let dt = self.clock.dt;
+
+        let a = -1.0 / &self.tau_m;
+        let b = (&self.v_rest + &self.r * &input) / &self.tau_m;
+
+        let v = self.clock.integrator.step(&[self.v.to_owned()], dt, |_| vec![(a.to_owned(), b.to_owned())]).remove(0);
+
+        zip!(v.len(), (v in &mut self.v, r in &mut self.refractory, &v_next in &v, &v_th in &self.v_th) {
+            // Half a step of tolerance, as the refractory time accumulates rounding errors
+            if *r >= 0.5 * dt {
+                *r -= dt;
+            } else {
+                // Clamp to the threshold, as for the Izhikevich model
+                *v = if v_next > v_th { v_th } else { v_next };
+            }
+        });
+
+        Spikes {
+            data: self.v.iter().zip(&self.v_th).map(|(v, th)| v >= th).collect()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.v = self.v_rest.to_owned();
+        self.refractory = Array::zeros(self.v.shape()[0]);
+    }
+
+    fn snapshot(&self) -> LifState {
+        LifState {
+            v: self.v.clone(),
+            refractory: self.refractory.clone(),
+        }
+    }
+
+    fn restore(&mut self, state: &LifState) {
+        assert!(state.v.len() == self.v.len(), "state has {} neurons, expected {}", state.v.len(), self.v.len());
+
+        self.v.assign(&state.v);
+        self.refractory.assign(&state.refractory);
+    }
+
+    fn potentials(&self) -> Array1<f32> {
+        self.v.to_owned()
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+}
+
+impl Lif {
+    fn reset_spiking(&mut self) {
+        zip!(self.v.len(), (v in &mut self.v, r in &mut self.refractory, &v_th in &self.v_th, &v_reset in &self.v_reset, &t_ref in &self.t_ref) {
+            if *v >= v_th {
+                *v = v_reset;
+                *r = t_ref;
+            }
+        });
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LifState {
+    pub v: Array1<f32>,          // membrane potential (mV)
+    pub refractory: Array1<f32>, // remaining refractory time (ms)
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct LifParameters {
+    pub tau_m: f32,     // membrane time constant (ms)
+    pub v_rest: f32,    // resting potential (mV)
+    pub v_reset: f32,   // reset potential (mV)
+    pub v_th: f32,      // firing threshold (mV)
+    pub r: f32,         // membrane resistance
+    pub t_ref: f32,     // refractory period (ms)
+}
+
+impl LifParameters {
+    /// Pyramidal-like excitatory neuron
+    pub const EXCITATORY: LifParameters = LifParameters {
+        tau_m: 20.0,
+        v_rest: -65.0,
+        v_reset: -65.0,
+        v_th: -50.0,
+        r: 1.0,
+        t_ref: 2.0,
+    };
+
+    /// Fast-spiking interneuron
+    pub const INHIBITORY: LifParameters = LifParameters {
+        tau_m: 10.0,
+        v_rest: -65.0,
+        v_reset: -65.0,
+        v_th: -50.0,
+        r: 1.0,
+        t_ref: 1.0,
+    };
+
+    pub const DEFAULT: LifParameters = Self::EXCITATORY;
+
+    /// Smallest constant input that makes the neuron fire
+    pub fn rheobase(&self) -> f32 {
+        (self.v_th - self.v_rest) / self.r
+    }
+
+    /// Analytical firing rate (Hz) under a constant input current
+    pub fn firing_rate(&self, input: f32) -> f32 {
+        let v_inf = self.v_rest + self.r * input;
+
+        if v_inf <= self.v_th {
+            return 0.0;
+        }
+
+        let t = self.t_ref + self.tau_m * ((v_inf - self.v_reset) / (v_inf - self.v_th)).ln();
+
+        1000.0 / t
+    }
+}
+
+impl Default for LifParameters {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Integrator;
+
+    /// Simulated time (ms)
+    const T: usize = 1000;
+
+    /// Mean inter-spike interval (ms) of a single neuron under constant input
+    fn simulated_isi(p: LifParameters, input: f32, clock: Clock) -> Option<f32> {
+        let mut lif = Lif::new(1, vec![p]);
+        lif.set_clock(clock);
+
+        let spike_times: Vec<usize> = (0..T * clock.steps_per_ms())
+            .filter(|_| lif.step(Array::ones(1) * input).data[0])
+            .collect();
+
+        if spike_times.len() < 2 {
+            return None;
+        }
+
+        let span = (spike_times[spike_times.len() - 1] - spike_times[0]) as f32;
+
+        Some(span * clock.dt / (spike_times.len() - 1) as f32)
+    }
+
+    #[test]
+    fn test_lif_silent_below_rheobase() {
+        for p in [LifParameters::EXCITATORY, LifParameters::INHIBITORY] {
+            assert!(simulated_isi(p, 0.9 * p.rheobase(), Clock::default()).is_none());
+            assert!(p.firing_rate(0.9 * p.rheobase()) == 0.0);
+        }
+    }
+
+    #[test]
+    fn test_lif_fi_curve() {
+        let clocks = [
+            Clock::new(1.0, Integrator::ExponentialEuler),
+            Clock::new(0.1, Integrator::ExponentialEuler),
+            Clock::new(0.1, Integrator::RK4),
+        ];
+
+        for clock in clocks {
+            for p in [LifParameters::EXCITATORY, LifParameters::INHIBITORY] {
+                for k in [1.05, 1.2, 1.5, 2.0, 3.0] {
+                    let input = k * p.rheobase();
+
+                    let expected = 1000.0 / p.firing_rate(input);
+                    let isi = simulated_isi(p, input, clock).unwrap();
+
+                    // Spikes are registered at the end of the step in which the threshold is crossed
+                    assert!(isi >= expected - 1e-2 && isi <= expected + clock.dt,
+                        "{clock:?} - input: {input}, expected isi: {expected}, got {isi}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lif_euler_converges() {
+        let p = LifParameters::EXCITATORY;
+        let input = 2.0 * p.rheobase();
+
+        let expected = 1000.0 / p.firing_rate(input);
+
+        let coarse = simulated_isi(p, input, Clock::new(1.0, Integrator::Euler)).unwrap();
+        let fine = simulated_isi(p, input, Clock::new(0.1, Integrator::Euler)).unwrap();
+
+        assert!((fine - expected).abs() < (coarse - expected).abs());
+    }
+}
@@ -0,0 +1,215 @@
+//! Network of neurons of different models, such as Izhikevich excitatory neurons and LIF interneurons.
+//!
+//! The parameters of each neuron name its model. Neurons are grouped by model, where each
+//! group is stepped as a single instance of that model, while the network keeps a single
+//! index space over all neurons.
+
+use crate::spikes::Spikes;
+use crate::neuron::NeuronModel;
+use crate::neuron::izhikevich::{Izhikevich, IzhikevichParameters, IzhikevichState};
+use crate::neuron::lif::{Lif, LifParameters, LifState};
+use crate::neuron::adex::{AdEx, AdExParameters, AdExState};
+use crate::neuron::lapicque::{Lapicque, LapicqueParameters, LapicqueState};
+use crate::clock::Clock;
+
+use ndarray::{Array, Array1, Axis};
+
+use serde::{Serialize, Deserialize};
+
+
+pub struct Mixed {
+    n: usize,
+
+    izhikevich: Group<Izhikevich>,
+    lif: Group<Lif>,
+    adex: Group<AdEx>,
+    lapicque: Group<Lapicque>,
+}
+
+impl NeuronModel for Mixed {
+    type Parameters = MixedParameters;
+    type State = MixedState;
+
+    fn new(n: usize, params: Vec<MixedParameters>) -> Mixed {
+        assert!(params.len() == n, "expected {n} params, got {:?}", params.len());
+
+        Mixed {
+            n,
+
+            izhikevich: Group::new(&params, |p| match p { MixedParameters::Izhikevich(p) => Some(*p), _ => None }),
+            lif: Group::new(&params, |p| match p { MixedParameters::Lif(p) => Some(*p), _ => None }),
+            adex: Group::new(&params, |p| match p { MixedParameters::AdEx(p) => Some(*p), _ => None }),
+            lapicque: Group::new(&params, |p| match p { MixedParameters::Lapicque(p) => Some(*p), _ => None }),
+        }
+    }
+
+    fn step(&mut self, input: Array1<f32>) -> Spikes {
+        assert!(input.shape()[0] == self.n);
+
+        let mut spikes = Spikes::new(self.n);
+
+        self.izhikevich.step(&input, &mut spikes);
+        self.lif.step(&input, &mut spikes);
+        self.adex.step(&input, &mut spikes);
+        self.lapicque.step(&input, &mut spikes);
+
+        spikes
+    }
+
+    fn reset(&mut self) {
+        self.izhikevich.model.reset();
+        self.lif.model.reset();
+        self.adex.model.reset();
+        self.lapicque.model.reset();
+    }
+
+    fn snapshot(&self) -> MixedState {
+        MixedState {
+            izhikevich: self.izhikevich.model.snapshot(),
+            lif: self.lif.model.snapshot(),
+            adex: self.adex.model.snapshot(),
+            lapicque: self.lapicque.model.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, state: &MixedState) {
+        self.izhikevich.model.restore(&state.izhikevich);
+        self.lif.model.restore(&state.lif);
+        self.adex.model.restore(&state.adex);
+        self.lapicque.model.restore(&state.lapicque);
+    }
+
+    fn potentials(&self) -> Array1<f32> {
+        let mut v = Array::zeros(self.n);
+
+        self.izhikevich.potentials(&mut v);
+        self.lif.potentials(&mut v);
+        self.adex.potentials(&mut v);
+        self.lapicque.potentials(&mut v);
+
+        v
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.izhikevich.model.set_clock(clock);
+        self.lif.model.set_clock(clock);
+        self.adex.model.set_clock(clock);
+        self.lapicque.model.set_clock(clock);
+    }
+}
+
+impl Mixed {
+    /// Indices of the neurons of each model, in the order izhikevich, lif, adex, lapicque
+    pub fn groups(&self) -> [&[usize]; 4] {
+        [&self.izhikevich.neurons, &self.lif.neurons, &self.adex.neurons, &self.lapicque.neurons]
+    }
+}
+
+/// The neurons of a single model, given by their index in the network
+struct Group<N: NeuronModel> {
+    model: N,
+    neurons: Vec<usize>,
+}
+
+impl<N: NeuronModel> Group<N> {
+    fn new<F>(params: &[MixedParameters], select: F) -> Group<N>
+        where F: Fn(&MixedParameters) -> Option<N::Parameters>
+    {
+        let (neurons, params): (Vec<usize>, Vec<N::Parameters>) = params.iter().enumerate()
+            .filter_map(|(i, p)| select(p).map(|p| (i, p)))
+            .unzip();
+
+        Group {
+            model: N::new(neurons.len(), params),
+            neurons,
+        }
+    }
+
+    fn step(&mut self, input: &Array1<f32>, spikes: &mut Spikes) {
+        if self.neurons.is_empty() {
+            return;
+        }
+
+        let s = self.model.step(input.select(Axis(0), &self.neurons));
+
+        for (i, fired) in self.neurons.iter().zip(s.data) {
+            spikes.data[*i] = fired;
+        }
+    }
+
+    fn potentials(&self, v: &mut Array1<f32>) {
+        for (i, p) in self.neurons.iter().zip(self.model.potentials()) {
+            v[*i] = p;
+        }
+    }
+}
+
+/// Parameters of a neuron, which name its model
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "model")]
+pub enum MixedParameters {
+    #[serde(rename = "izhikevich")]
+    Izhikevich(IzhikevichParameters),
+
+    #[serde(rename = "lif")]
+    Lif(LifParameters),
+
+    #[serde(rename = "adex")]
+    AdEx(AdExParameters),
+
+    #[serde(rename = "lapicque")]
+    Lapicque(LapicqueParameters),
+}
+
+impl Default for MixedParameters {
+    fn default() -> Self {
+        MixedParameters::Izhikevich(IzhikevichParameters::default())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MixedState {
+    pub izhikevich: IzhikevichState,
+    pub lif: LifState,
+    pub adex: AdExState,
+    pub lapicque: LapicqueState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> Vec<MixedParameters> {
+        vec![
+            MixedParameters::Izhikevich(IzhikevichParameters::default()),
+            MixedParameters::Lif(LifParameters::INHIBITORY),
+            MixedParameters::Izhikevich(IzhikevichParameters::default()),
+            MixedParameters::AdEx(AdExParameters::DEFAULT),
+        ]
+    }
+
+    #[test]
+    fn test_groups_keep_global_index() {
+        let model = Mixed::new(4, params());
+
+        assert_eq!(model.groups(), [&[0, 2][..], &[1], &[3], &[]]);
+
+        let mut izhikevich = Izhikevich::new(1, vec![IzhikevichParameters::default()]);
+        let mut lif = Lif::new(1, vec![LifParameters::INHIBITORY]);
+
+        assert_eq!(model.potentials()[0], izhikevich.potentials()[0]);
+        assert_eq!(model.potentials()[1], lif.potentials()[0]);
+
+        // Each neuron behaves as in a network of its own model
+        let mut model = model;
+
+        for _ in 0..100 {
+            let spikes = model.step(Array::from_elem(4, 20.0));
+
+            assert_eq!(spikes.data[0], izhikevich.step(Array::from_elem(1, 20.0)).data[0]);
+            assert_eq!(spikes.data[1], lif.step(Array::from_elem(1, 20.0)).data[0]);
+
+            assert_eq!(model.potentials()[2], izhikevich.potentials()[0]);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Sources of background activity, independent of the input from the task.
+
+use crate::spikes::Spikes;
+use crate::clock::Clock;
+
+use utils::random;
+
+use ndarray::{Array, Array1};
+use ndarray_rand::rand_distr::{StandardNormal, Standard};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use serde::{Serialize, Deserialize};
+
+
+/// A source of noise, applied independently to each neuron
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum Noise {
+    /// White noise current, where std is the standard deviation at a step of 1 ms
+    #[serde(rename = "gaussian")]
+    Gaussian { mean: f32, std: f32 },
+
+    /// Spike trains with the given rate (Hz), delivered through a background synapse
+    #[serde(rename = "poisson")]
+    Poisson { rate: f32, weight: f32 },
+
+    /// Current relaxing to the mean with time constant tau (ms), with stationary standard deviation std
+    #[serde(rename = "ornstein_uhlenbeck")]
+    OrnsteinUhlenbeck { mean: f32, std: f32, tau: f32 },
+}
+
+/// Noise current to each of n neurons
+pub struct CurrentNoise {
+    noise: Noise,
+
+    /// Current of the previous step, which is the state of the Ornstein-Uhlenbeck process
+    i: Array1<f32>,
+
+    stream: NoiseStream,
+    clock: Clock,
+}
+
+impl CurrentNoise {
+    pub fn new(noise: Noise, n: usize) -> CurrentNoise {
+        assert!(!matches!(noise, Noise::Poisson {..}), "Poisson noise is not a current");
+
+        let mut source = CurrentNoise {
+            noise,
+            i: Array::zeros(n),
+
+            stream: NoiseStream::new(),
+            clock: Clock::default(),
+        };
+
+        source.reset();
+        source
+    }
+
+    pub fn step(&mut self) -> Array1<f32> {
+        let dt = self.clock.dt;
+        let rng = &mut self.stream.rng;
+
+        match self.noise {
+            Noise::Gaussian { mean, std } => {
+                // Scaled so that the effect of the noise does not depend on the step size
+                let std = std / dt.sqrt();
+
+                self.i.mapv_inplace(|_| mean + std * rng.sample::<f32, _>(StandardNormal));
+            },
+            Noise::OrnsteinUhlenbeck { mean, std, tau } => {
+                // Exact update of the process over a step
+                let decay = (-dt / tau).exp();
+                let std = std * (1.0 - decay * decay).sqrt();
+
+                self.i.mapv_inplace(|i| mean + (i - mean) * decay + std * rng.sample::<f32, _>(StandardNormal));
+            },
+            Noise::Poisson {..} => unreachable!(),
+        }
+
+        self.i.clone()
+    }
+
+    /// Restarts the noise, so that it repeats after every reset
+    pub fn reset(&mut self) {
+        self.stream.reset();
+
+        let mean = match self.noise {
+            Noise::OrnsteinUhlenbeck { mean, .. } => mean,
+            _ => 0.0,
+        };
+
+        self.i.fill(mean);
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn current(&self) -> &Array1<f32> {
+        &self.i
+    }
+
+    pub fn set_current(&mut self, i: &Array1<f32>) {
+        self.i.assign(i);
+    }
+}
+
+/// Independent Poisson spike trains, one for each of n neurons
+pub struct PoissonInput {
+    /// Firing rate (Hz)
+    rate: f32,
+    n: usize,
+
+    stream: NoiseStream,
+    clock: Clock,
+}
+
+impl PoissonInput {
+    pub fn new(rate: f32, n: usize) -> PoissonInput {
+        assert!(rate >= 0.0, "rate must be non-negative, got {rate}");
+
+        PoissonInput {
+            rate,
+            n,
+
+            stream: NoiseStream::new(),
+            clock: Clock::default(),
+        }
+    }
+
+    pub fn step(&mut self) -> Spikes {
+        let p = self.rate * self.clock.dt / 1000.0;
+        let rng = &mut self.stream.rng;
+
+        Spikes {
+            data: (0..self.n).map(|_| rng.gen::<f32>() < p).collect()
+        }
+    }
+
+    /// Restarts the spike trains, so that they repeat after every reset
+    pub fn reset(&mut self) {
+        self.stream.reset();
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+}
+
+/// A random stream seeded from utils::random, so that noise is reproducible given its seed
+struct NoiseStream {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl NoiseStream {
+    fn new() -> NoiseStream {
+        let seed: u64 = random::random_sample(Standard);
+
+        NoiseStream {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Integrator;
+
+    fn mean_and_std(x: &[f32]) -> (f32, f32) {
+        let mean = x.iter().sum::<f32>() / x.len() as f32;
+        let var = x.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / x.len() as f32;
+
+        (mean, var.sqrt())
+    }
+
+    #[test]
+    fn test_gaussian_noise() {
+        let mut noise = CurrentNoise::new(Noise::Gaussian { mean: 1.0, std: 2.0 }, 1000);
+
+        let (mean, std) = mean_and_std(noise.step().as_slice().unwrap());
+
+        assert!((mean - 1.0).abs() < 0.2, "mean: {mean}");
+        assert!((std - 2.0).abs() < 0.2, "std: {std}");
+    }
+
+    #[test]
+    fn test_ornstein_uhlenbeck_noise() {
+        let (mean, std, tau, dt) = (-1.0, 0.5, 10.0, 0.5);
+
+        let mut noise = CurrentNoise::new(Noise::OrnsteinUhlenbeck { mean, std, tau }, 500);
+        noise.set_clock(Clock::new(dt, Integrator::Euler));
+
+        // Discard 10 tau, so that the process is stationary
+        for _ in 0..200 {
+            noise.step();
+        }
+
+        let steps: Vec<Array1<f32>> = (0..400).map(|_| noise.step()).collect();
+        let i: Vec<f32> = steps.iter().flatten().copied().collect();
+
+        let (m, s) = mean_and_std(&i);
+
+        assert!((m - mean).abs() < 0.05, "mean: {m}");
+        assert!((s.powi(2) - std.powi(2)).abs() < 0.1 * std.powi(2), "variance: {}", s.powi(2));
+
+        // Lag-1 autocorrelation of a stationary OU process is exp(-dt/tau)
+        let cov: f32 = steps.windows(2)
+            .map(|w| ((&w[0] - m) * (&w[1] - m)).sum())
+            .sum::<f32>() / (i.len() - steps[0].len()) as f32;
+
+        let r = cov / s.powi(2);
+        let expected = (-dt / tau).exp();
+
+        assert!((r - expected).abs() < 0.02, "autocorrelation: {r}, expected: {expected}");
+    }
+
+    #[test]
+    fn test_poisson_rate() {
+        let mut input = PoissonInput::new(20.0, 100);
+
+        let spikes: usize = (0..1000).map(|_| input.step().firing().len()).sum();
+
+        // 100 neurons at 20 Hz for 1 s
+        assert!((spikes as f32 - 2000.0).abs() < 200.0, "spikes: {spikes}");
+    }
+
+    #[test]
+    fn test_noise_repeats_after_reset() {
+        let mut noise = CurrentNoise::new(Noise::Gaussian { mean: 0.0, std: 1.0 }, 10);
+
+        let first = noise.step();
+        noise.reset();
+
+        assert_eq!(noise.step(), first);
+    }
+}
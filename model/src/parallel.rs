@@ -0,0 +1,44 @@
+//! Elementwise updates that run on multiple threads with the `parallel` feature.
+//!
+//! The updates of each element are independent, and are computed in the same order either way,
+//! so that the parallel path gives exactly the same results as the serial one.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Networks with fewer neurons are updated serially, as distributing the work costs more than it gains
+pub const MIN_PARALLEL_LEN: usize = 4096;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Like ndarray's azip!, with the length of the arrays given first. Arrays of at least
+/// MIN_PARALLEL_LEN elements are processed in parallel when the `parallel` feature is enabled.
+#[cfg(feature = "parallel")]
+macro_rules! zip {
+    ($len:expr, $($t:tt)*) => {
+        if $crate::parallel::is_parallel($len) {
+            ndarray::par_azip!($($t)*)
+        } else {
+            ndarray::azip!($($t)*)
+        }
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+macro_rules! zip {
+    ($len:expr, $($t:tt)*) => {
+        ndarray::azip!($($t)*)
+    }
+}
+
+pub(crate) use zip;
+
+/// Whether work over len elements is split across threads
+pub fn is_parallel(len: usize) -> bool {
+    cfg!(feature = "parallel") && len >= MIN_PARALLEL_LEN && ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turns the parallel path on or off for all threads, so that both paths can be compared
+/// within the same build. It has no effect without the `parallel` feature.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
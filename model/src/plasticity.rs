@@ -0,0 +1,325 @@
+//! Online learning rules that change the weights of a network as it runs.
+//!
+//! A rule observes the spikes sent through the synapse (pre) and the spikes they
+//! result in (post), and updates the weights accordingly. Only connections from
+//! excitatory neurons are plastic, and weights are kept within [0, w_max].
+
+pub mod pair;
+pub mod triplet;
+pub mod reward_modulated;
+
+use crate::spikes::Spikes;
+use crate::clock::Clock;
+use crate::parallel::zip;
+use crate::synapse::representation::CsrRepresentation;
+
+use pair::{PairStdp, PairStdpParameters};
+use triplet::{TripletStdp, TripletStdpParameters};
+use reward_modulated::{RewardModulatedStdp, RewardModulatedStdpParameters};
+
+use utils::parameters::Parameter;
+
+use ndarray::{Array, Array1};
+
+use serde::{Serialize, Deserialize};
+
+
+/// Evolved parameters scale the default values by exp(x), where x is bounded by this
+const MAX_LOG_SCALE: f32 = 5.0;
+
+pub trait LearningRule {
+    /// Updates the weights, given the spikes sent through the synapse and the resulting spikes
+    fn step(&mut self, pre: &Spikes, post: &Spikes, weights: &mut CsrRepresentation);
+
+    /// Delivers a reward signal; ignored by rules that are not reward-modulated
+    fn reward(&mut self, _r: f32) { }
+
+    /// Clears the traces of the rule; the weights are left as they are
+    fn reset(&mut self);
+
+    fn set_clock(&mut self, clock: Clock);
+}
+
+/// Parameters of a learning rule, which can be converted to and from a flat vector
+pub trait RuleParameters: Copy + Sized {
+    fn to_vec(&self) -> Vec<f32>;
+    fn from_vec(v: &[f32]) -> Self;
+
+    /// Scales each parameter by exp(x), so that x = 0 gives the parameters unchanged
+    fn scaled(&self, x: &[f32]) -> Self {
+        let v = self.to_vec();
+        assert!(x.len() == v.len(), "expected {} parameters, got {}", v.len(), x.len());
+
+        let scaled: Vec<f32> = v.iter().zip(x)
+            .map(|(p, x)| p * x.clamp(-MAX_LOG_SCALE, MAX_LOG_SCALE).exp())
+            .collect();
+
+        Self::from_vec(&scaled)
+    }
+}
+
+/// Description of the learning rule of a network
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Plasticity {
+    #[serde(rename = "pair")]
+    Pair(PairStdpParameters),
+    #[serde(rename = "triplet")]
+    Triplet(TripletStdpParameters),
+    #[serde(rename = "reward_modulated")]
+    RewardModulated(RewardModulatedStdpParameters),
+}
+
+impl Plasticity {
+    /// The rule with the given name and default parameters
+    pub fn from_name(name: &str) -> Option<Plasticity> {
+        match name {
+            "pair" => Some(Plasticity::Pair(PairStdpParameters::default())),
+            "triplet" => Some(Plasticity::Triplet(TripletStdpParameters::default())),
+            "reward_modulated" => Some(Plasticity::RewardModulated(RewardModulatedStdpParameters::default())),
+            _ => None
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn LearningRule> {
+        match self {
+            Plasticity::Pair(p) => Box::new(PairStdp::new(*p)),
+            Plasticity::Triplet(p) => Box::new(TripletStdp::new(*p)),
+            Plasticity::RewardModulated(p) => Box::new(RewardModulatedStdp::new(*p)),
+        }
+    }
+
+    /// Evolvable parameter of the rule, where zero corresponds to the current parameters
+    pub fn params(&self) -> Parameter {
+        let n = match self {
+            Plasticity::Pair(p) => p.to_vec().len(),
+            Plasticity::Triplet(p) => p.to_vec().len(),
+            Plasticity::RewardModulated(p) => p.to_vec().len(),
+        };
+
+        Parameter::Vector(Array::zeros(n))
+    }
+
+    /// The rule with parameters scaled by an evolved parameter, see params()
+    pub fn evolved(&self, param: &Parameter) -> Plasticity {
+        let x = param.linearize();
+
+        match self {
+            Plasticity::Pair(p) => Plasticity::Pair(p.scaled(&x)),
+            Plasticity::Triplet(p) => Plasticity::Triplet(p.scaled(&x)),
+            Plasticity::RewardModulated(p) => Plasticity::RewardModulated(p.scaled(&x)),
+        }
+    }
+}
+
+/// Exponentially decaying trace of the spikes of a population, incremented by 1 per spike.
+/// Traces are decayed exactly, regardless of the integrator.
+pub struct Trace {
+    pub x: Array1<f32>,
+
+    tau: f32,
+    decay: f32,
+}
+
+impl Trace {
+    pub fn new(tau: f32) -> Trace {
+        Trace {
+            x: Array::zeros(0),
+            tau,
+            decay: (-Clock::default().dt / tau).exp(),
+        }
+    }
+
+    pub fn decay(&mut self, n: usize) {
+        if self.x.len() != n {
+            self.x = Array::zeros(n);
+        }
+
+        let decay = self.decay;
+
+        zip!(n, (x in &mut self.x) {
+            *x *= decay;
+        });
+    }
+
+    pub fn add(&mut self, spikes: &Spikes) {
+        for i in spikes.firing() {
+            self.x[i] += 1.0;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.x.fill(0.0);
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.decay = (-clock.dt / self.tau).exp();
+    }
+}
+
+/// Applies the weight change dw(ix, from, to) to the connections from an excitatory neuron
+/// that are from a neuron that fired (pre) or to a neuron that fired (post), bounding the
+/// weights to [0, w_max].
+///
+/// Rules that only change weights on spikes thus visit the rows and columns of the firing
+/// neurons, rather than every connection.
+pub fn update_firing_weights<F>(weights: &mut CsrRepresentation, pre: &Spikes, post: &Spikes, w_max: f32, mut dw: F)
+    where F: FnMut(usize, usize, usize) -> f32
+{
+    let neuron_type = weights.neuron_type().to_owned();
+
+    for k in pre.firing() {
+        if neuron_type[k] > 0.0 {
+            weights.for_each_outgoing_mut(k, |ix, k, j, w| {
+                *w = (*w + dw(ix, k, j)).clamp(0.0, w_max);
+            });
+        }
+    }
+
+    for j in post.firing() {
+        weights.for_each_incoming_mut(j, |ix, k, j, w| {
+            // Connections from neurons that fired are updated with their rows
+            if neuron_type[k] > 0.0 && !pre.data[k] {
+                *w = (*w + dw(ix, k, j)).clamp(0.0, w_max);
+            }
+        });
+    }
+}
+
+/// Applies the weight change dw(ix, from, to) to each connection from an excitatory neuron,
+/// bounding the weights to [0, w_max]. Used by rules that change the weights without spikes.
+pub fn update_weights<F>(weights: &mut CsrRepresentation, w_max: f32, mut dw: F)
+    where F: FnMut(usize, usize, usize) -> f32
+{
+    let neuron_type = weights.neuron_type().to_owned();
+
+    weights.for_each_connection_mut(|ix, k, j, w| {
+        if neuron_type[k] > 0.0 {
+            *w = (*w + dw(ix, k, j)).clamp(0.0, w_max);
+        }
+    });
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    /// A single connection from neuron 0 to neuron 1
+    pub fn single_connection(w: f32) -> CsrRepresentation {
+        CsrRepresentation::new(array![[0.0, 0.0], [w, 0.0]], array![1.0, 1.0])
+    }
+
+    pub fn weight(weights: &CsrRepresentation) -> f32 {
+        weights.matrix()[[1, 0]]
+    }
+
+    fn spikes(firing: &[usize]) -> Spikes {
+        let mut s = Spikes::new(2);
+
+        for i in firing {
+            s.data[*i] = true;
+        }
+
+        s
+    }
+
+    /// Runs the rule on a pre spike at t = 0 and a post spike at t = lag, and returns the change in weight
+    pub fn weight_change(rule: &mut dyn LearningRule, lag: i32, reward: Option<f32>) -> f32 {
+        let mut weights = single_connection(1.0);
+
+        let (t_pre, t_post) = if lag >= 0 { (0, lag) } else { (-lag, 0) };
+
+        for t in 0..100 {
+            let pre = if t == t_pre { spikes(&[0]) } else { spikes(&[]) };
+            let post = if t == t_post { spikes(&[1]) } else { spikes(&[]) };
+
+            rule.step(&pre, &post, &mut weights);
+
+            if let Some(r) = reward {
+                if t == t_pre.max(t_post) {
+                    rule.reward(r);
+                }
+            }
+        }
+
+        weight(&weights) - 1.0
+    }
+
+    #[test]
+    fn test_plasticity_from_name() {
+        for name in ["pair", "triplet", "reward_modulated"] {
+            assert!(Plasticity::from_name(name).is_some());
+        }
+
+        assert!(Plasticity::from_name("hebbian").is_none());
+    }
+
+    #[test]
+    fn test_evolved_parameters() {
+        let p = Plasticity::from_name("pair").unwrap();
+
+        assert_eq!(p.evolved(&p.params()), p);
+
+        let x = Parameter::Vector(Array::ones(p.params().len()));
+
+        match (p, p.evolved(&x)) {
+            (Plasticity::Pair(a), Plasticity::Pair(b)) => {
+                assert!((b.a_plus - a.a_plus * 1f32.exp()).abs() < 1e-6);
+            },
+            _ => panic!("evolved rule changed type")
+        }
+    }
+
+    #[test]
+    fn test_inhibitory_connections_are_not_plastic() {
+        let mut weights = CsrRepresentation::new(array![[0.0, 0.0], [1.0, 0.0]], array![-1.0, 1.0]);
+
+        update_weights(&mut weights, 10.0, |_, _, _| 1.0);
+
+        assert_eq!(weight(&weights), 1.0);
+    }
+
+    #[test]
+    fn test_firing_weights_match_all_weights() {
+        let n = 50;
+
+        let w = Array::from_shape_fn((n, n), |(j, k)| if (j * 7 + k * 3) % 4 == 0 { 1.0 + (j % 5) as f32 * 0.1 } else { 0.0 });
+        let neuron_type = Array::from_shape_fn(n, |k| if k % 5 == 0 { -1.0 } else { 1.0 });
+
+        let (mut pre, mut post) = (Spikes::new(n), Spikes::new(n));
+        for i in 0..n {
+            pre.data[i] = i % 3 == 0;
+            post.data[i] = i % 4 == 1;
+        }
+
+        // A change on spikes that differs for each connection
+        let dw = |_, k: usize, j: usize| {
+            let mut dw = 0.0;
+
+            if pre.data[k] { dw -= 0.01 * (j + 1) as f32; }
+            if post.data[j] { dw += 0.02 * (k + 1) as f32; }
+
+            dw
+        };
+
+        let mut all = CsrRepresentation::new(w.clone(), neuron_type.clone());
+        update_weights(&mut all, 2.0, dw);
+
+        let mut firing = CsrRepresentation::new(w, neuron_type);
+        update_firing_weights(&mut firing, &pre, &post, 2.0, dw);
+
+        assert_eq!(firing.matrix(), all.matrix());
+    }
+
+    #[test]
+    fn test_weights_are_bounded() {
+        let mut weights = single_connection(1.0);
+
+        update_weights(&mut weights, 2.0, |_, _, _| 5.0);
+        assert_eq!(weight(&weights), 2.0);
+
+        update_weights(&mut weights, 2.0, |_, _, _| -5.0);
+        assert_eq!(weight(&weights), 0.0);
+    }
+}
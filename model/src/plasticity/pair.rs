@@ -0,0 +1,158 @@
+//! Pair-based STDP with all-to-all spike interactions (Song, Miller & Abbott, 2000).
+
+use crate::spikes::Spikes;
+use crate::clock::Clock;
+use crate::plasticity::{LearningRule, RuleParameters, Trace, update_firing_weights};
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation};
+
+use serde::{Serialize, Deserialize};
+
+
+/*
+ * Each neuron keeps a trace of its spikes, x for presynaptic and y for postsynaptic
+ * neurons, decaying with time constants tau_plus and tau_minus. The weight of a
+ * connection k -> j changes as
+ *
+ *      dw = w_max * a_plus * x_k       if j fires
+ *      dw = -w_max * a_minus * y_j     if k fires
+ *
+ * so that a pre spike followed by a post spike potentiates the connection,
+ * and the reverse order depresses it.
+ */
+pub struct PairStdp {
+    params: PairStdpParameters,
+
+    x: Trace,
+    y: Trace,
+}
+
+impl LearningRule for PairStdp {
+    fn step(&mut self, pre: &Spikes, post: &Spikes, weights: &mut CsrRepresentation) {
+        let (n_to, n_from) = weights.shape();
+
+        self.x.decay(n_from);
+        self.y.decay(n_to);
+
+        self.x.add(pre);
+
+        let p = self.params;
+        let (x, y) = (&self.x.x, &self.y.x);
+
+        update_firing_weights(weights, pre, post, p.w_max, |_, k, j| {
+            let mut dw = 0.0;
+
+            if pre.data[k] {
+                dw -= p.a_minus * y[j];
+            }
+            if post.data[j] {
+                dw += p.a_plus * x[k];
+            }
+
+            p.w_max * dw
+        });
+
+        self.y.add(post);
+    }
+
+    fn reset(&mut self) {
+        self.x.reset();
+        self.y.reset();
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.x.set_clock(clock);
+        self.y.set_clock(clock);
+    }
+}
+
+impl PairStdp {
+    pub fn new(params: PairStdpParameters) -> PairStdp {
+        PairStdp {
+            x: Trace::new(params.tau_plus),
+            y: Trace::new(params.tau_minus),
+
+            params,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PairStdpParameters {
+    pub a_plus: f32,    // potentiation amplitude, relative to w_max
+    pub a_minus: f32,   // depression amplitude, relative to w_max
+    pub tau_plus: f32,  // time constant of the presynaptic trace (ms)
+    pub tau_minus: f32, // time constant of the postsynaptic trace (ms)
+    pub w_max: f32,     // upper bound on weights
+}
+
+impl Default for PairStdpParameters {
+    /// Slightly dominated by depression, which keeps the rule stable
+    fn default() -> Self {
+        PairStdpParameters {
+            a_plus: 0.005,
+            a_minus: 0.00525,
+            tau_plus: 20.0,
+            tau_minus: 20.0,
+            w_max: 10.0,
+        }
+    }
+}
+
+impl RuleParameters for PairStdpParameters {
+    fn to_vec(&self) -> Vec<f32> {
+        vec![self.a_plus, self.a_minus, self.tau_plus, self.tau_minus, self.w_max]
+    }
+
+    fn from_vec(v: &[f32]) -> Self {
+        PairStdpParameters {
+            a_plus: v[0],
+            a_minus: v[1],
+            tau_plus: v[2],
+            tau_minus: v[3],
+            w_max: v[4],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plasticity::tests::weight_change;
+
+    fn stdp() -> PairStdp {
+        PairStdp::new(PairStdpParameters::default())
+    }
+
+    #[test]
+    fn test_pair_stdp_window() {
+        let p = PairStdpParameters::default();
+
+        for lag in [1, 5, 20] {
+            let expected = p.w_max * p.a_plus * (-lag as f32 / p.tau_plus).exp();
+
+            let ltp = weight_change(&mut stdp(), lag, None);
+            let ltd = weight_change(&mut stdp(), -lag, None);
+
+            assert!((ltp - expected).abs() < 1e-2 * expected, "lag: {lag}, expected: {expected}, got {ltp}");
+            assert!(ltd < 0.0, "lag: -{lag}, expected depression, got {ltd}");
+        }
+
+        // The window decays with the time between spikes
+        assert!(weight_change(&mut stdp(), 5, None) > weight_change(&mut stdp(), 20, None));
+        assert!(weight_change(&mut stdp(), -5, None) < weight_change(&mut stdp(), -20, None));
+    }
+
+    #[test]
+    fn test_pair_stdp_clock() {
+        let p = PairStdpParameters::default();
+
+        // A 5 ms lag with a 0.5 ms step
+        let mut rule = stdp();
+        rule.set_clock(Clock::new(0.5, crate::clock::Integrator::Euler));
+
+        let ltp = weight_change(&mut rule, 10, None);
+        let expected = p.w_max * p.a_plus * (-5.0 / p.tau_plus).exp();
+
+        assert!((ltp - expected).abs() < 1e-2 * expected, "expected: {expected}, got {ltp}");
+    }
+}
@@ -0,0 +1,192 @@
+//! Reward-modulated STDP, where pair-based STDP is gated by a reward signal (Izhikevich, 2007).
+
+use crate::spikes::Spikes;
+use crate::clock::Clock;
+use crate::plasticity::{LearningRule, RuleParameters, Trace, update_weights};
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation};
+
+use serde::{Serialize, Deserialize};
+
+
+/*
+ * Rather than changing the weights directly, the changes given by pair-based STDP
+ * are accumulated in an eligibility trace c for each connection,
+ *
+ *      dc/dt = -c / tau_c + STDP
+ *
+ * Reward increments a dopamine signal d, which decays with time constant tau_d.
+ * The weights then change as
+ *
+ *      dw/dt = c * d
+ *
+ * so that only connections whose spike timing preceded the reward are reinforced.
+ */
+pub struct RewardModulatedStdp {
+    params: RewardModulatedStdpParameters,
+
+    x: Trace,
+    y: Trace,
+
+    /// Eligibility of each connection, indexed as the connections of the representation
+    c: Vec<f32>,
+    d: f32,
+
+    c_decay: f32,
+    d_decay: f32,
+    dt: f32,
+}
+
+impl LearningRule for RewardModulatedStdp {
+    fn step(&mut self, pre: &Spikes, post: &Spikes, weights: &mut CsrRepresentation) {
+        let (n_to, n_from) = weights.shape();
+
+        if self.c.len() != weights.connection_count() {
+            self.c = vec![0.0; weights.connection_count()];
+        }
+
+        self.x.decay(n_from);
+        self.y.decay(n_to);
+
+        self.x.add(pre);
+
+        let p = self.params;
+        let (x, y) = (&self.x.x, &self.y.x);
+        let (c, d) = (&mut self.c, self.d);
+
+        let (c_decay, dt) = (self.c_decay, self.dt);
+
+        update_weights(weights, p.w_max, |ix, k, j| {
+            c[ix] *= c_decay;
+
+            if pre.data[k] {
+                c[ix] -= p.a_minus * y[j];
+            }
+            if post.data[j] {
+                c[ix] += p.a_plus * x[k];
+            }
+
+            p.w_max * c[ix] * d * dt
+        });
+
+        self.y.add(post);
+
+        self.d *= self.d_decay;
+    }
+
+    fn reward(&mut self, r: f32) {
+        self.d += r;
+    }
+
+    fn reset(&mut self) {
+        self.x.reset();
+        self.y.reset();
+
+        self.c.fill(0.0);
+        self.d = 0.0;
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.x.set_clock(clock);
+        self.y.set_clock(clock);
+
+        self.c_decay = (-clock.dt / self.params.tau_c).exp();
+        self.d_decay = (-clock.dt / self.params.tau_d).exp();
+        self.dt = clock.dt;
+    }
+}
+
+impl RewardModulatedStdp {
+    pub fn new(params: RewardModulatedStdpParameters) -> RewardModulatedStdp {
+        let mut rule = RewardModulatedStdp {
+            x: Trace::new(params.tau_plus),
+            y: Trace::new(params.tau_minus),
+
+            c: Vec::new(),
+            d: 0.0,
+
+            c_decay: 0.0,
+            d_decay: 0.0,
+            dt: 0.0,
+
+            params,
+        };
+
+        rule.set_clock(Clock::default());
+        rule
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RewardModulatedStdpParameters {
+    pub a_plus: f32,    // potentiation amplitude of the eligibility trace
+    pub a_minus: f32,   // depression amplitude of the eligibility trace
+    pub tau_plus: f32,  // time constant of the presynaptic trace (ms)
+    pub tau_minus: f32, // time constant of the postsynaptic trace (ms)
+    pub tau_c: f32,     // time constant of the eligibility trace (ms)
+    pub tau_d: f32,     // time constant of the reward signal (ms)
+    pub w_max: f32,     // upper bound on weights
+}
+
+impl Default for RewardModulatedStdpParameters {
+    fn default() -> Self {
+        RewardModulatedStdpParameters {
+            a_plus: 0.005,
+            a_minus: 0.00525,
+            tau_plus: 20.0,
+            tau_minus: 20.0,
+            tau_c: 1000.0,
+            tau_d: 200.0,
+            w_max: 10.0,
+        }
+    }
+}
+
+impl RuleParameters for RewardModulatedStdpParameters {
+    fn to_vec(&self) -> Vec<f32> {
+        vec![self.a_plus, self.a_minus, self.tau_plus, self.tau_minus, self.tau_c, self.tau_d, self.w_max]
+    }
+
+    fn from_vec(v: &[f32]) -> Self {
+        RewardModulatedStdpParameters {
+            a_plus: v[0],
+            a_minus: v[1],
+            tau_plus: v[2],
+            tau_minus: v[3],
+            tau_c: v[4],
+            tau_d: v[5],
+            w_max: v[6],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plasticity::tests::weight_change;
+
+    fn rstdp() -> RewardModulatedStdp {
+        RewardModulatedStdp::new(RewardModulatedStdpParameters::default())
+    }
+
+    #[test]
+    fn test_reward_modulated_stdp() {
+        // Without reward, the weights are unchanged
+        assert_eq!(weight_change(&mut rstdp(), 5, None), 0.0);
+        assert_eq!(weight_change(&mut rstdp(), -5, None), 0.0);
+
+        // Reward reinforces the change given by the spike timing, and punishment reverses it
+        assert!(weight_change(&mut rstdp(), 5, Some(1.0)) > 0.0);
+        assert!(weight_change(&mut rstdp(), -5, Some(1.0)) < 0.0);
+        assert!(weight_change(&mut rstdp(), 5, Some(-1.0)) < 0.0);
+    }
+
+    #[test]
+    fn test_reward_is_cleared_on_reset() {
+        let mut rule = rstdp();
+
+        rule.reward(1.0);
+        rule.reset();
+
+        assert_eq!(weight_change(&mut rule, 5, None), 0.0);
+    }
+}
@@ -0,0 +1,176 @@
+//! Triplet STDP with all-to-all spike interactions (Pfister & Gerstner, 2006).
+
+use crate::spikes::Spikes;
+use crate::clock::Clock;
+use crate::plasticity::{LearningRule, RuleParameters, Trace, update_firing_weights};
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation};
+
+use serde::{Serialize, Deserialize};
+
+
+/*
+ * In addition to the pair traces r1 (pre) and o1 (post), each neuron keeps a slower
+ * trace, r2 (pre) and o2 (post), which makes the change in weight depend on the
+ * spikes preceding the pair. The weight of a connection k -> j changes as
+ *
+ *      dw = w_max * r1_k * (a2_plus + a3_plus * o2_j)      if j fires
+ *      dw = -w_max * o1_j * (a2_minus + a3_minus * r2_k)   if k fires
+ *
+ * where o2 and r2 are taken before being incremented by the current spike. This
+ * captures the dependence of plasticity on the firing rate, which a pair rule cannot.
+ */
+pub struct TripletStdp {
+    params: TripletStdpParameters,
+
+    r1: Trace,
+    r2: Trace,
+    o1: Trace,
+    o2: Trace,
+}
+
+impl LearningRule for TripletStdp {
+    fn step(&mut self, pre: &Spikes, post: &Spikes, weights: &mut CsrRepresentation) {
+        let (n_to, n_from) = weights.shape();
+
+        self.r1.decay(n_from);
+        self.r2.decay(n_from);
+        self.o1.decay(n_to);
+        self.o2.decay(n_to);
+
+        self.r1.add(pre);
+
+        let p = self.params;
+        let (r1, r2, o1, o2) = (&self.r1.x, &self.r2.x, &self.o1.x, &self.o2.x);
+
+        update_firing_weights(weights, pre, post, p.w_max, |_, k, j| {
+            let mut dw = 0.0;
+
+            if pre.data[k] {
+                dw -= o1[j] * (p.a2_minus + p.a3_minus * r2[k]);
+            }
+            if post.data[j] {
+                dw += r1[k] * (p.a2_plus + p.a3_plus * o2[j]);
+            }
+
+            p.w_max * dw
+        });
+
+        self.r2.add(pre);
+        self.o1.add(post);
+        self.o2.add(post);
+    }
+
+    fn reset(&mut self) {
+        self.r1.reset();
+        self.r2.reset();
+        self.o1.reset();
+        self.o2.reset();
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.r1.set_clock(clock);
+        self.r2.set_clock(clock);
+        self.o1.set_clock(clock);
+        self.o2.set_clock(clock);
+    }
+}
+
+impl TripletStdp {
+    pub fn new(params: TripletStdpParameters) -> TripletStdp {
+        TripletStdp {
+            r1: Trace::new(params.tau_plus),
+            r2: Trace::new(params.tau_x),
+            o1: Trace::new(params.tau_minus),
+            o2: Trace::new(params.tau_y),
+
+            params,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TripletStdpParameters {
+    pub a2_plus: f32,   // pair potentiation amplitude, relative to w_max
+    pub a3_plus: f32,   // triplet potentiation amplitude, relative to w_max
+    pub a2_minus: f32,  // pair depression amplitude, relative to w_max
+    pub a3_minus: f32,  // triplet depression amplitude, relative to w_max
+    pub tau_plus: f32,  // time constant of r1 (ms)
+    pub tau_minus: f32, // time constant of o1 (ms)
+    pub tau_x: f32,     // time constant of r2 (ms)
+    pub tau_y: f32,     // time constant of o2 (ms)
+    pub w_max: f32,     // upper bound on weights
+}
+
+impl Default for TripletStdpParameters {
+    /// All-to-all fit to visual cortex data, from Pfister & Gerstner (2006)
+    fn default() -> Self {
+        TripletStdpParameters {
+            a2_plus: 5e-10,
+            a3_plus: 6.2e-3,
+            a2_minus: 7e-3,
+            a3_minus: 2.3e-4,
+            tau_plus: 16.8,
+            tau_minus: 33.7,
+            tau_x: 101.0,
+            tau_y: 125.0,
+            w_max: 10.0,
+        }
+    }
+}
+
+impl RuleParameters for TripletStdpParameters {
+    fn to_vec(&self) -> Vec<f32> {
+        vec![self.a2_plus, self.a3_plus, self.a2_minus, self.a3_minus,
+            self.tau_plus, self.tau_minus, self.tau_x, self.tau_y, self.w_max]
+    }
+
+    fn from_vec(v: &[f32]) -> Self {
+        TripletStdpParameters {
+            a2_plus: v[0],
+            a3_plus: v[1],
+            a2_minus: v[2],
+            a3_minus: v[3],
+            tau_plus: v[4],
+            tau_minus: v[5],
+            tau_x: v[6],
+            tau_y: v[7],
+            w_max: v[8],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plasticity::tests::{single_connection, weight};
+
+    /// Change in weight from pairs of spikes at t_pre and t_post (ms), repeated at a frequency (Hz)
+    fn pairing(frequency: f32, t_pre: usize, t_post: usize) -> f32 {
+        let mut rule = TripletStdp::new(TripletStdpParameters::default());
+        let mut weights = single_connection(5.0);
+
+        let period = (1000.0 / frequency) as usize;
+
+        for t in 0..(60 * period) {
+            let mut pre = Spikes::new(2);
+            let mut post = Spikes::new(2);
+
+            pre.data[0] = t % period == t_pre;
+            post.data[1] = t % period == t_post;
+
+            rule.step(&pre, &post, &mut weights);
+        }
+
+        weight(&weights) - 5.0
+    }
+
+    #[test]
+    fn test_triplet_stdp_frequency_dependence() {
+        // Potentiation requires postsynaptic activity preceding the pair, and so increases with frequency
+        assert!(pairing(40.0, 0, 10) > pairing(1.0, 0, 10));
+        assert!(pairing(40.0, 0, 10) > 0.0, "got {}", pairing(40.0, 0, 10));
+
+        // At low frequencies, post-pre pairs are depressing
+        assert!(pairing(1.0, 10, 0) < 0.0, "got {}", pairing(1.0, 10, 0));
+    }
+}
@@ -0,0 +1,142 @@
+//! Non-spiking readout of the state of a network, as in liquid state machines.
+//!
+//! The state of the network is given by the firing rate of each neuron over a window,
+//! and the readout is a linear model fit to targets given at some of the task steps.
+
+use crate::record::{Record, RecordType};
+
+use utils::analysis::firing_rate;
+use utils::regression::{self, LinearModel};
+use utils::math::{self, ml};
+
+use ndarray::{Array2, Axis};
+
+use serde::{Serialize, Deserialize};
+
+
+const DEFAULT_WINDOW: usize = 20;
+
+/// Linear model fit to the state of the network
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum Readout {
+    /// Ridge regression, scored by the coefficient of determination (R²)
+    #[serde(rename = "ridge")]
+    Ridge { alpha: f32 },
+
+    /// Logistic regression of targets in [0, 1], scored by accuracy. A single target is
+    /// a binary class, while several targets are the one-hot encoding of a class.
+    #[serde(rename = "logistic")]
+    Logistic { alpha: f32, iterations: usize, learning_rate: f32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadoutConfig {
+    pub model: Readout,
+
+    /// Window (ms) over which spikes are averaged to give the state of the network
+    #[serde(default = "default_window")]
+    pub window: usize,
+
+    /// Fraction of the trials held out to score the readout. If 0, the readout is scored
+    /// on the samples it is fit to.
+    #[serde(default)]
+    pub test_fraction: f32,
+}
+
+fn default_window() -> usize {
+    DEFAULT_WINDOW
+}
+
+impl Readout {
+    /// Fits the readout to samples x (m x neurons) with targets y (m x outputs)
+    pub fn fit(&self, x: &Array2<f32>, y: &Array2<f32>) -> LinearModel {
+        match *self {
+            Readout::Ridge { alpha } => regression::ridge(x, y, alpha),
+            Readout::Logistic { alpha, iterations, learning_rate } =>
+                regression::logistic(x, y, alpha, iterations, learning_rate),
+        }
+    }
+
+    pub fn predict(&self, model: &LinearModel, x: &Array2<f32>) -> Array2<f32> {
+        match self {
+            Readout::Ridge {..} => model.predict(x),
+            Readout::Logistic {..} => model.predict(x).mapv(ml::sigmoid),
+        }
+    }
+
+    /// Scores a prediction against the targets, where 1 is a perfect prediction
+    pub fn score(&self, prediction: &Array2<f32>, y: &Array2<f32>) -> f32 {
+        assert!(prediction.shape() == y.shape());
+
+        match self {
+            Readout::Ridge {..} => {
+                let mean = y.mean_axis(Axis(0)).unwrap();
+
+                let residual = (prediction - y).mapv(|e| e * e).sum();
+                let total = (y - &mean).mapv(|e| e * e).sum();
+
+                if total == 0.0 { 0.0 } else { 1.0 - residual / total }
+            },
+            Readout::Logistic {..} => {
+                let correct = prediction.outer_iter().zip(y.outer_iter()).filter(|(p, y)| {
+                    if y.len() == 1 {
+                        (p[0] >= 0.5) == (y[0] >= 0.5)
+                    } else {
+                        math::max_index(p) == math::max_index(y)
+                    }
+                }).count();
+
+                correct as f32 / y.nrows() as f32
+            },
+        }
+    }
+}
+
+/// State of the network at the end of each task step, given by the firing rate (spikes per ms)
+/// of each neuron over the window, from a record of spikes at every network step.
+pub fn states(record: &Record, window: usize, steps_per_tick: usize) -> Array2<f32> {
+    let spikes = record.as_array(RecordType::Spikes).mapv(|s| s as u32);
+
+    let rates = firing_rate(spikes, window * steps_per_tick) * steps_per_tick as f32;
+
+    let last_steps: Vec<usize> = (steps_per_tick - 1..rates.nrows()).step_by(steps_per_tick).collect();
+
+    rates.select(Axis(0), &last_steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    #[test]
+    fn test_states_per_tick() {
+        let mut record = Record::new();
+
+        // Two neurons over two ticks of two steps each
+        for s in [[1.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 0.0]] {
+            record.log(RecordType::Spikes, array![s[0], s[1]]);
+        }
+
+        let x = states(&record, 1, 2);
+
+        assert_eq!(x, array![[2.0, 0.0], [0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_readout_scores() {
+        let x = array![[0.0, 1.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]];
+        let y = array![[1.0], [2.0], [3.0], [0.0]];
+
+        let ridge = Readout::Ridge { alpha: 1e-4 };
+        let model = ridge.fit(&x, &y);
+
+        assert!(ridge.score(&ridge.predict(&model, &x), &y) > 0.99);
+
+        let logistic = Readout::Logistic { alpha: 0.0, iterations: 0, learning_rate: 1.0 };
+
+        assert_eq!(logistic.score(&array![[0.9, 0.1], [0.2, 0.7]], &array![[1.0, 0.0], [1.0, 0.0]]), 0.5);
+    }
+}
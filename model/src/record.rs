@@ -1,10 +1,20 @@
-/// Record of the time-series evolution of a spiking network.
-///
-/// All data points are real vectors, stored as Array1<f32>.
+//! Record of the time-series evolution of a spiking network.
+//!
+//! All data points are real vectors, stored as Array1<f32>. What is recorded is selected
+//! by a RecordConfig, and records are either kept in memory by Record, or passed to
+//! a RecordSink as they are produced.
+
+pub mod binary;
+
+use utils::npy::NpzWriter;
 
 use ndarray::{Array, Array1, Array2};
 
+use serde::{Serialize, Deserialize};
+
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write, BufWriter};
 
 
 pub type RecordData = Array1<f32>;
@@ -58,13 +68,236 @@ impl Record {
 
         out
     }
+
+    /// Writes the record as a .npz archive, with one array per record type, where row t is step t.
+    /// Spikes are boolean, and are also given as (t, neuron) pairs in <type>_events.
+    pub fn save_npz(&self, path: &str) -> io::Result<()> {
+        let mut npz = NpzWriter::create(path)?;
+
+        for record_type in RecordType::ALL {
+            if self.get_ref(record_type).is_empty() {
+                continue;
+            }
+
+            let a = self.as_array(record_type);
+
+            if record_type.is_spikes() {
+                let events: Vec<u32> = a.indexed_iter()
+                    .filter(|(_, x)| **x != 0.0)
+                    .flat_map(|((t, i), _)| [t as u32, i as u32])
+                    .collect();
+
+                npz.add_array(record_type.name(), &a.mapv(|x| x != 0.0))?;
+                npz.add_array(&format!("{}_events", record_type.name()),
+                    &Array::from_shape_vec((events.len() / 2, 2), events).unwrap())?;
+            } else {
+                npz.add_array(record_type.name(), &a)?;
+            }
+        }
+
+        npz.finish()?;
+
+        Ok(())
+    }
+}
+
+impl RecordSink for Record {
+    fn write(&mut self, _t: usize, record_type: RecordType, data: &RecordData) -> io::Result<()> {
+        self.log(record_type, data.clone());
+
+        Ok(())
+    }
 }
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Deserialize, Serialize)]
 pub enum RecordType {
+    #[serde(rename = "spikes")]
     Spikes,
+    #[serde(rename = "potentials")]
     Potentials,
+    #[serde(rename = "input_spikes")]
     InputSpikes,
+    #[serde(rename = "output_spikes")]
     OutputSpikes,
+    #[serde(rename = "synaptic_current")]
     SynapticCurrent,
 }
+
+impl RecordType {
+    pub const ALL: [RecordType; 5] = [RecordType::Spikes, RecordType::Potentials,
+        RecordType::InputSpikes, RecordType::OutputSpikes, RecordType::SynapticCurrent];
+
+    pub fn is_spikes(&self) -> bool {
+        matches!(self, RecordType::Spikes | RecordType::InputSpikes | RecordType::OutputSpikes)
+    }
+
+    /// Whether the record has an entry per neuron of the network
+    pub fn is_per_neuron(&self) -> bool {
+        matches!(self, RecordType::Spikes | RecordType::Potentials | RecordType::SynapticCurrent)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordType::Spikes => "spikes",
+            RecordType::Potentials => "potentials",
+            RecordType::InputSpikes => "input_spikes",
+            RecordType::OutputSpikes => "output_spikes",
+            RecordType::SynapticCurrent => "synaptic_current",
+        }
+    }
+}
+
+/// Selects what is recorded from a network
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "UncheckedRecordConfig")]
+pub struct RecordConfig {
+    pub types: Vec<RecordType>,
+
+    /// Neurons to record spikes, potentials and synaptic current from; all if not set
+    pub neurons: Option<Vec<usize>>,
+
+    /// Only every k-th step is recorded
+    pub every: usize,
+}
+
+impl RecordConfig {
+    pub fn is_recorded_step(&self, t: usize) -> bool {
+        t.is_multiple_of(self.every)
+    }
+
+    /// Selects the recorded neurons from the data of a record
+    pub fn select(&self, record_type: RecordType, data: RecordData) -> RecordData {
+        match &self.neurons {
+            Some(neurons) if record_type.is_per_neuron() => neurons.iter().map(|i| data[*i]).collect(),
+            _ => data
+        }
+    }
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        RecordConfig {
+            types: all_types(),
+            neurons: None,
+            every: every_step(),
+        }
+    }
+}
+
+/// A record config as given in a config, which is rejected when every is 0
+#[derive(Deserialize)]
+struct UncheckedRecordConfig {
+    #[serde(default = "all_types")]
+    types: Vec<RecordType>,
+
+    #[serde(default)]
+    neurons: Option<Vec<usize>>,
+
+    #[serde(default = "every_step")]
+    every: usize,
+}
+
+impl TryFrom<UncheckedRecordConfig> for RecordConfig {
+    type Error = String;
+
+    fn try_from(c: UncheckedRecordConfig) -> Result<RecordConfig, String> {
+        if c.every == 0 {
+            return Err("every must be at least 1 step".to_string());
+        }
+
+        Ok(RecordConfig { types: c.types, neurons: c.neurons, every: c.every })
+    }
+}
+
+fn all_types() -> Vec<RecordType> {
+    RecordType::ALL.to_vec()
+}
+
+fn every_step() -> usize {
+    1
+}
+
+/// Receives records as they are produced, where t is the step of the network
+pub trait RecordSink {
+    fn write(&mut self, t: usize, record_type: RecordType, data: &RecordData) -> io::Result<()>;
+
+    /// Writes any buffered records
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Streams records as lines of comma-separated values, on the form type,t,x_0,x_1,...
+pub struct CsvSink<W: Write> {
+    writer: W,
+}
+
+impl CsvSink<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(CsvSink::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> CsvSink<W> {
+        CsvSink {
+            writer
+        }
+    }
+}
+
+impl<W: Write> RecordSink for CsvSink<W> {
+    fn write(&mut self, t: usize, record_type: RecordType, data: &RecordData) -> io::Result<()> {
+        write!(self.writer, "{},{t}", record_type.name())?;
+
+        for x in data {
+            write!(self.writer, ",{x}")?;
+        }
+
+        writeln!(self.writer)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    #[test]
+    fn test_record_config_select() {
+        let config = RecordConfig {
+            neurons: Some(vec![0, 2]),
+            every: 3,
+            ..RecordConfig::default()
+        };
+
+        assert_eq!(config.select(RecordType::Potentials, array![1.0, 2.0, 3.0]), array![1.0, 3.0]);
+        assert_eq!(config.select(RecordType::InputSpikes, array![1.0, 0.0]), array![1.0, 0.0]);
+
+        assert!(config.is_recorded_step(0) && config.is_recorded_step(3));
+        assert!(!config.is_recorded_step(1));
+    }
+
+    #[test]
+    fn test_record_config_rejects_every_0() {
+        let config = |every| UncheckedRecordConfig { types: all_types(), neurons: None, every };
+
+        assert!(RecordConfig::try_from(config(0)).is_err());
+        assert_eq!(RecordConfig::try_from(config(5)).unwrap().every, 5);
+    }
+
+    #[test]
+    fn test_csv_sink() {
+        let mut sink = CsvSink::new(Vec::new());
+
+        sink.write(0, RecordType::Spikes, &array![1.0, 0.0]).unwrap();
+        sink.write(2, RecordType::Potentials, &array![-65.0, 0.5]).unwrap();
+
+        assert_eq!(String::from_utf8(sink.writer).unwrap(), "spikes,0,1,0\npotentials,2,-65,0.5\n");
+    }
+}
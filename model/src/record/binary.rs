@@ -0,0 +1,304 @@
+//! Compact binary format for records, which can be streamed to disk and read back.
+
+use crate::record::{Record, RecordData, RecordType, RecordSink};
+
+use ndarray::Array;
+
+use std::fs::File;
+use std::io::{self, Read, Write, BufReader, BufWriter, ErrorKind};
+
+
+/// Extension of record files
+pub const EXTENSION: &str = "rec";
+
+const MAGIC: &[u8; 8] = b"SRSNNREC";
+const VERSION: u16 = 1;
+
+const DENSE: u8 = 0;
+const SPARSE: u8 = 1;
+
+/*
+ * A record file starts with the header
+ *
+ *      magic: [u8; 8] = "SRSNNREC", version: u16
+ *
+ * followed by a sequence of entries, one for each record of each step:
+ *
+ *      record type: u8, encoding: u8, t: u64, len: u32
+ *
+ * where the record type is the index into RecordType::ALL, and len is the size of the
+ * record. Spikes are sparse, and given as the number of firing neurons followed by
+ * their indices,
+ *
+ *      n: u32, [neuron: u32; n]
+ *
+ * while other records are dense, given as len values [f32; len].
+ *
+ * All values are little-endian.
+ */
+pub struct BinarySink<W: Write> {
+    writer: W,
+}
+
+impl BinarySink<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        BinarySink::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> BinarySink<W> {
+    /// Creates the sink, writing the header
+    pub fn new(mut writer: W) -> io::Result<BinarySink<W>> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+
+        Ok(BinarySink {
+            writer
+        })
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> RecordSink for BinarySink<W> {
+    fn write(&mut self, t: usize, record_type: RecordType, data: &RecordData) -> io::Result<()> {
+        let sparse = record_type.is_spikes();
+
+        self.writer.write_all(&[type_code(record_type), if sparse { SPARSE } else { DENSE }])?;
+        self.writer.write_all(&(t as u64).to_le_bytes())?;
+        self.writer.write_all(&(data.len() as u32).to_le_bytes())?;
+
+        if sparse {
+            let firing: Vec<u32> = data.iter().enumerate()
+                .filter(|(_, x)| **x != 0.0)
+                .map(|(i, _)| i as u32)
+                .collect();
+
+            self.writer.write_all(&(firing.len() as u32).to_le_bytes())?;
+
+            for i in firing {
+                self.writer.write_all(&i.to_le_bytes())?;
+            }
+        } else {
+            for x in data {
+                self.writer.write_all(&x.to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A single record of a step
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub t: usize,
+    pub record_type: RecordType,
+    pub data: RecordData,
+}
+
+/// Reads the entries of a record file in the order they were written
+pub struct BinaryReader<R: Read> {
+    reader: R,
+}
+
+impl BinaryReader<BufReader<File>> {
+    pub fn open(path: &str) -> io::Result<Self> {
+        BinaryReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> BinaryReader<R> {
+    /// Creates the reader, checking the header
+    pub fn new(mut reader: R) -> io::Result<BinaryReader<R>> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(invalid_data("not a record file".to_string()));
+        }
+
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+
+        if u16::from_le_bytes(version) != VERSION {
+            return Err(invalid_data(format!("unsupported record version {}", u16::from_le_bytes(version))));
+        }
+
+        Ok(BinaryReader {
+            reader
+        })
+    }
+
+    fn read_entry(&mut self) -> io::Result<Option<Entry>> {
+        let mut head = [0u8; 2];
+
+        // The file may only end between entries, before the first byte of the head
+        let n = loop {
+            match self.reader.read(&mut head[..1]) {
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                r => break r?,
+            }
+        };
+
+        if n == 0 {
+            return Ok(None);
+        }
+
+        self.reader.read_exact(&mut head[1..])?;
+
+        let record_type = *RecordType::ALL.get(head[0] as usize)
+            .ok_or_else(|| invalid_data(format!("unknown record type {}", head[0])))?;
+
+        let t = u64::from_le_bytes(self.read_bytes()?) as usize;
+        let len = self.read_u32()? as usize;
+
+        let data = match head[1] {
+            DENSE => {
+                let mut data = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    data.push(f32::from_le_bytes(self.read_bytes()?));
+                }
+
+                Array::from_vec(data)
+            },
+            SPARSE => {
+                let mut data = Array::zeros(len);
+
+                for _ in 0..self.read_u32()? {
+                    let i = self.read_u32()? as usize;
+
+                    *data.get_mut(i).ok_or_else(|| invalid_data(format!("neuron {i} out of bounds")))? = 1.0;
+                }
+
+                data
+            },
+            e => return Err(invalid_data(format!("unknown encoding {e}"))),
+        };
+
+        Ok(Some(Entry { t, record_type, data }))
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.read_bytes()?))
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        self.reader.read_exact(&mut bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+impl<R: Read> Iterator for BinaryReader<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<io::Result<Entry>> {
+        self.read_entry().transpose()
+    }
+}
+
+/// Writes an in-memory record, where the step of each entry is its index
+pub fn save(record: &Record, path: &str) -> io::Result<()> {
+    let mut sink = BinarySink::create(path)?;
+
+    for record_type in RecordType::ALL {
+        for (t, data) in record.get_ref(record_type).iter().enumerate() {
+            sink.write(t, record_type, data)?;
+        }
+    }
+
+    sink.flush()
+}
+
+/// Reads a record file into memory
+pub fn load(path: &str) -> io::Result<Record> {
+    let mut record = Record::new();
+
+    for entry in BinaryReader::open(path)? {
+        let entry = entry?;
+
+        record.log(entry.record_type, entry.data);
+    }
+
+    Ok(record)
+}
+
+fn type_code(record_type: RecordType) -> u8 {
+    RecordType::ALL.iter().position(|r| *r == record_type).unwrap() as u8
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            Entry { t: 0, record_type: RecordType::Spikes, data: array![0.0, 1.0, 0.0, 1.0] },
+            Entry { t: 0, record_type: RecordType::Potentials, data: array![-65.0, 30.0, -70.5, 30.0] },
+            Entry { t: 5, record_type: RecordType::OutputSpikes, data: array![0.0, 0.0] },
+            Entry { t: 5, record_type: RecordType::SynapticCurrent, data: array![0.25, -1.0, 0.0, 3.5] },
+        ]
+    }
+
+    fn write(entries: &[Entry]) -> Vec<u8> {
+        let mut sink = BinarySink::new(Vec::new()).unwrap();
+
+        for e in entries {
+            sink.write(e.t, e.record_type, &e.data).unwrap();
+        }
+
+        sink.into_inner()
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let bytes = write(&entries());
+
+        let read: Vec<Entry> = BinaryReader::new(bytes.as_slice()).unwrap()
+            .collect::<io::Result<_>>().unwrap();
+
+        assert_eq!(read, entries());
+    }
+
+    #[test]
+    fn test_spikes_are_sparse() {
+        let spikes = Entry { t: 0, record_type: RecordType::Spikes, data: Array::zeros(1000) };
+
+        // Header, entry header and spike count
+        assert_eq!(write(&[spikes]).len(), 10 + 14 + 4);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(BinaryReader::new(b"NOTARECORD".as_slice()).is_err());
+
+        // Truncated within an entry
+        let bytes = write(&entries());
+        let first = write(&entries()[..1]).len();
+
+        let entries: Vec<io::Result<Entry>> = BinaryReader::new(&bytes[..bytes.len() - 2]).unwrap().collect();
+
+        assert!(entries.last().unwrap().is_err());
+
+        // Truncated within the head of an entry
+        let read: Vec<io::Result<Entry>> = BinaryReader::new(&bytes[..first + 1]).unwrap().collect();
+
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[1].as_ref().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -4,8 +4,10 @@ use std::fmt;
 
 use num_traits::Num;
 
+use serde::{Serialize, Deserialize};
 
-#[derive(Clone, Debug)]
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Spikes {
     pub data: Array1<bool>
 }
@@ -81,6 +83,7 @@ mod tests {
         let s = Spikes::new(N);
 
         let a: Array1<f32> = Array::zeros(N);
-        assert_eq!(s.into(), a);
+        let b: Array1<f32> = (&s).into();
+        assert_eq!(b, a);
     }
 }
@@ -2,11 +2,19 @@ pub mod representation;
 pub mod basic;
 pub mod exponential;
 pub mod bi_exponential;
+pub mod conductance;
+pub mod tsodyks_markram;
 
 use crate::spikes::Spikes;
+use crate::clock::Clock;
+use crate::synapse::representation::CsrRepresentation;
+use crate::synapse::tsodyks_markram::ShortTermPlasticity;
 
 use ndarray::{Array1, Array2};
 
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
 /// A vector describing the neuron type of N neurons, where entry i is -1.0 if
 /// neuron i is inhibitory, or 1.0 if it is excitatory.
 pub type NeuronType = Array1<f32>;
@@ -14,10 +22,35 @@ pub type NeuronType = Array1<f32>;
 pub type SynapticPotential = Array1<f32>;
 
 pub trait Synapse {
+    /// The dynamic state of the synapse, such as traces and spikes in transit
+    type State: Clone + Serialize + DeserializeOwned;
+
     fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self;
-    fn step(&mut self, input: &Spikes) -> SynapticPotential;
+
+    /// Propagates the spikes of the presynaptic neurons, where v is the membrane
+    /// potential of each postsynaptic neuron.
+    fn step(&mut self, input: &Spikes, v: &Array1<f32>) -> SynapticPotential;
 
     fn shape(&self) -> (usize, usize);
 
     fn reset(&mut self);
+
+    fn snapshot(&self) -> Self::State;
+
+    /// Restores a state taken from a synapse with the same connections and clock
+    fn restore(&mut self, state: &Self::State);
+
+    /// Sets the step size and integrator used by the synapse
+    fn set_clock(&mut self, clock: Clock);
+
+    /// Sets the delay (ms) of each connection, where entry D_jk is the delay from neuron k to neuron j
+    fn set_delays(&mut self, delays: &Array2<u32>);
+
+    /// The weights that learning rules can modify, if the synapse supports plasticity
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation>;
+
+    /// Sets the short-term plasticity of each connection, for synapses that support it
+    fn set_short_term_plasticity(&mut self, _stp: &ShortTermPlasticity) {
+        log::warn!("Synapse does not support short-term plasticity, ignoring");
+    }
 }
@@ -1,6 +1,7 @@
 use crate::spikes::Spikes;
 use crate::synapse::{Synapse, SynapticPotential};
-use crate::synapse::representation::{SynapseRepresentation, MatrixRepresentation};
+use crate::clock::Clock;
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation, RepresentationState};
 
 use ndarray::{Array1, Array2};
 
@@ -10,7 +11,9 @@ pub struct BasicSynapse {
 }
 
 impl Synapse for BasicSynapse {
-    fn step(&mut self, input: &Spikes) -> SynapticPotential {
+    type State = RepresentationState;
+
+    fn step(&mut self, input: &Spikes, _v: &Array1<f32>) -> SynapticPotential {
         self.representation.step(input)
     }
 
@@ -19,12 +22,34 @@ impl Synapse for BasicSynapse {
     }
 
     fn reset(&mut self) {
-        // this does nothing, because the base synapse does not have state
+        // the base synapse does not have state, apart from spikes in transit
+        self.representation.reset();
+    }
+
+    fn snapshot(&self) -> RepresentationState {
+        self.representation.snapshot()
+    }
+
+    fn restore(&mut self, state: &RepresentationState) {
+        self.representation.restore(state);
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.representation.set_dt(clock.dt);
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        self.representation.set_delays(delays);
+    }
+
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation> {
+        // the representation is opaque, and so cannot be modified by learning rules
+        None
     }
 
     fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self {
         BasicSynapse {
-            representation: Box::new(MatrixRepresentation::new(w, neuron_type))
+            representation: Box::new(CsrRepresentation::new(w, neuron_type))
         }
     }
 }
@@ -47,18 +72,19 @@ impl BasicSynapse {
 
 #[test]
 fn test_create_and_step_base_synapse() {
+    use crate::synapse::representation::{MatrixRepresentation, MapRepresentation};
+
     let m = ndarray::array![[1.0, 2.0],[3.0,4.0]];
     let input = Spikes::new(2);
 
-    let mut a: BasicSynapse<MatrixRepresentation> =
-        BasicSynapse::<MatrixRepresentation>::from_matrix(m, ndarray::Array::zeros(2));
+    let mut a = BasicSynapse::new(m, ndarray::Array::ones(2));
 
-    let a_out = a.step(&input);
+    let a_out = a.step(&input, &ndarray::Array::zeros(2));
 
-    let mut b: BasicSynapse<MapRepresentation> =
-        BasicSynapse::<MapRepresentation>::from(&a);
+    let matrix_repr = MatrixRepresentation::new(ndarray::array![[1.0, 2.0],[3.0,4.0]], ndarray::Array::ones(2));
+    let mut b = BasicSynapse::from_repr(Box::new(MapRepresentation::from(&matrix_repr)));
 
-    let b_out = b.step(&input);
+    let b_out = b.step(&input, &ndarray::Array::zeros(2));
 
     assert_eq!(a_out, b_out);
 }
@@ -2,22 +2,30 @@
 
 use crate::spikes::Spikes;
 use crate::synapse::Synapse;
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation, RepresentationState};
+use crate::clock::Clock;
 
 use ndarray::{Array, Array1, Array2};
 
+use serde::{Serialize, Deserialize};
 
+
+/// Time constants (ms)
 const TAU_D: f32 = 5.0;
 const TAU_R: f32 = 8.0;
 
 pub struct BiExponentialSynapse {
-    w: Array2<f32>,
-    neuron_type: Array1<f32>,
+    representation: CsrRepresentation,
 
     s: Array1<f32>,
-    h: Array1<f32>
+    h: Array1<f32>,
+
+    clock: Clock,
 }
 
 impl Synapse for BiExponentialSynapse {
+    type State = BiExponentialState;
+
     fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self {
         let n_in = w.shape()[0];
 
@@ -25,28 +33,68 @@ impl Synapse for BiExponentialSynapse {
             s: Array::zeros(n_in),
             h: Array::zeros(n_in),
 
-            w,
-            neuron_type,
+            representation: CsrRepresentation::new(w, neuron_type),
+
+            clock: Clock::default(),
         }
     }
 
-    fn step(&mut self, input: &Spikes) -> Array1<f32> {
-        let ns = &input.into() * &self.neuron_type;
+    fn step(&mut self, input: &Spikes, _v: &Array1<f32>) -> Array1<f32> {
+        let n = self.s.shape()[0];
 
-        self.s = &self.s + ( -&self.s / TAU_D + &self.h);
+        let mut x = self.clock.integrator.step(&[self.s.to_owned(), self.h.to_owned()], self.clock.dt, |x| vec![
+            (Array::from_elem(n, -1.0 / TAU_D), x[1].to_owned()),
+            (Array::from_elem(n, -1.0 / TAU_R), Array::zeros(n)),
+        ]);
 
-        self.h = &self.h + ( -&self.h / TAU_R  + 1.0 / ( TAU_D + TAU_R) * self.w.dot(&ns));
+        self.s = x.remove(0);
+        self.h = x.remove(0) + 1.0 / (TAU_D + TAU_R) * self.representation.step(input);
 
         log::trace!("BiExponential: {}", self.s);
         self.s.clone()
     }
 
     fn shape(&self) -> (usize, usize) {
-        (self.w.shape()[0], self.w.shape()[1])
+        self.representation.shape()
     }
 
     fn reset(&mut self) {
-        self.s = Array::zeros(self.w.shape()[0]);
-        self.h = Array::zeros(self.w.shape()[0]);
+        self.s = Array::zeros(self.shape().0);
+        self.h = Array::zeros(self.shape().0);
+        self.representation.reset();
+    }
+
+    fn snapshot(&self) -> BiExponentialState {
+        BiExponentialState {
+            s: self.s.clone(),
+            h: self.h.clone(),
+            representation: self.representation.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, state: &BiExponentialState) {
+        self.s.assign(&state.s);
+        self.h.assign(&state.h);
+        self.representation.restore(&state.representation);
     }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.representation.set_dt(clock.dt);
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        self.representation.set_delays(delays);
+    }
+
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation> {
+        Some(&mut self.representation)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BiExponentialState {
+    pub s: Array1<f32>,
+    pub h: Array1<f32>,
+    pub representation: RepresentationState,
 }
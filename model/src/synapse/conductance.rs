@@ -0,0 +1,219 @@
+//! Conductance-based synapse with AMPA, NMDA and GABA_A receptors.
+
+use crate::spikes::Spikes;
+use crate::synapse::{Synapse, SynapticPotential};
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation, RepresentationState};
+use crate::clock::Clock;
+use crate::parallel::zip;
+
+use ndarray::{Array, Array1, Array2, Zip};
+
+use serde::{Serialize, Deserialize};
+
+
+/// Time constants of the conductances (ms)
+const TAU_AMPA: f32 = 5.0;
+const TAU_NMDA: f32 = 100.0;
+const TAU_GABA_A: f32 = 6.0;
+
+/// Reversal potentials (mV)
+const E_AMPA: f32 = 0.0;
+const E_NMDA: f32 = 0.0;
+const E_GABA_A: f32 = -70.0;
+
+/// NMDA conductance relative to the AMPA conductance of an excitatory spike
+const NMDA_RATIO: f32 = 0.25;
+
+/// Extracellular magnesium concentration (mM)
+const MG: f32 = 1.0;
+
+/// Conductances are scaled so that a spike of unit weight gives a unit of
+/// current at this potential (mV), comparable to the current-based synapses
+const V_REF: f32 = -55.0;
+
+/*
+ * Spikes from excitatory neurons increase the AMPA and NMDA conductances of their
+ * targets, while spikes from inhibitory neurons increase the GABA_A conductance.
+ * The synaptic current then depends on the potential v of the postsynaptic neuron,
+ *
+ *      I = g_ampa (E_ampa - v) + g_nmda B(v) (E_nmda - v) + g_gaba_a (E_gaba_a - v)
+ *
+ * where B(v) is the voltage-dependent magnesium block of the NMDA receptor
+ * (Jahr & Stevens, 1990). Inhibition is thereby shunting rather than subtractive,
+ * and reverses below E_gaba_a.
+ *
+ * Reversal potentials are given in mV, and so assume a neuron model in mV.
+ */
+pub struct ConductanceSynapse {
+    excitatory: CsrRepresentation,
+    inhibitory: CsrRepresentation,
+
+    g_ampa: Array1<f32>,
+    g_nmda: Array1<f32>,
+    g_gaba_a: Array1<f32>,
+
+    /// Decay of each conductance over a single step
+    decay: (f32, f32, f32),
+}
+
+impl Synapse for ConductanceSynapse {
+    type State = ConductanceState;
+
+    fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self {
+        let n_to = w.shape()[0];
+        let n_from = w.shape()[1];
+
+        assert!(n_from == neuron_type.shape()[0],
+        "weight_matrix.shape({:?} != neuron_type.shape({:?})", w.shape(), neuron_type.shape());
+
+        let is_excitatory = neuron_type.mapv(|t| if t > 0.0 { 1.0 } else { 0.0 });
+        let is_inhibitory = 1.0 - &is_excitatory;
+
+        ConductanceSynapse {
+            excitatory: CsrRepresentation::new(&w * &is_excitatory, Array::ones(n_from)),
+            inhibitory: CsrRepresentation::new(&w * &is_inhibitory, Array::ones(n_from)),
+
+            g_ampa: Array::zeros(n_to),
+            g_nmda: Array::zeros(n_to),
+            g_gaba_a: Array::zeros(n_to),
+
+            decay: Self::decay(Clock::default()),
+        }
+    }
+
+    fn step(&mut self, input: &Spikes, v: &Array1<f32>) -> SynapticPotential {
+        let exc = self.excitatory.step(input) / (E_AMPA - V_REF);
+        let inh = self.inhibitory.step(input) / (V_REF - E_GABA_A);
+
+        let decay = self.decay;
+
+        zip!(exc.len(), (g_ampa in &mut self.g_ampa, g_nmda in &mut self.g_nmda, g_gaba_a in &mut self.g_gaba_a,
+                &e in &exc, &i in &inh) {
+            *g_ampa = decay.0 * *g_ampa + e;
+            *g_nmda = decay.1 * *g_nmda + NMDA_RATIO * e;
+            *g_gaba_a = decay.2 * *g_gaba_a + i;
+        });
+
+        let i = Zip::from(&self.g_ampa).and(&self.g_nmda).and(&self.g_gaba_a).and(v)
+            .map_collect(|g_ampa, g_nmda, g_gaba_a, v|
+                g_ampa * (E_AMPA - v) + g_nmda * mg_block(*v) * (E_NMDA - v) + g_gaba_a * (E_GABA_A - v));
+
+        log::trace!("ConductanceSynapse: {}", i);
+        i
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        self.excitatory.shape()
+    }
+
+    fn reset(&mut self) {
+        self.g_ampa.fill(0.0);
+        self.g_nmda.fill(0.0);
+        self.g_gaba_a.fill(0.0);
+
+        self.excitatory.reset();
+        self.inhibitory.reset();
+    }
+
+    fn snapshot(&self) -> ConductanceState {
+        ConductanceState {
+            g_ampa: self.g_ampa.clone(),
+            g_nmda: self.g_nmda.clone(),
+            g_gaba_a: self.g_gaba_a.clone(),
+
+            excitatory: self.excitatory.snapshot(),
+            inhibitory: self.inhibitory.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, state: &ConductanceState) {
+        self.g_ampa.assign(&state.g_ampa);
+        self.g_nmda.assign(&state.g_nmda);
+        self.g_gaba_a.assign(&state.g_gaba_a);
+
+        self.excitatory.restore(&state.excitatory);
+        self.inhibitory.restore(&state.inhibitory);
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.decay = Self::decay(clock);
+
+        self.excitatory.set_dt(clock.dt);
+        self.inhibitory.set_dt(clock.dt);
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        self.excitatory.set_delays(delays);
+        self.inhibitory.set_delays(delays);
+    }
+
+    /// Only excitatory connections are plastic
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation> {
+        Some(&mut self.excitatory)
+    }
+}
+
+impl ConductanceSynapse {
+    fn decay(clock: Clock) -> (f32, f32, f32) {
+        (clock.decay(TAU_AMPA), clock.decay(TAU_NMDA), clock.decay(TAU_GABA_A))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConductanceState {
+    pub g_ampa: Array1<f32>,
+    pub g_nmda: Array1<f32>,
+    pub g_gaba_a: Array1<f32>,
+
+    pub excitatory: RepresentationState,
+    pub inhibitory: RepresentationState,
+}
+
+/// Fraction of NMDA receptors that are not blocked by magnesium at potential v (mV)
+fn mg_block(v: f32) -> f32 {
+    1.0 / (1.0 + MG / 3.57 * (-0.062 * v).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    /// Current into neuron 1 following a single spike from neuron 0, at a fixed potential
+    fn current(neuron_type: f32, v: f32) -> f32 {
+        let mut synapse = ConductanceSynapse::new(array![[0.0, 0.0], [1.0, 0.0]], array![neuron_type, 1.0]);
+
+        let input = Spikes { data: array![true, false] };
+
+        synapse.step(&input, &array![v, v])[1]
+    }
+
+    #[test]
+    fn test_current_depends_on_potential() {
+        // Excitatory currents shrink as the potential approaches the reversal potential
+        assert!(current(1.0, -70.0) > current(1.0, -50.0));
+        assert!(current(1.0, -50.0) > 0.0);
+        assert!(current(1.0, E_AMPA).abs() < 1e-6);
+
+        // Inhibitory currents grow as the neuron depolarizes, and reverse below the reversal potential
+        assert!(current(-1.0, -50.0) < current(-1.0, -65.0));
+        assert!(current(-1.0, -65.0) < 0.0);
+        assert!(current(-1.0, -80.0) > 0.0);
+    }
+
+    #[test]
+    fn test_unit_current_at_reference_potential() {
+        let nmda = NMDA_RATIO * mg_block(V_REF);
+
+        assert!((current(1.0, V_REF) - (1.0 + nmda)).abs() < 1e-5);
+        assert!((current(-1.0, V_REF) + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_mg_block() {
+        assert!(mg_block(-80.0) < 0.05);
+        assert!(mg_block(0.0) > 0.75);
+        assert!(mg_block(-40.0) < mg_block(-20.0));
+    }
+}
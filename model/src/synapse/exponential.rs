@@ -2,44 +2,90 @@
 
 use crate::spikes::Spikes;
 use crate::synapse::Synapse;
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation, RepresentationState};
+use crate::clock::Clock;
+use crate::parallel::zip;
 
 use ndarray::{Array, Array1, Array2};
 
+use serde::{Serialize, Deserialize};
 
 
+
+/// Time constant (ms)
 const TAU: f32 = 10.0;
 
 pub struct ExponentialSynapse {
-    w: Array2<f32>,
-    neuron_type: Array1<f32>,
+    representation: CsrRepresentation,
+
+    s: Array1<f32>,
 
-    s: Array1<f32>
+    /// Decay of s over a single step
+    decay: f32,
 }
 
 impl Synapse for ExponentialSynapse {
+    type State = ExponentialState;
+
     fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self {
         ExponentialSynapse {
             s: Array::zeros(w.shape()[0]),
 
-            w,
-            neuron_type,
+            representation: CsrRepresentation::new(w, neuron_type),
+
+            decay: Clock::default().decay(TAU),
         }
     }
 
-    fn step(&mut self, input: &Spikes) -> Array1<f32> {
-        let ns = &input.into() * &self.neuron_type;
+    fn step(&mut self, input: &Spikes, _v: &Array1<f32>) -> Array1<f32> {
+        let input = self.representation.step(input);
+        let decay = self.decay;
 
-        self.s = &self.s + ( -&self.s / TAU + self.w.dot(&ns));
+        zip!(input.len(), (s in &mut self.s, &i in &input) {
+            *s = decay * *s + i;
+        });
 
         log::trace!("ExponentialSynapse: {}", self.s);
         self.s.clone()
     }
 
     fn shape(&self) -> (usize, usize) {
-        (self.w.shape()[0], self.w.shape()[1])
+        self.representation.shape()
     }
 
     fn reset(&mut self) {
-        self.s = Array::zeros(self.w.shape()[0]);
+        self.s = Array::zeros(self.shape().0);
+        self.representation.reset();
+    }
+
+    fn snapshot(&self) -> ExponentialState {
+        ExponentialState {
+            s: self.s.clone(),
+            representation: self.representation.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, state: &ExponentialState) {
+        self.s.assign(&state.s);
+        self.representation.restore(&state.representation);
     }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.decay = clock.decay(TAU);
+        self.representation.set_dt(clock.dt);
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        self.representation.set_delays(delays);
+    }
+
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation> {
+        Some(&mut self.representation)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExponentialState {
+    pub s: Array1<f32>,
+    pub representation: RepresentationState,
 }
@@ -2,9 +2,12 @@
 
 use crate::spikes::Spikes;
 use crate::synapse::{SynapticPotential, NeuronType};
+use crate::clock::DEFAULT_DT;
 
 use ndarray::{Array, Array1, Array2};
 
+use serde::{Serialize, Deserialize};
+
 use std::collections::HashMap;
 use std::convert::From;
 
@@ -14,6 +17,36 @@ pub trait SynapseRepresentation {
 
     fn shape(&self) -> (usize, usize);
     fn connection_count(&self) -> usize;
+
+    /// Sets the delay (ms) of each connection, where entry D_jk is the delay from neuron k to neuron j.
+    /// By default, spikes are delivered on the next step, and so delays longer than 1 ms are not supported.
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        assert!(delays.iter().all(|d| *d <= 1), "representation does not support delays");
+    }
+
+    /// Sets the duration of a step (ms), which determines the delays in steps
+    fn set_dt(&mut self, _dt: f32) { }
+
+    /// Clears spikes in transit
+    fn reset(&mut self) { }
+
+    /// The weights and spikes in transit, for representations that change during a run
+    fn snapshot(&self) -> RepresentationState {
+        RepresentationState::default()
+    }
+
+    fn restore(&mut self, _state: &RepresentationState) { }
+}
+
+/// The state of a representation that can change during a run
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RepresentationState {
+    /// Weight of each connection, which can be changed by learning rules
+    pub weights: Vec<f32>,
+
+    /// Input to be delivered on the coming steps
+    pub queue: Array2<f32>,
+    pub t: usize,
 }
 
 /// Connections between neurons represented by an N x N matrix,
@@ -100,6 +133,316 @@ impl MapRepresentation {
     }
 }
 
+/// Connections stored in compressed sparse row (CSR) format, with one row per presynaptic neuron;
+/// the neurons that neuron k projects to are targets[offsets[k]..offsets[k+1]], in increasing order.
+///
+/// Only the rows of firing neurons are visited, so that a step is proportional to the
+/// number of spikes times the fan-out, rather than to the size of the weight matrix.
+///
+/// Spikes are delivered through a ring buffer holding the input to each neuron for the
+/// coming steps, so that each connection can have its own delay.
+pub struct CsrRepresentation {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<f32>,
+
+    /// Delay of each connection (ms)
+    delays: Vec<u32>,
+    /// Delay of each connection in steps; a delay of 1 delivers spikes on the next step
+    delay_steps: Vec<usize>,
+    /// Duration of a step (ms)
+    dt: f32,
+
+    /// Ring buffer of input to be delivered, where row (t + d) % len is delivered d steps from now
+    queue: Array2<f32>,
+    t: usize,
+
+    n_to: usize,
+    neuron_type: NeuronType,
+
+    /// Index of the connections to each neuron, built when first needed
+    incoming: Option<IncomingIndex>,
+}
+
+/// The connections to neuron j, as (from, ix), are sources[offsets[j]..offsets[j+1]]
+struct IncomingIndex {
+    offsets: Vec<usize>,
+    sources: Vec<(usize, usize)>,
+}
+
+impl IncomingIndex {
+    fn new(offsets: &[usize], targets: &[usize], n_to: usize) -> IncomingIndex {
+        let mut in_offsets = vec![0; n_to + 1];
+        for j in targets {
+            in_offsets[j + 1] += 1;
+        }
+
+        for j in 0..n_to {
+            in_offsets[j + 1] += in_offsets[j];
+        }
+
+        let mut next = in_offsets.clone();
+        let mut sources = vec![(0, 0); targets.len()];
+
+        for k in 0..offsets.len() - 1 {
+            for (ix, &j) in targets.iter().enumerate().take(offsets[k + 1]).skip(offsets[k]) {
+                sources[next[j]] = (k, ix);
+                next[j] += 1;
+            }
+        }
+
+        IncomingIndex { offsets: in_offsets, sources }
+    }
+}
+
+impl SynapseRepresentation for CsrRepresentation {
+    fn step(&mut self, input: &Spikes) -> SynapticPotential {
+        #[cfg(feature = "parallel")]
+        if crate::parallel::is_parallel(self.n_to) {
+            return self.par_step(input);
+        }
+
+        self.step_with(input, |_| 1.0)
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.n_to, self.neuron_type.len())
+    }
+
+    fn connection_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        assert!(delays.shape() == [self.n_to, self.neuron_type.len()],
+            "delay matrix has shape {:?}, expected {:?}", delays.shape(), self.shape());
+
+        for k in 0..self.neuron_type.len() {
+            for ix in self.offsets[k]..self.offsets[k + 1] {
+                self.delays[ix] = delays[[self.targets[ix], k]];
+            }
+        }
+
+        self.update_delay_steps();
+    }
+
+    fn set_dt(&mut self, dt: f32) {
+        self.dt = dt;
+
+        self.update_delay_steps();
+    }
+
+    fn reset(&mut self) {
+        self.queue.fill(0.0);
+        self.t = 0;
+    }
+
+    fn snapshot(&self) -> RepresentationState {
+        RepresentationState {
+            weights: self.weights.clone(),
+            queue: self.queue.clone(),
+            t: self.t,
+        }
+    }
+
+    /// Restores a state taken from a representation with the same connections, delays and step size
+    fn restore(&mut self, state: &RepresentationState) {
+        assert!(state.weights.len() == self.weights.len(),
+            "state has {} connections, expected {}", state.weights.len(), self.weights.len());
+        assert!(state.queue.shape() == self.queue.shape(),
+            "state has queue of shape {:?}, expected {:?}", state.queue.shape(), self.queue.shape());
+
+        self.weights.clone_from(&state.weights);
+        self.queue.assign(&state.queue);
+        self.t = state.t;
+    }
+}
+
+impl CsrRepresentation {
+    /// Creates the representation from an N x M matrix, where entry W_jk is the weight from neuron k to neuron j
+    pub fn new(weight_matrix: Array2<f32>, neuron_type: NeuronType) -> CsrRepresentation {
+        assert!(weight_matrix.shape()[1] == neuron_type.shape()[0],
+        "weight_matrix.shape({:?} != neuron_type.shape({:?})", weight_matrix.shape(), neuron_type.shape());
+
+        let mut offsets = vec![0];
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+
+        for column in weight_matrix.columns() {
+            for (j, w) in column.iter().enumerate() {
+                if *w != 0.0 {
+                    targets.push(j);
+                    weights.push(*w);
+                }
+            }
+
+            offsets.push(targets.len());
+        }
+
+        let n_to = weight_matrix.shape()[0];
+
+        CsrRepresentation {
+            delays: vec![0; targets.len()],
+            delay_steps: vec![1; targets.len()],
+            dt: DEFAULT_DT,
+
+            queue: Array::zeros((1, n_to)),
+            t: 0,
+
+            offsets,
+            targets,
+            weights,
+
+            n_to,
+            neuron_type,
+
+            incoming: None,
+        }
+    }
+
+    /// The weights as an N x M matrix, where entry W_jk is the weight from neuron k to neuron j
+    pub fn matrix(&self) -> Array2<f32> {
+        let mut m = Array::zeros(self.shape());
+
+        for k in 0..self.neuron_type.len() {
+            for ix in self.offsets[k]..self.offsets[k + 1] {
+                m[[self.targets[ix], k]] = self.weights[ix];
+            }
+        }
+
+        m
+    }
+
+    /// % of connections of all possible
+    pub fn density(&self) -> f32 {
+        self.connection_count() as f32 / (self.n_to * self.neuron_type.len()) as f32
+    }
+
+    /// Propagates the input, where the weight of each connection that transmits a spike is scaled
+    /// by efficacy(ix). The efficacy is evaluated once per transmitted spike, in order of connection.
+    pub fn step_with<F>(&mut self, input: &Spikes, mut efficacy: F) -> SynapticPotential
+        where F: FnMut(usize) -> f32
+    {
+        let len = self.queue.shape()[0];
+
+        for (k, _) in input.data.iter().enumerate().filter(|(_, s)| **s) {
+            let t = self.neuron_type[k];
+
+            for ix in self.offsets[k]..self.offsets[k + 1] {
+                let slot = (self.t + self.delay_steps[ix] - 1) % len;
+
+                self.queue[[slot, self.targets[ix]]] += self.weights[ix] * efficacy(ix) * t;
+            }
+        }
+
+        let now = self.t % len;
+
+        let output = self.queue.row(now).to_owned();
+        self.queue.row_mut(now).fill(0.0);
+
+        self.t += 1;
+
+        output
+    }
+
+    /// Propagates the input as step_with(input, |_| 1.0), with the targets split into blocks that
+    /// are handled in parallel. Each block finds its part of the targets of a firing neuron by binary
+    /// search, and the input to each target is summed in the same order as in the serial step.
+    #[cfg(feature = "parallel")]
+    fn par_step(&mut self, input: &Spikes) -> SynapticPotential {
+        use ndarray::parallel::prelude::*;
+
+        let len = self.queue.shape()[0];
+        let block = self.n_to.div_ceil(rayon::current_num_threads()).max(1);
+
+        let firing = input.firing();
+
+        let (offsets, targets, weights) = (&self.offsets, &self.targets, &self.weights);
+        let (delay_steps, neuron_type, t) = (&self.delay_steps, &self.neuron_type, self.t);
+
+        self.queue.axis_chunks_iter_mut(ndarray::Axis(1), block).into_par_iter().enumerate().for_each(|(b, mut queue)| {
+            let (start, end) = (b * block, b * block + queue.shape()[1]);
+
+            for &k in &firing {
+                let row = &targets[offsets[k]..offsets[k + 1]];
+
+                let from = offsets[k] + row.partition_point(|j| *j < start);
+                let to = offsets[k] + row.partition_point(|j| *j < end);
+
+                for ix in from..to {
+                    let slot = (t + delay_steps[ix] - 1) % len;
+
+                    queue[[slot, targets[ix] - start]] += weights[ix] * neuron_type[k];
+                }
+            }
+        });
+
+        let now = self.t % len;
+
+        let output = self.queue.row(now).to_owned();
+        self.queue.row_mut(now).fill(0.0);
+
+        self.t += 1;
+
+        output
+    }
+
+    pub fn neuron_type(&self) -> &NeuronType {
+        &self.neuron_type
+    }
+
+    /// Visits each connection as f(ix, from, to, weight), where ix is the index of the connection
+    pub fn for_each_connection_mut<F>(&mut self, mut f: F)
+        where F: FnMut(usize, usize, usize, &mut f32)
+    {
+        for k in 0..self.neuron_type.len() {
+            for ix in self.offsets[k]..self.offsets[k + 1] {
+                f(ix, k, self.targets[ix], &mut self.weights[ix]);
+            }
+        }
+    }
+
+    /// Visits each connection from neuron k as f(ix, from, to, weight)
+    pub fn for_each_outgoing_mut<F>(&mut self, k: usize, mut f: F)
+        where F: FnMut(usize, usize, usize, &mut f32)
+    {
+        for ix in self.offsets[k]..self.offsets[k + 1] {
+            f(ix, k, self.targets[ix], &mut self.weights[ix]);
+        }
+    }
+
+    /// Visits each connection to neuron j as f(ix, from, to, weight), in order of the
+    /// presynaptic neurons
+    pub fn for_each_incoming_mut<F>(&mut self, j: usize, mut f: F)
+        where F: FnMut(usize, usize, usize, &mut f32)
+    {
+        let incoming = self.incoming.get_or_insert_with(|| IncomingIndex::new(&self.offsets, &self.targets, self.n_to));
+
+        for (k, ix) in &incoming.sources[incoming.offsets[j]..incoming.offsets[j + 1]] {
+            f(*ix, *k, j, &mut self.weights[*ix]);
+        }
+    }
+
+    /// Converts the delays to steps, and resizes the queue to hold the longest delay.
+    /// Any spikes in transit are dropped.
+    fn update_delay_steps(&mut self) {
+        self.delay_steps = self.delays.iter()
+            .map(|d| ((*d as f32 / self.dt).round() as usize).max(1))
+            .collect();
+
+        let len = self.delay_steps.iter().max().copied().unwrap_or(1);
+
+        self.queue = Array::zeros((len, self.n_to));
+        self.t = 0;
+    }
+}
+
+impl From<&MatrixRepresentation> for CsrRepresentation {
+    fn from(item: &MatrixRepresentation) -> CsrRepresentation {
+        CsrRepresentation::new(item.weights.to_owned(), item.neuron_type.to_owned())
+    }
+}
+
 impl From<&MatrixRepresentation> for MapRepresentation {
     fn from(item: &MatrixRepresentation) -> MapRepresentation {
         let mut connections: HashMap<usize, Vec<(usize, f32)>> = HashMap::new();
@@ -145,7 +488,7 @@ mod tests {
         connections.get_mut(&1).unwrap().push((2, 0.8));
         connections.get_mut(&2).unwrap().push((0, 1.0));
 
-        MapRepresentation::new(connections, neuron_type)
+        MapRepresentation::from_map(connections, neuron_type)
     }
 
     fn get_test_input() -> Spikes {
@@ -187,4 +530,129 @@ mod tests {
 
         assert_eq!(map_output, matrix_output);
     }
+
+    #[test]
+    fn test_csr_repr_from_matrix_repr() {
+        let input = get_test_input();
+
+        let mut matrix_repr = get_test_matrix_repr();
+        let mut csr_repr = CsrRepresentation::from(&matrix_repr);
+
+        assert_eq!(csr_repr.connection_count(), matrix_repr.connection_count());
+        assert_eq!(csr_repr.matrix(), matrix_repr.weights);
+
+        assert_eq!(csr_repr.step(&input), matrix_repr.step(&input));
+    }
+
+    #[test]
+    fn test_csr_repr_non_square() {
+        let weights: Array2<f32> = array![[0.0, 2.0],
+                                          [1.0, 0.0],
+                                          [0.5, 0.8]];
+
+        let mut matrix_repr = MatrixRepresentation::new(weights.clone(), array![1.0, 1.0]);
+        let mut csr_repr = CsrRepresentation::new(weights, array![1.0, 1.0]);
+
+        let input = Spikes { data: array![true, true] };
+
+        assert_eq!(csr_repr.shape(), (3, 2));
+        assert_eq!(csr_repr.step(&input), matrix_repr.step(&input));
+    }
+
+    #[test]
+    fn test_csr_repr_delays() {
+        let matrix_repr = get_test_matrix_repr();
+        let mut csr_repr = CsrRepresentation::from(&matrix_repr);
+
+        // 0 -> 1 and 2 -> 0 are delayed by 3 ms, 0 -> 2 by 2 ms
+        let delays = array![[0, 1, 3],
+                            [3, 0, 0],
+                            [2, 1, 0]];
+        csr_repr.set_delays(&delays);
+
+        let mut outputs = vec![csr_repr.step(&get_test_input())];
+        for _ in 0..3 {
+            outputs.push(csr_repr.step(&Spikes::new(3)));
+        }
+
+        assert_eq!(outputs[0], array![0.0, 0.0, 0.0]);
+        assert_eq!(outputs[1], array![0.0, 0.0, 0.5]);
+        assert_eq!(outputs[2], array![-1.0, 1.0, 0.0]);
+        assert_eq!(outputs[3], array![0.0, 0.0, 0.0]);
+
+        // Delays are given in ms, so halving the step doubles the delay in steps
+        csr_repr.set_dt(0.5);
+
+        let mut outputs = vec![csr_repr.step(&get_test_input())];
+        for _ in 0..6 {
+            outputs.push(csr_repr.step(&Spikes::new(3)));
+        }
+
+        assert_eq!(outputs[3], array![0.0, 0.0, 0.5]);
+        assert_eq!(outputs[5], array![-1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_csr_repr_rows_and_columns() {
+        let mut csr_repr = CsrRepresentation::from(&get_test_matrix_repr());
+        let m = csr_repr.matrix();
+
+        for i in 0..3 {
+            let mut row = vec![];
+            csr_repr.for_each_outgoing_mut(i, |_, k, j, w| row.push((k, j, *w)));
+
+            let mut column = vec![];
+            csr_repr.for_each_incoming_mut(i, |_, k, j, w| column.push((k, j, *w)));
+
+            assert_eq!(row, (0..3).filter(|j| m[[*j, i]] != 0.0).map(|j| (i, j, m[[j, i]])).collect::<Vec<_>>());
+            assert_eq!(column, (0..3).filter(|k| m[[i, *k]] != 0.0).map(|k| (k, i, m[[i, k]])).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_csr_repr_restore_spikes_in_transit() {
+        let mut csr_repr = CsrRepresentation::from(&get_test_matrix_repr());
+        csr_repr.set_delays(&Array::from_elem((3, 3), 2));
+
+        csr_repr.step(&get_test_input());
+        let state = csr_repr.snapshot();
+
+        let expected = csr_repr.step(&Spikes::new(3));
+        assert!(expected.iter().any(|x| *x != 0.0));
+
+        csr_repr.reset();
+        csr_repr.restore(&state);
+
+        assert_eq!(csr_repr.step(&Spikes::new(3)), expected);
+    }
+
+    #[test]
+    fn test_csr_repr_step_matches_step_with() {
+        // Large enough to be propagated in parallel with the parallel feature
+        let n = crate::parallel::MIN_PARALLEL_LEN + 7;
+
+        let weights = Array::from_shape_fn((n, 20), |(j, k)| if (j * 7 + k) % 5 == 0 { (j % 11) as f32 * 0.1 } else { 0.0 });
+        let neuron_type = Array::from_shape_fn(20, |k| if k % 4 == 0 { -1.0 } else { 1.0 });
+        let delays = Array::from_shape_fn((n, 20), |(j, k)| ((j + k) % 3) as u32);
+
+        let mut csr_repr = CsrRepresentation::new(weights.clone(), neuron_type.clone());
+        let mut expected = CsrRepresentation::new(weights, neuron_type);
+
+        csr_repr.set_delays(&delays);
+        expected.set_delays(&delays);
+
+        for t in 0..5 {
+            let input = Spikes { data: Array::from_shape_fn(20, |k| (k + t) % 3 != 0) };
+
+            assert_eq!(csr_repr.step(&input), expected.step_with(&input, |_| 1.0));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matrix_repr_does_not_support_delays() {
+        let mut matrix_repr = get_test_matrix_repr();
+
+        matrix_repr.set_delays(&Array::from_elem((3, 3), 2));
+    }
 }
@@ -0,0 +1,341 @@
+//! Exponential synapse with short-term facilitation and depression (Tsodyks & Markram, 1997).
+
+use crate::spikes::Spikes;
+use crate::synapse::Synapse;
+use crate::synapse::representation::{SynapseRepresentation, CsrRepresentation, RepresentationState};
+use crate::clock::Clock;
+use crate::parallel::zip;
+
+use ndarray::{Array, Array1, Array2};
+
+use serde::{Serialize, Deserialize};
+
+
+/// Time constant (ms)
+const TAU: f32 = 10.0;
+
+/*
+ * Each connection has a fraction x of available resources, of which a fraction u is
+ * used by a spike. Between spikes, x recovers to 1 with time constant tau_rec, and u
+ * decays to U with time constant tau_facil. When the presynaptic neuron fires, after
+ * an interval dt since its previous spike,
+ *
+ *      u = U + u (1 - U) exp(-dt / tau_facil)
+ *      x = 1 + (x - 1) exp(-dt / tau_rec)
+ *
+ * the spike is transmitted with efficacy u * x, and x is reduced by u * x. A large
+ * U and tau_rec makes the connection depressing, while a small U and large tau_facil
+ * makes it facilitating.
+ */
+pub struct TsodyksMarkramSynapse {
+    representation: CsrRepresentation,
+
+    s: Array1<f32>,
+
+    /// Parameters of each connection, indexed as the connections of the representation
+    params: Vec<TsodyksMarkramParameters>,
+
+    u: Vec<f32>,
+    x: Vec<f32>,
+
+    /// Step of the last spike of each connection, if any
+    last_spike: Vec<Option<u64>>,
+    t: u64,
+
+    clock: Clock,
+    decay: f32,
+}
+
+impl Synapse for TsodyksMarkramSynapse {
+    type State = TsodyksMarkramState;
+
+    fn new(w: Array2<f32>, neuron_type: Array1<f32>) -> Self {
+        let representation = CsrRepresentation::new(w, neuron_type);
+        let n = representation.connection_count();
+
+        TsodyksMarkramSynapse {
+            s: Array::zeros(representation.shape().0),
+
+            params: vec![TsodyksMarkramParameters::STATIC; n],
+
+            u: vec![0.0; n],
+            x: vec![1.0; n],
+
+            last_spike: vec![None; n],
+            t: 0,
+
+            representation,
+
+            clock: Clock::default(),
+            decay: Clock::default().decay(TAU),
+        }
+    }
+
+    fn step(&mut self, input: &Spikes, _v: &Array1<f32>) -> Array1<f32> {
+        let (params, u, x, last_spike) = (&self.params, &mut self.u, &mut self.x, &mut self.last_spike);
+        let (t, dt) = (self.t, self.clock.dt);
+
+        let input = self.representation.step_with(input, |ix| {
+            let p = params[ix];
+            let interval = last_spike[ix].map_or(f32::INFINITY, |l| (t - l) as f32 * dt);
+
+            u[ix] = p.u + u[ix] * (1.0 - p.u) * recovery(interval, p.tau_facil);
+            x[ix] = 1.0 + (x[ix] - 1.0) * recovery(interval, p.tau_rec);
+
+            let efficacy = u[ix] * x[ix];
+
+            x[ix] -= efficacy;
+            last_spike[ix] = Some(t);
+
+            efficacy
+        });
+
+        let decay = self.decay;
+
+        zip!(input.len(), (s in &mut self.s, &i in &input) {
+            *s = decay * *s + i;
+        });
+        self.t += 1;
+
+        log::trace!("TsodyksMarkramSynapse: {}", self.s);
+        self.s.clone()
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        self.representation.shape()
+    }
+
+    fn reset(&mut self) {
+        self.s = Array::zeros(self.shape().0);
+
+        self.u.fill(0.0);
+        self.x.fill(1.0);
+        self.last_spike.fill(None);
+        self.t = 0;
+
+        self.representation.reset();
+    }
+
+    fn snapshot(&self) -> TsodyksMarkramState {
+        TsodyksMarkramState {
+            s: self.s.clone(),
+
+            u: self.u.clone(),
+            x: self.x.clone(),
+
+            last_spike: self.last_spike.clone(),
+            t: self.t,
+
+            representation: self.representation.snapshot(),
+        }
+    }
+
+    fn restore(&mut self, state: &TsodyksMarkramState) {
+        assert!(state.u.len() == self.u.len(),
+            "state has {} connections, expected {}", state.u.len(), self.u.len());
+
+        self.s.assign(&state.s);
+
+        self.u.clone_from(&state.u);
+        self.x.clone_from(&state.x);
+
+        self.last_spike.clone_from(&state.last_spike);
+        self.t = state.t;
+
+        self.representation.restore(&state.representation);
+    }
+
+    fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.decay = clock.decay(TAU);
+
+        self.representation.set_dt(clock.dt);
+    }
+
+    fn set_delays(&mut self, delays: &Array2<u32>) {
+        self.representation.set_delays(delays);
+    }
+
+    fn weights_mut(&mut self) -> Option<&mut CsrRepresentation> {
+        Some(&mut self.representation)
+    }
+
+    fn set_short_term_plasticity(&mut self, stp: &ShortTermPlasticity) {
+        assert!(stp.u.shape() == [self.shape().0, self.shape().1],
+            "short-term plasticity has shape {:?}, expected {:?}", stp.u.shape(), self.shape());
+
+        let params = &mut self.params;
+
+        self.representation.for_each_connection_mut(|ix, k, j, _| {
+            params[ix] = TsodyksMarkramParameters {
+                u: stp.u[[j, k]],
+                tau_rec: stp.tau_rec[[j, k]],
+                tau_facil: stp.tau_facil[[j, k]],
+            };
+        });
+
+        self.reset();
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TsodyksMarkramState {
+    pub s: Array1<f32>,
+
+    pub u: Vec<f32>,
+    pub x: Vec<f32>,
+
+    pub last_spike: Vec<Option<u64>>,
+    pub t: u64,
+
+    pub representation: RepresentationState,
+}
+
+/// Fraction of the deviation from the resting value that remains after an interval (ms)
+fn recovery(interval: f32, tau: f32) -> f32 {
+    if tau <= 0.0 {
+        return 0.0;
+    }
+
+    (-interval / tau).exp()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TsodyksMarkramParameters {
+    pub u: f32,         // utilization of resources by a spike
+    pub tau_rec: f32,   // recovery time constant (ms)
+    pub tau_facil: f32, // facilitation time constant (ms)
+}
+
+/// Synapse classes from Gupta, Wang & Markram (2000)
+impl TsodyksMarkramParameters {
+    pub const FACILITATING: TsodyksMarkramParameters = TsodyksMarkramParameters {
+        u: 0.16, tau_rec: 45.0, tau_facil: 376.0,
+    };
+
+    pub const DEPRESSING: TsodyksMarkramParameters = TsodyksMarkramParameters {
+        u: 0.25, tau_rec: 706.0, tau_facil: 21.0,
+    };
+
+    pub const PSEUDO_LINEAR: TsodyksMarkramParameters = TsodyksMarkramParameters {
+        u: 0.32, tau_rec: 144.0, tau_facil: 62.0,
+    };
+
+    /// Every spike is transmitted in full, as for the exponential synapse
+    pub const STATIC: TsodyksMarkramParameters = TsodyksMarkramParameters {
+        u: 1.0, tau_rec: 0.0, tau_facil: 0.0,
+    };
+}
+
+impl Default for TsodyksMarkramParameters {
+    fn default() -> Self {
+        Self::STATIC
+    }
+}
+
+/// Short-term plasticity parameters of each connection, where entry jk
+/// is the parameter of the connection from neuron k to neuron j
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ShortTermPlasticity {
+    pub u: Array2<f32>,
+    pub tau_rec: Array2<f32>,
+    pub tau_facil: Array2<f32>,
+}
+
+impl ShortTermPlasticity {
+    pub fn new(u: Array2<f32>, tau_rec: Array2<f32>, tau_facil: Array2<f32>) -> ShortTermPlasticity {
+        assert!(u.shape() == tau_rec.shape() && u.shape() == tau_facil.shape(),
+            "short-term plasticity parameters have different shapes");
+
+        ShortTermPlasticity {
+            u,
+            tau_rec,
+            tau_facil
+        }
+    }
+
+    /// The same parameters for every connection
+    pub fn uniform(shape: (usize, usize), p: TsodyksMarkramParameters) -> ShortTermPlasticity {
+        ShortTermPlasticity {
+            u: Array::from_elem(shape, p.u),
+            tau_rec: Array::from_elem(shape, p.tau_rec),
+            tau_facil: Array::from_elem(shape, p.tau_facil),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synapse::exponential::ExponentialSynapse;
+
+    use ndarray::array;
+
+    /// Input to neuron 1 from each of a train of spikes from neuron 0, with the given interval (ms)
+    fn efficacies(p: TsodyksMarkramParameters, interval: usize) -> Vec<f32> {
+        let mut synapse = TsodyksMarkramSynapse::new(array![[0.0, 0.0], [1.0, 0.0]], array![1.0, 1.0]);
+        synapse.set_short_term_plasticity(&ShortTermPlasticity::uniform((2, 2), p));
+
+        let mut efficacies = vec![];
+        let mut previous = 0.0;
+
+        for t in 0..(8 * interval) {
+            let input = Spikes { data: array![t % interval == 0, false] };
+
+            let s = synapse.step(&input, &Array::zeros(2))[1];
+
+            // The efficacy of the spike is the increase over the decayed input
+            if t % interval == 0 {
+                efficacies.push(s - previous * synapse.decay);
+            }
+
+            previous = s;
+        }
+
+        efficacies
+    }
+
+    #[test]
+    fn test_depressing_synapse() {
+        let e = efficacies(TsodyksMarkramParameters::DEPRESSING, 20);
+
+        assert!((e[0] - TsodyksMarkramParameters::DEPRESSING.u).abs() < 1e-6);
+        assert!(e.windows(2).all(|w| w[1] < w[0]), "{e:?}");
+    }
+
+    #[test]
+    fn test_facilitating_synapse() {
+        let e = efficacies(TsodyksMarkramParameters::FACILITATING, 20);
+
+        assert!((e[0] - TsodyksMarkramParameters::FACILITATING.u).abs() < 1e-6);
+        assert!(e[1] > e[0], "{e:?}");
+    }
+
+    #[test]
+    fn test_depression_recovers() {
+        let p = TsodyksMarkramParameters::DEPRESSING;
+
+        // Long intervals give the resources time to recover
+        let fast = efficacies(p, 20);
+        let slow = efficacies(p, 2000);
+
+        assert!(slow[1] > fast[1]);
+        assert!((slow[1] - p.u).abs() < 0.1 * p.u, "{slow:?}");
+    }
+
+    #[test]
+    fn test_static_synapse_is_exponential() {
+        let w = array![[0.0, 0.5], [1.0, 0.0]];
+        let t = array![1.0, -1.0];
+
+        let mut tm = TsodyksMarkramSynapse::new(w.clone(), t.clone());
+        let mut exponential = ExponentialSynapse::new(w, t);
+
+        for i in 0..50 {
+            let input = Spikes { data: array![i % 3 == 0, i % 2 == 0] };
+            let v = Array::zeros(2);
+
+            assert_eq!(tm.step(&input, &v), exponential.step(&input, &v));
+        }
+    }
+}
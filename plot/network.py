@@ -25,12 +25,30 @@ class Network():
         self.input_cm = parse_array2(data['input_cm'])
         self.input_w = parse_array2(data['input_w'])
 
+    @classmethod
+    def from_npz(cls, path):
+        """ Load a network saved with NetworkRepresentation::save_npz """
+        npz = np.load(path)
+
+        network = cls.__new__(cls)
+
+        network.n = npz['network_cm'].shape[0]
+        network.env = tuple(npz['env'])
+
+        network.network_cm = npz['network_cm']
+        network.network_w = npz['network_w']
+
+        network.input_cm = npz['input_cm']
+        network.input_w = npz['input_w']
+
+        return network
+
     def __repr__(self):
         return f"N(n: {self.n}, env: {self.env})"
 
 def args():
     if len(sys.argv) < 2:
-        print("usage: [NETWORK.json]\n\n")
+        print("usage: [NETWORK.json | NETWORK.npz]\n\n")
         assert(1+1 != 2)
 
     return sys.argv[1:]
@@ -50,14 +68,13 @@ def parse_graph(m):
 def run():
     files = args()
 
-    d = []
-    for path in files:
-        with open(path) as f:
-            d.append(json.load(f))
-
-    assert(len(d) == 1) # Expect a single network
+    assert(len(files) == 1) # Expect a single network
 
-    network = Network(d[0])
+    if files[0].endswith(".npz"):
+        network = Network.from_npz(files[0])
+    else:
+        with open(files[0]) as f:
+            network = Network(json.load(f))
 
     print(network.network_cm.shape)
 
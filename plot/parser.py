@@ -1,3 +1,7 @@
+# Parsing of the serde JSON layout of ndarray arrays. Records and networks can also be
+# saved as .npz archives (Record::save_npz, NetworkRepresentation::save_npz), which are
+# loaded directly with np.load.
+
 import numpy as np
 import json
 
@@ -29,3 +33,60 @@ def parse_array1(d):
         a[i] = data[i]
 
     return a
+
+# Record files, see model/src/record/binary.rs
+RECORD_MAGIC = b"SRSNNREC"
+RECORD_VERSION = 1
+
+RECORD_TYPES = ["spikes", "potentials", "input_spikes", "output_spikes", "synaptic_current"]
+
+def load_record(path):
+    """
+    Load a record file, returning a dict from record type to (t, data), where t is
+    the step of each row of data. Spike records are also given as (t, neuron)
+    events under the key "<type>_events".
+    """
+    with open(path, "rb") as f:
+        buf = f.read()
+
+    if buf[:8] != RECORD_MAGIC:
+        raise ValueError(f"{path}: not a record file")
+
+    version = np.frombuffer(buf, dtype="<u2", count=1, offset=8)[0]
+    if version != RECORD_VERSION:
+        raise ValueError(f"{path}: unsupported record version {version}")
+
+    steps = {}
+    rows = {}
+    events = {}
+
+    pos = 10
+    while pos < len(buf):
+        record_type = RECORD_TYPES[buf[pos]]
+        sparse = buf[pos + 1] == 1
+        t = int(np.frombuffer(buf, dtype="<u8", count=1, offset=pos + 2)[0])
+        n = int(np.frombuffer(buf, dtype="<u4", count=1, offset=pos + 10)[0])
+        pos += 14
+
+        if sparse:
+            k = int(np.frombuffer(buf, dtype="<u4", count=1, offset=pos)[0])
+            neurons = np.frombuffer(buf, dtype="<u4", count=k, offset=pos + 4)
+            pos += 4 + 4 * k
+
+            row = np.zeros(n, dtype=np.float32)
+            row[neurons] = 1.0
+
+            events.setdefault(record_type, []).extend((t, int(i)) for i in neurons)
+        else:
+            row = np.frombuffer(buf, dtype="<f4", count=n, offset=pos)
+            pos += 4 * n
+
+        steps.setdefault(record_type, []).append(t)
+        rows.setdefault(record_type, []).append(row)
+
+    record = {r: (np.array(steps[r]), np.stack(rows[r])) for r in rows}
+
+    for r in events:
+        record[r + "_events"] = np.array(events[r], dtype=np.uint32).reshape(-1, 2)
+
+    return record
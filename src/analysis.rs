@@ -1,8 +1,11 @@
 use crate::runnable::RunnableNetwork;
 
-use model::DefaultNetwork;
-use model::network::representation::{NetworkRepresentation, DefaultRepresentation};
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::SpikingNetwork;
+use model::network::representation::{NetworkRepresentation, Representation};
 use model::record::Record;
+use model::clock::Clock;
 
 use tasks::{Task, TaskEval};
 use tasks::task_runner::TaskRunner;
@@ -12,15 +15,23 @@ use ndarray::{s, Axis, Array, Array1, Array2};
 use graph::{Graph, GraphAnalysis};
 
 
-pub fn run_analysis<T: Task + TaskEval> (repr: &DefaultRepresentation, setups: &[T::Setup])
+pub fn run_analysis<T: Task + TaskEval, N: NeuronModel, S: Synapse> (repr: &Representation<N>, setups: &[T::Setup])
 -> Vec<Record> {
+    run_analysis_with_clock::<T, N, S>(repr, setups, Clock::default())
+}
+
+pub fn run_analysis_with_clock<T: Task + TaskEval, N: NeuronModel, S: Synapse> (
+    repr: &Representation<N>,
+    setups: &[T::Setup],
+    clock: Clock
+) -> Vec<Record> {
     log::debug!("Performing run analysis");
 
     let mut records = vec![];
 
     for s in setups {
         let task = T::new(s);
-        let mut runnable = RunnableNetwork::<DefaultNetwork>::build(repr);
+        let mut runnable = RunnableNetwork::<SpikingNetwork<N, S>>::build_with_clock(repr, clock);
         runnable.network.enable_recording();
 
         let mut runner = TaskRunner::new(task, &mut runnable);
@@ -32,7 +43,7 @@ pub fn run_analysis<T: Task + TaskEval> (repr: &DefaultRepresentation, setups: &
     records
 }
 
-pub fn analyze_network(r: &DefaultRepresentation) -> (Graph, GraphAnalysis) {
+pub fn analyze_network<N: NeuronModel>(r: &Representation<N>) -> (Graph, GraphAnalysis) {
     let graph: Graph = r.into();
     let graph_analysis = GraphAnalysis::analyze(&graph);
 
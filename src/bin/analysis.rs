@@ -1,19 +1,14 @@
-//! Spiking network analysis.
+//! Spiking network analysis, of either a saved network or a record file.
 
 use srsnn::analysis;
 use model::network::representation::DefaultRepresentation;
-use utils::data;
+use model::record::binary;
 
 use std::env;
 
 
-fn load_network() -> DefaultRepresentation {
-    let path = match parse_arg()  {
-        Some(s) => { s },
-        None => { println!("usage: analysis [path]"); std::process::exit(1); }
-    };
-
-    match data::load::<DefaultRepresentation>(path.as_str()) {
+fn load_network(path: String) -> DefaultRepresentation {
+    match DefaultRepresentation::load(path.as_str()) {
         Ok(r) => { r },
         Err(e) =>     { println!("error: {e}"); std::process::exit(1); }
     }
@@ -30,8 +25,28 @@ fn parse_arg() -> Option<String> {
 }
 
 
+fn analyze_record(path: String) {
+    let record = match binary::load(path.as_str()) {
+        Ok(r) => { r },
+        Err(e) => { println!("error: {e}"); std::process::exit(1); }
+    };
+
+    srsnn::plots::generate_plots(&record);
+    srsnn::plots::plot_run_spikes(&record, None);
+}
+
 fn main() {
-    let r = load_network();
+    let path = match parse_arg()  {
+        Some(s) => { s },
+        None => { println!("usage: analysis [path]"); std::process::exit(1); }
+    };
+
+    if path.ends_with(&format!(".{}", binary::EXTENSION)) {
+        analyze_record(path);
+        return;
+    }
+
+    let r = load_network(path);
 
     let (g, ga) = analysis::analyze_network(&r);
 
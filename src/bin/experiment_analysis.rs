@@ -8,9 +8,13 @@ use srsnn::config::BaseConfig;
 use srsnn::models::generator_model::GeneratorModel;
 use srsnn::models::generator::base::BaseModel;
 
+use srsnn::process::{self, SynapseFn};
 use srsnn::process::experiment::report::ExperimentReport;
 
 use model::network::representation::DefaultRepresentation;
+use model::synapse::Synapse;
+use model::clock::Clock;
+use model::record::Record;
 use evolution::stats::{OptimizationStatistics, Run};
 
 use tasks::{Task, TaskEval};
@@ -44,9 +48,50 @@ fn plot_individual_runs(stats: &OptimizationStatistics) {
 }
 
 
-/// Evaluates a network over a number of setups, returning the fitness and, optionally,  accuracy
-fn evaluate<T: Task + TaskEval> (repr: &DefaultRepresentation, setups: &[T::Setup]) -> (f32, Option<f32>) {
-    let results = eval::run_network_on_task::<T>(repr, setups);
+/// Clock that networks are validated with, as given by the config
+fn validation_clock<T: Task + TaskEval>() -> Clock {
+    config::get_config::<eval::MultiEvaluator<T>>().validation_clock()
+}
+
+/// Runs of a network over a number of setups
+struct TaskRuns<'a, T: Task> {
+    repr: &'a DefaultRepresentation,
+    setups: &'a [T::Setup],
+}
+
+impl<T: Task + TaskEval> SynapseFn for TaskRuns<'_, T> {
+    type Output = Vec<T::Result>;
+
+    fn call<S: Synapse>(self) -> Vec<T::Result> {
+        eval::run_network_on_task::<T, _, S>(self.repr, self.setups, validation_clock::<T>())
+    }
+}
+
+/// Recorded runs of a network over a number of setups
+struct RecordedRuns<'a, T: Task> {
+    repr: &'a DefaultRepresentation,
+    setups: &'a [T::Setup],
+}
+
+impl<T: Task + TaskEval> SynapseFn for RecordedRuns<'_, T> {
+    type Output = Vec<Record>;
+
+    fn call<S: Synapse>(self) -> Vec<Record> {
+        srsnn::analysis::run_analysis_with_clock::<T, _, S>(self.repr, self.setups, validation_clock::<T>())
+    }
+}
+
+/// Records the runs of a network over a number of setups, with the synapse of the config it was optimized with
+fn record_runs<T: Task + TaskEval>(repr: &DefaultRepresentation, setups: &[T::Setup], conf: &BaseConfig) -> Vec<Record> {
+    process::resolve_synapse(&conf.synapse, RecordedRuns::<T> { repr, setups })
+        .unwrap_or_else(|| panic!("Unknown synapse: {}", conf.synapse))
+}
+
+/// Evaluates a network over a number of setups, with the synapse of the config it was optimized with,
+/// returning the fitness and, optionally,  accuracy
+fn evaluate<T: Task + TaskEval> (repr: &DefaultRepresentation, setups: &[T::Setup], conf: &BaseConfig) -> (f32, Option<f32>) {
+    let results = process::resolve_synapse(&conf.synapse, TaskRuns::<T> { repr, setups })
+        .unwrap_or_else(|| panic!("Unknown synapse: {}", conf.synapse));
 
     let accuracy = T::accuracy(&results);
     let val = T::fitness(results);
@@ -73,14 +118,14 @@ fn base_model_parameters(p: &ParameterSet) {
     println!("input_t_cpm:\n{}", input_t_cpm);
 }
 
-fn analyze<T: Task + TaskEval>(stats: OptimizationStatistics) {
+fn analyze<T: Task + TaskEval>(stats: OptimizationStatistics, conf: &BaseConfig) {
     let mut best: Option<(f32, &DefaultRepresentation, &ParameterSet)> = None;
     for (i, r) in stats.runs.iter().enumerate() {
         let run_best = r.best();
         print!("[{i}] fitness: {} ", run_best.0);
 
         let validation_setups = T::eval_setups();
-        let (fitness, accuracy) = evaluate::<T>(run_best.1, &validation_setups);
+        let (fitness, accuracy) = evaluate::<T>(run_best.1, &validation_setups, conf);
         match accuracy {
             Some(acc) =>  {
                 println!("validation fitness: {:.3}, accuracy: {:.3}", fitness, acc);
@@ -117,7 +162,7 @@ fn analyze_report<T: Task + TaskEval>(report: ExperimentReport, validation_setup
 
     for (i, r) in report.stats.runs.iter().enumerate() {
         if let Some((fitness, network, ps)) = &r.best_network {
-            let (val, accuracy) = evaluate::<T>(&network, validation_setups);
+            let (val, accuracy) = evaluate::<T>(&network, validation_setups, &analysis.conf);
 
             let run = RunAnalysis {
                 run: r.clone(),
@@ -161,7 +206,7 @@ fn single_report<T: Task + TaskEval>(report: ExperimentReport) -> Vec<NetworkEva
     log::info!("{} validation setups", validation_setups.len());
 
     for (i, b) in runs.iter().enumerate() {
-        let (val, accuracy) = evaluate::<T>(&b.0.1, &validation_setups);
+        let (val, accuracy) = evaluate::<T>(&b.0.1, &validation_setups, &report.conf);
         //model_parameters(&b.0.2);
 
         evals.push( NetworkEval {
@@ -184,7 +229,7 @@ fn single_report<T: Task + TaskEval>(report: ExperimentReport) -> Vec<NetworkEva
     let (best_eval, best_repr) = (best.validation, best.network.clone());
 
     // Run best network
-    let record = &srsnn::analysis::run_analysis::<T>(&best_repr, &validation_setups)[0];
+    let record = &record_runs::<T>(&best_repr, &validation_setups, &report.conf)[0];
 
     let name = format!("{}_eval_{}", desc, best_eval);
     srsnn::plots::plot_run_spikes(&record, Some((name + ".png").as_str()));
@@ -284,6 +329,9 @@ fn get_avg_eval(mut analysis: ExperimentAnalysis) {
 fn main() {
     logger::init_logger(Some("debug".to_string()));
 
+    // Networks are validated with the clock of the config
+    config::base_config(None);
+
     let args: Vec<String> = match parse_args() {
         Some(a) => {a},
         None => {println!("Add arguments"); std::process::exit(0) }
@@ -370,7 +418,7 @@ mod alys {
             let (graph, graph_analysis) = srsnn::analysis::analyze_network(repr);
 
             let validation_setups = vec![TimeSeriesTask::<SinSeries>::eval_setups()[0].clone()];
-            let record = &srsnn::analysis::run_analysis::<TimeSeriesTask<SinSeries>>(&repr, &validation_setups)[0];
+            let record = &record_runs::<TimeSeriesTask<SinSeries>>(repr, &validation_setups, &report.conf)[0];
 
             let name = format!("{}_eval_{}", desc, val);
             srsnn::plots::plot_run_spikes(&record, Some((name.clone() + ".png").as_str()));
@@ -9,6 +9,9 @@ use std::cell::RefCell;
 const CONFIG_DIR: &str = "config/";
 const DEFAULT_CONFIG_PATH: &str = "config/default.toml";
 
+const DEFAULT_NEURON: &str = "izhikevich";
+const DEFAULT_SYNAPSE: &str = "exponential";
+
 thread_local! {
     static CONFIG: RefCell<Option<Config>> = RefCell::new(None);
 }
@@ -19,7 +22,24 @@ pub struct BaseConfig {
     pub process: String,
     pub task: String,
     pub model: String,
+
+    #[serde(default = "default_neuron")]
+    pub neuron: String,
+    #[serde(default = "default_synapse")]
+    pub synapse: String,
+
     pub log_level: Option<String>,
+
+    /// Seed of all random draws, so that a run can be reproduced. If not set, a random seed is used.
+    pub seed: Option<u64>,
+}
+
+fn default_neuron() -> String {
+    DEFAULT_NEURON.to_string()
+}
+
+fn default_synapse() -> String {
+    DEFAULT_SYNAPSE.to_string()
 }
 
 impl BaseConfig {
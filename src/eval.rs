@@ -12,39 +12,55 @@ use tasks::task_runner::{TaskRunner, Runnable};
 
 use evolution::Evaluate;
 
-use model::DefaultNetwork;
-use model::network::representation::DefaultRepresentation;
+use model::neuron::NeuronModel;
+use model::neuron::izhikevich::Izhikevich;
+use model::synapse::Synapse;
+use model::synapse::exponential::ExponentialSynapse;
+use model::network::SpikingNetwork;
+use model::network::representation::{NeuronDescription, Representation};
+use model::clock::Clock;
+use model::record::RecordType;
+use model::readout::{self, ReadoutConfig};
 
 use utils::math;
+use utils::random;
 use utils::config::Configurable;
 
+use ndarray::{Array2, Axis};
+
 use std::thread;
 use std::sync::Arc;
 use std::time::Instant;
+use std::marker::PhantomData;
 
 use crossbeam::queue::ArrayQueue;
 
 
-pub type Trial = (u32, DefaultRepresentation);
-pub type Evaluation = (u32, f32, DefaultRepresentation);
+/// A developed network, and the seed it was developed from by Model::develop_with_seed
+pub type Developed<N = Izhikevich> = (Representation<N>, u64);
+
+pub type Trial<N = Izhikevich> = (u32, Developed<N>);
+pub type Evaluation<N = Izhikevich> = (u32, f32, Developed<N>);
 
 /// Evaluates a network on one or more setups and returns the evaluation over them.
-pub fn evaluate_on_task<T: Task + TaskEval> (
-    repr: &DefaultRepresentation,
-    setups: &[T::Setup]
+pub fn evaluate_on_task<T: Task + TaskEval, N: NeuronModel, S: Synapse> (
+    repr: &Representation<N>,
+    setups: &[T::Setup],
+    clock: Clock
 ) -> f32 {
 
-    let results = run_network_on_task::<T>(repr, setups);
+    let results = run_network_on_task::<T, N, S>(repr, setups, clock);
 
     T::fitness(results)
 }
 
-pub fn run_network_on_task<T: Task> (
-    repr: &DefaultRepresentation,
-    setups: &[T::Setup]
+pub fn run_network_on_task<T: Task, N: NeuronModel, S: Synapse> (
+    repr: &Representation<N>,
+    setups: &[T::Setup],
+    clock: Clock
 ) -> Vec<T::Result> {
     let mut results: Vec<T::Result> = Vec::new();
-    let mut r = RunnableNetwork::<DefaultNetwork>::build(repr);
+    let mut r = RunnableNetwork::<SpikingNetwork<N, S>>::build_with_clock(repr, clock);
 
     for s in setups {
         let task = T::new(s);
@@ -60,24 +76,90 @@ pub fn run_network_on_task<T: Task> (
     results
 }
 
-#[derive(Clone)]
-pub struct MultiEvaluator<T: Task + TaskEval> {
+/// Evaluates a network by the score of a readout fit to its state, rather than by its output.
+///
+/// The readout is fit to the samples of all setups except the last test_fraction, which it is
+/// scored on. The evaluation is 100 times the score.
+pub fn evaluate_with_readout<T: Task + TaskEval, N: NeuronModel, S: Synapse> (
+    repr: &Representation<N>,
+    setups: &[T::Setup],
+    clock: Clock,
+    config: &ReadoutConfig
+) -> f32 {
+    let mut r = RunnableNetwork::<SpikingNetwork<N, S>>::build_with_clock(repr, clock);
+
+    r.network.enable_recording();
+    r.network.record_config.types = vec![RecordType::Spikes];
+
+    let n_test = (setups.len() as f32 * config.test_fraction).round() as usize;
+    assert!(n_test < setups.len(), "no setups left to fit the readout to");
+
+    let mut samples: Vec<(Array2<f32>, Array2<f32>)> = Vec::new();
+
+    for s in setups {
+        let targets = T::readout_targets(s).expect("task does not provide readout targets");
+
+        TaskRunner::new(T::new(s), &mut r).run();
+
+        let states = readout::states(&r.network.record, config.window, r.steps_per_tick);
+        let ticks: Vec<usize> = targets.iter().map(|(t, _)| *t).collect();
+
+        let x = states.select(Axis(0), &ticks);
+        let y = ndarray::stack(Axis(0), &targets.iter().map(|(_, y)| y.view()).collect::<Vec<_>>()).unwrap();
+
+        samples.push((x, y));
+
+        r.reset();
+    }
+
+    let concat = |samples: &[(Array2<f32>, Array2<f32>)]| -> (Array2<f32>, Array2<f32>) {
+        (ndarray::concatenate(Axis(0), &samples.iter().map(|s| s.0.view()).collect::<Vec<_>>()).unwrap(),
+         ndarray::concatenate(Axis(0), &samples.iter().map(|s| s.1.view()).collect::<Vec<_>>()).unwrap())
+    };
+
+    let (train, test) = samples.split_at(setups.len() - n_test);
+
+    let (x, y) = concat(train);
+    let model = config.model.fit(&x, &y);
+
+    let (x, y) = if test.is_empty() { (x, y) } else { concat(test) };
+
+    100.0 * config.model.score(&config.model.predict(&model, &x), &y)
+}
+
+/// Evaluates models on a task, building networks of neuron model N connected by synapse S.
+pub struct MultiEvaluator<T: Task + TaskEval, N: NeuronModel = Izhikevich, S: Synapse = ExponentialSynapse> {
     setup: EvalSetup<T>,
     config: EvalConfig,
+
+    network: PhantomData<fn() -> (N, S)>,
 }
 
-impl<M: Model, T: Task + TaskEval> Evaluate<M, DefaultRepresentation> for MultiEvaluator<T> {
-    fn eval(&mut self, models: &[(u32, &M)]) -> Vec<Evaluation> {
+impl<M, T, N, S> Evaluate<M, Developed<N>> for MultiEvaluator<T, N, S>
+where
+    M: Model<NeuronDescription<N>>,
+    T: Task + TaskEval,
+    N: NeuronModel,
+    S: Synapse,
+{
+    fn eval(&mut self, models: &[(u32, &M)]) -> Vec<Evaluation<N>> {
         let t0 = Instant::now();
 
         let n_samples = models.len() * self.config.trials;
 
-        let input_queue: Arc<ArrayQueue<Trial>> = Arc::new(ArrayQueue::new(n_samples));
-        let output_queue: Arc<ArrayQueue<Evaluation>> = Arc::new(ArrayQueue::new(n_samples));
+        // Each trial is evaluated with its own random stream, as the order in which the
+        // workers take the trials is not deterministic
+        let input_queue: Arc<ArrayQueue<(Trial<N>, u64)>> = Arc::new(ArrayQueue::new(n_samples));
+        let output_queue: Arc<ArrayQueue<Evaluation<N>>> = Arc::new(ArrayQueue::new(n_samples));
 
+        // Each trial develops its own network, from a seed that is kept with the evaluation
+        // such that the network can be developed again
         for m in models {
             for _ in 0..self.config.trials {
-                let _ = input_queue.push((m.0, m.1.develop()));
+                let dev_seed = random::derive_seed();
+                let developed = (m.1.develop_with_seed(dev_seed), dev_seed);
+
+                let _ = input_queue.push(((m.0, developed), random::derive_seed()));
             }
         }
         log::trace!("Developed {} models in {:.2}s", models.len(), t0.elapsed().as_secs_f32());
@@ -85,6 +167,8 @@ impl<M: Model, T: Task + TaskEval> Evaluate<M, DefaultRepresentation> for MultiE
         assert!(input_queue.len() == n_samples);
 
         let setup = (*(self.setup.get())).to_vec();
+        let clock = self.config.clock;
+        let readout = &self.config.readout;
 
         // Don't create more threads than there are objects to evaluate
         let n_threads = std::cmp::min(self.config.max_threads, models.len());
@@ -100,8 +184,13 @@ impl<M: Model, T: Task + TaskEval> Evaluate<M, DefaultRepresentation> for MultiE
                 let sref = &setup[..];
 
                 s.spawn(move || {
-                    while let Some(t) = iq.pop() {
-                        let eval = evaluate_on_task::<T>(&t.1, sref);
+                    while let Some((t, seed)) = iq.pop() {
+                        random::set_seed(seed);
+
+                        let eval = match readout {
+                            Some(r) => evaluate_with_readout::<T, N, S>(&t.1.0, sref, clock, r),
+                            None => evaluate_on_task::<T, N, S>(&t.1.0, sref, clock),
+                        };
 
                         let _ = oq.push((t.0, eval, t.1));
                     }
@@ -109,7 +198,7 @@ impl<M: Model, T: Task + TaskEval> Evaluate<M, DefaultRepresentation> for MultiE
             }
         });
 
-        let mut evals: Vec<Evaluation> = vec![];
+        let mut evals: Vec<Evaluation<N>> = vec![];
 
         while let Some(e) = output_queue.pop() {
             evals.push(e);
@@ -135,8 +224,8 @@ impl<M: Model, T: Task + TaskEval> Evaluate<M, DefaultRepresentation> for MultiE
     }
 }
 
-impl<T: Task + TaskEval> MultiEvaluator<T> {
-    pub fn new(config: EvalConfig, batch_config: Option<BatchConfig>, setups: Vec<T::Setup>) -> MultiEvaluator<T> {
+impl<T: Task + TaskEval, N: NeuronModel, S: Synapse> MultiEvaluator<T, N, S> {
+    pub fn new(config: EvalConfig, batch_config: Option<BatchConfig>, setups: Vec<T::Setup>) -> MultiEvaluator<T, N, S> {
         let setup = match batch_config {
             Some(bc) => EvalSetup::Batched(BatchSetup::new(setups, bc.batch_size)),
             None => EvalSetup::Base(setups)
@@ -144,11 +233,13 @@ impl<T: Task + TaskEval> MultiEvaluator<T> {
 
         MultiEvaluator {
             setup,
-            config
+            config,
+
+            network: PhantomData,
         }
     }
 
-    pub fn multitrial_evals(&self, mut e: Vec<Evaluation>, n_models: usize) -> Vec<Evaluation> {
+    pub fn multitrial_evals(&self, mut e: Vec<Evaluation<N>>, n_models: usize) -> Vec<Evaluation<N>> {
         e.sort_by_key(|x| x.0);
 
         let mut evals = vec![];
@@ -171,8 +262,28 @@ impl<T: Task + TaskEval> MultiEvaluator<T> {
     pub fn validation_setups(&self) -> &[T::Setup] {
         self.setup.validation_setups()
     }
+
+    pub fn validation_clock(&self) -> Clock {
+        self.config.validation_clock()
+    }
+
+    /// The readout networks are evaluated by, if any
+    pub fn readout(&self) -> Option<&ReadoutConfig> {
+        self.config.readout.as_ref()
+    }
+}
+
+impl<T: Task + TaskEval + Clone, N: NeuronModel, S: Synapse> Clone for MultiEvaluator<T, N, S> {
+    fn clone(&self) -> Self {
+        MultiEvaluator {
+            setup: self.setup.clone(),
+            config: self.config.clone(),
+
+            network: PhantomData,
+        }
+    }
 }
 
-impl<T: Task + TaskEval> Configurable for MultiEvaluator<T> {
+impl<T: Task + TaskEval, N: NeuronModel, S: Synapse> Configurable for MultiEvaluator<T, N, S> {
     type Config = EvalConfig;
 }
@@ -2,11 +2,30 @@ use serde::Deserialize;
 
 use utils::config::{Configurable, ConfigSection};
 
+use model::clock::Clock;
+use model::readout::ReadoutConfig;
+
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct EvalConfig {
     pub max_threads: usize,
     pub trials: usize,
+
+    /// Clock used when evaluating networks during optimization
+    #[serde(default)]
+    pub clock: Clock,
+
+    /// Clock used for validation, defaults to the evaluation clock
+    pub validation_clock: Option<Clock>,
+
+    /// If given, networks are evaluated by a readout fit to their state, rather than by their output
+    pub readout: Option<ReadoutConfig>,
+}
+
+impl EvalConfig {
+    pub fn validation_clock(&self) -> Clock {
+        self.validation_clock.unwrap_or(self.clock)
+    }
 }
 
 impl ConfigSection for EvalConfig {
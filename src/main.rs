@@ -30,14 +30,21 @@ fn parse_config_name_from_args() -> Option<String> {
 
 fn main() {
     let config_name = parse_config_name_from_args();
-    let config = base_config(config_name.clone());
+    let mut config = base_config(config_name.clone());
 
     init_logger(config.log_level.clone());
 
     log::info!("Using config: {}", config_name.unwrap_or("default".to_string()));
     log::info!("Package version: {}", env!("CARGO_PKG_VERSION"));
 
-    random::random_seed();
+    let seed = match config.seed {
+        Some(seed) => { random::set_seed(seed); seed },
+        None => random::random_seed(),
+    };
+    log::info!("Seed: {seed}");
+
+    // Stored with the config, so that reports give the seed of the run
+    config.seed = Some(seed);
 
     run_process(config);
 }
@@ -34,11 +34,14 @@ pub trait Generator: Configurable + Clone + Debug + Sync {
     fn params(config: &ModelConfig<Self>, env: &Environment) -> ParameterSet;
 
     fn default_output() -> Mask {
-        Mask { f: Arc::new( move |i, j| i == j ) }
+        Mask::new(Arc::new( move |i, j| i == j ))
     }
 
     /// 4 Izhikevich parameters + inhibitory flag
     const N_DYNAMICAL_PARAMETERS: usize = 5;
+
+    /// Whether the Izhikevich parameters of the neurons are evolved, rather than fixed
+    const EVOLVES_DYNAMICS: bool = false;
 }
 
 pub struct NetworkModel {
@@ -59,8 +62,6 @@ pub mod blk {
     pub mod weights {
         use super::*;
 
-        use rand::Rng;
-
         fn default() -> ValueSet {
             ValueSet { f: Arc::new(
                 move |_i, _j| 1.0
@@ -69,7 +70,7 @@ pub mod blk {
 
         fn uniform(max: f32) -> ValueSet {
             ValueSet { f: Arc::new(
-                move |_i, _j| rand::thread_rng().gen_range(0.0..max)
+                move |_i, _j| random::random_range((0.0, max))
             )}
         }
     }
@@ -78,7 +79,6 @@ pub mod blk {
         use super::*;
 
         use model::neuron::izhikevich::IzhikevichParameters;
-        use rand::Rng;
 
         use ndarray::{Array, Array2};
 
@@ -95,11 +95,11 @@ pub mod blk {
 
             NeuronSet {f: Arc::new(
                 move |_i| array![
-                    rand::thread_rng().gen_range(r[0].0..r[0].1),     // a
-                    rand::thread_rng().gen_range(r[1].0..r[1].1),     // b
-                    rand::thread_rng().gen_range(r[2].0..r[2].1),     // c
-                    rand::thread_rng().gen_range(r[3].0..r[3].1),     // d
-                    if rand::random::<f32>() > 0.5 { 1.0 } else { 0.0 }
+                    random::random_range((r[0].0, r[0].1)),     // a
+                    random::random_range((r[1].0, r[1].1)),     // b
+                    random::random_range((r[2].0, r[2].1)),     // c
+                    random::random_range((r[3].0, r[3].1)),     // d
+                    if random::random::<f32>() > 0.5 { 1.0 } else { 0.0 }
                 ]
             )}
         }
@@ -111,11 +111,11 @@ pub mod blk {
             let mut d: Array2<f32> = Array::zeros((k, 5));
 
             for mut row in d.rows_mut() {
-                row[0] = rand::thread_rng().gen_range(pr[0].0..pr[0].1);
-                row[1] = rand::thread_rng().gen_range(pr[1].0..pr[1].1);
-                row[2] = rand::thread_rng().gen_range(pr[2].0..pr[2].1);
-                row[3] = rand::thread_rng().gen_range(pr[3].0..pr[3].1);
-                row[4] = if rand::random::<f32>() > 0.5 { 1.0 } else { 0.0 };
+                row[0] = random::random_range((pr[0].0, pr[0].1));
+                row[1] = random::random_range((pr[1].0, pr[1].1));
+                row[2] = random::random_range((pr[2].0, pr[2].1));
+                row[3] = random::random_range((pr[3].0, pr[3].1));
+                row[4] = if random::random::<f32>() > 0.5 { 1.0 } else { 0.0 };
             }
 
             NeuronSet::from_value(d)
@@ -6,9 +6,11 @@ use crate::models::generator_model::ModelConfig;
 
 use csa::op::LabelFn;
 use csa::op::geometric::{CoordinateFn, Metric};
-use csa::{ConnectionSet, ValueSet, NeuronSet, NetworkSet};
+use csa::{ConnectionSet, ValueSet, NeuronSet, NetworkSet, StpSet};
 use csa::mask::Mask;
 
+use model::synapse::tsodyks_markram::TsodyksMarkramParameters;
+
 use utils::{math, random};
 use utils::config::{ConfigSection, Configurable};
 use utils::parameters::{Parameter, ParameterSet};
@@ -17,7 +19,7 @@ use utils::environment::Environment;
 use serde::Deserialize;
 
 use ndarray::{array, Array, Array1, Array2};
-use ndarray_rand::rand_distr::{Binomial, Distribution};
+use ndarray_rand::rand_distr::Binomial;
 
 use std::sync::Arc;
 
@@ -87,17 +89,21 @@ impl Generator for BaseModel {
 
         let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
 
-        let disc = csa::op::geometric::disc(config.model.distance_threshold, d);
+        let disc = csa::op::geometric::coordinate_disc(config.model.distance_threshold, coords.clone(), coords.clone());
 
         let mask = sbm_mask & disc;
 
-        let w = Self::minimal_weights(itypes, labels.clone(), config);
+        let w = Self::minimal_weights(itypes.clone(), labels.clone(), config);
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let mut ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
+
+        if let Some(scale) = config.model.delay_scale {
+            ns = ns.with_delays(csa::op::geometric::distance_delay(scale, d));
+        }
+
+        if let Some(stp) = &config.model.short_term_plasticity {
+            ns = ns.with_short_term_plasticity(Self::short_term_plasticity(stp, &itypes, labels.clone(), config));
+        }
 
         let input_cs = Self::input_cs(m2, labels.clone(), coords.clone(), config, env);
 
@@ -134,10 +140,41 @@ impl BaseModel {
 
         let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
         ValueSet { f: Arc::new(
-            move |_i, j| w_map[l(j) as usize] * (1 + bin.sample(&mut rand::thread_rng())) as f32
+            move |_i, j| w_map[l(j) as usize] * (1 + random::random_sample(bin)) as f32
         )}
     }
 
+    /// Value sets of U, tau_rec and tau_facil, assigned by the type of both neurons
+    fn short_term_plasticity(stp: &TypeStpConfig, itypes: &[usize], l: LabelFn, config: &ModelConfig<Self>)
+        -> StpSet {
+        let n_types = config.model.k + config.model.k_out;
+
+        if let Some(by_type) = &stp.by_type {
+            assert!(by_type.len() == n_types && by_type.iter().all(|r| r.len() == n_types),
+                "short-term plasticity by_type must be {n_types} x {n_types}, one entry per pair of types");
+        }
+
+        // Entry (a, b) holds the parameters of connections from type b to type a
+        let p: Array2<TsodyksMarkramParameters> = Array::from_shape_fn((n_types, n_types), |(a, b)| {
+            if let Some(by_type) = &stp.by_type {
+                return by_type[a][b];
+            }
+
+            match (itypes.contains(&b), itypes.contains(&a)) {
+                (false, false) => stp.ee,
+                (false, true) => stp.ei,
+                (true, false) => stp.ie,
+                (true, true) => stp.ii,
+            }
+        });
+
+        StpSet {
+            u: csa::op::v_group(l.clone(), ValueSet::from_value(p.mapv(|p| p.u))),
+            tau_rec: csa::op::v_group(l.clone(), ValueSet::from_value(p.mapv(|p| p.tau_rec))),
+            tau_facil: csa::op::v_group(l, ValueSet::from_value(p.mapv(|p| p.tau_facil))),
+        }
+    }
+
     fn static_dynamics(l: LabelFn, config: &ModelConfig<Self>) -> (NeuronSet, Vec<usize>) {
         let (td, itypes) = Self::type_dynamics(config);
 
@@ -176,15 +213,13 @@ impl BaseModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
 
         let max_x = config.model.max_coordinate;
-        let g_in: CoordinateFn = Arc::new(move |_| (rand::random::<f32>() * max_x, 0.0));
+        let g_in: CoordinateFn = Arc::new(move |_| (random::random::<f32>() * max_x, 0.0));
         let d: Metric = csa::op::geometric::distance_metric(g, g_in);
 
         let input_mask = m & csa::op::geometric::disc(config.model.distance_threshold, d);
@@ -201,7 +236,7 @@ impl BaseModel {
 fn weights(w: f32) -> ValueSet {
     let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
     ValueSet { f: Arc::new(
-        move |_i, _j| w * (1 + bin.sample(&mut rand::thread_rng())) as f32
+        move |_i, _j| w * (1 + random::random_sample(bin)) as f32
     )}
 }
 
@@ -219,11 +254,47 @@ pub struct BaseModelConfig {
     pub distance_threshold: f32,
     pub max_coordinate: f32,
 
+    /// Transmission delay (ms) per unit of distance; if not set, spikes arrive on the next step
+    #[serde(default)]
+    pub delay_scale: Option<f32>,
+
+    #[serde(default)]
+    pub short_term_plasticity: Option<TypeStpConfig>,
+
     pub input_w: f32,
     pub exc_w: f32,
     pub inh_w: f32,
 }
 
+/// Short-term plasticity of the connections between excitatory (e) and inhibitory (i) types,
+/// where ei is the connections from excitatory to inhibitory types.
+///
+/// If by_type is given, it sets the parameters of each pair of types instead, where entry
+/// [a][b] is the connections from type b to type a, over the k types followed by the k_out
+/// output types.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TypeStpConfig {
+    #[serde(default)]
+    pub by_type: Option<Vec<Vec<TsodyksMarkramParameters>>>,
+
+    #[serde(default = "depressing")]
+    pub ee: TsodyksMarkramParameters,
+    #[serde(default = "facilitating")]
+    pub ei: TsodyksMarkramParameters,
+    #[serde(default = "depressing")]
+    pub ie: TsodyksMarkramParameters,
+    #[serde(default = "depressing")]
+    pub ii: TsodyksMarkramParameters,
+}
+
+fn depressing() -> TsodyksMarkramParameters {
+    TsodyksMarkramParameters::DEPRESSING
+}
+
+fn facilitating() -> TsodyksMarkramParameters {
+    TsodyksMarkramParameters::FACILITATING
+}
+
 impl ConfigSection for BaseModelConfig {
     fn name() -> String {
         "base_model".to_string()
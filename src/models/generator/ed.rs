@@ -19,7 +19,7 @@ use utils::environment::Environment;
 use serde::Deserialize;
 
 use ndarray::{s, array, Array, Array1, Array2};
-use ndarray_rand::rand_distr::{Binomial, Distribution};
+use ndarray_rand::rand_distr::Binomial;
 
 use std::sync::Arc;
 
@@ -39,6 +39,8 @@ const WT_DRAW_PROB: f64 = 0.2;
 pub struct EvolvedDynamicsModel;
 
 impl Generator for EvolvedDynamicsModel {
+    const EVOLVES_DYNAMICS: bool = true;
+
     fn params(config: &ModelConfig<Self>, env: &Environment) -> ParameterSet {
         assert!(env.inputs % config.model.k_in == 0,
             "Number of input neurons({}) % input types({}) != 0", env.inputs, config.model.k_in);
@@ -93,19 +95,13 @@ impl Generator for EvolvedDynamicsModel {
             config.model.max_coordinate,
             config.n + env.outputs);
 
-        let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
-
-        let disc = csa::op::geometric::disc(config.model.distance_threshold, d);
+        let disc = csa::op::geometric::coordinate_disc(config.model.distance_threshold, coords.clone(), coords.clone());
 
         let mask = sbm_mask & disc;
 
         let w = Self::minimal_weights(itypes, labels.clone(), config);
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
 
         let input_cs = Self::input_cs(m2, labels.clone(), coords.clone(), config, env);
 
@@ -156,7 +152,7 @@ impl EvolvedDynamicsModel {
 
         let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
         ValueSet { f: Arc::new(
-            move |_i, j| w_map[l(j) as usize] * (1 + bin.sample(&mut rand::thread_rng())) as f32
+            move |_i, j| w_map[l(j) as usize] * (1 + random::random_sample(bin)) as f32
         )}
     }
 
@@ -218,15 +214,13 @@ impl EvolvedDynamicsModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
 
         let max_x = config.model.max_coordinate;
-        let g_in: CoordinateFn = Arc::new(move |_| (rand::random::<f32>() * max_x, 0.0));
+        let g_in: CoordinateFn = Arc::new(move |_| (random::random::<f32>() * max_x, 0.0));
         let d: Metric = csa::op::geometric::distance_metric(g, g_in);
 
         let input_mask = m & csa::op::geometric::disc(config.model.distance_threshold, d);
@@ -243,7 +237,7 @@ impl EvolvedDynamicsModel {
 fn weights(w: f32) -> ValueSet {
     let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
     ValueSet { f: Arc::new(
-        move |_i, _j| w * (1 + bin.sample(&mut rand::thread_rng())) as f32
+        move |_i, _j| w * (1 + random::random_sample(bin)) as f32
     )}
 }
 
@@ -20,7 +20,7 @@ use utils::environment::Environment;
 use serde::Deserialize;
 
 use ndarray::{s, array, Array, Array1, Array2};
-use ndarray_rand::rand_distr::{Binomial, Distribution};
+use ndarray_rand::rand_distr::Binomial;
 
 use std::sync::Arc;
 
@@ -40,6 +40,8 @@ const WT_DRAW_PROB: f64 = 0.2;
 pub struct EDDModel;
 
 impl Generator for EDDModel {
+    const EVOLVES_DYNAMICS: bool = true;
+
     fn params(config: &ModelConfig<Self>, env: &Environment) -> ParameterSet {
         assert!(env.inputs % config.model.k_in == 0,
             "Number of input neurons({}) % input types({}) != 0", env.inputs, config.model.k_in);
@@ -92,19 +94,13 @@ impl Generator for EDDModel {
             config.model.max_coordinate,
             config.n + env.outputs);
 
-        let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
-
-        let disc = csa::op::geometric::disc(config.model.distance_threshold, d);
+        let disc = csa::op::geometric::coordinate_disc(config.model.distance_threshold, coords.clone(), coords.clone());
 
         let mask = sbm_mask & disc;
 
         let w = Self::minimal_weights(itypes, labels.clone(), config);
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
 
         let input_cs = Self::input_cs(m2, labels.clone(), coords.clone(), config, env);
 
@@ -148,7 +144,7 @@ impl EDDModel {
 
         let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
         ValueSet { f: Arc::new(
-            move |_i, j| w_map[l(j) as usize] * (1 + bin.sample(&mut rand::thread_rng())) as f32
+            move |_i, j| w_map[l(j) as usize] * (1 + random::random_sample(bin)) as f32
         )}
     }
 
@@ -258,15 +254,13 @@ impl EDDModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
 
         let max_x = config.model.max_coordinate;
-        let g_in: CoordinateFn = Arc::new(move |_| (rand::random::<f32>() * max_x, 0.0));
+        let g_in: CoordinateFn = Arc::new(move |_| (random::random::<f32>() * max_x, 0.0));
         let d: Metric = csa::op::geometric::distance_metric(g, g_in);
 
         let input_mask = m & csa::op::geometric::disc(config.model.distance_threshold, d);
@@ -283,7 +277,7 @@ impl EDDModel {
 fn weights(w: f32) -> ValueSet {
     let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
     ValueSet { f: Arc::new(
-        move |_i, _j| w * (1 + bin.sample(&mut rand::thread_rng())) as f32
+        move |_i, _j| w * (1 + random::random_sample(bin)) as f32
     )}
 }
 
@@ -8,9 +8,9 @@ use csa::{NetworkSet, ConnectionSet, ValueSet, NeuronSet, NeuronMask};
 
 use utils::config::{ConfigSection, Configurable, EmptyConfig};
 use utils::environment::Environment;
+use utils::random;
 use utils::parameters::{Parameter, ParameterSet};
 
-use rand::Rng;
 use ndarray::array;
 
 use serde::Deserialize;
@@ -25,8 +25,6 @@ pub struct ER0Model;
 
 impl Generator for ER0Model {
     fn get(ps: &ParameterSet, config: &ModelConfig<Self>, env: &Environment) -> (NetworkSet, ConnectionSet) {
-        let mut rng = rand::thread_rng();
-
         let p: f32 = match &ps.set[0] {
             Parameter::Scalar(x) => {*x},
             _ => { panic!("invalid parameter set") }
@@ -36,17 +34,13 @@ impl Generator for ER0Model {
 
         let d = generator::blk::dynamics::uniform();
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m,
-            v: vec![w],
-            d: vec![d]
-        };
+        let ns = NetworkSet::new(m, vec![w], vec![d]);
 
         let input_mask = csa::mask::random(p);
 
-        let input_w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let input_w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
         let input_cs = ConnectionSet {
             m: input_mask,
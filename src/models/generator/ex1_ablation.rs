@@ -17,7 +17,6 @@ use utils::config::{ConfigSection, Configurable, EmptyConfig};
 use utils::environment::Environment;
 use utils::parameters::{Parameter, ParameterSet};
 
-use rand::Rng;
 use ndarray::{array, Array, Array2};
 
 use serde::Deserialize;
@@ -32,9 +31,7 @@ pub struct GeometricModel;
 
 impl Generator for GeometricModel {
     fn get(p: &ParameterSet, config: &ModelConfig<Self>, env: &Environment) -> (NetworkSet, ConnectionSet) {
-        let mut rng = rand::thread_rng();
-
-        let p = rng.gen_range(0.0..1.0);
+        let p = random::random_range((0.0, 1.0));
         let random_mask = csa::mask::random(p);
 
         // geometric setup
@@ -43,25 +40,20 @@ impl Generator for GeometricModel {
             10.0, // Max coordinate
             config.n + env.outputs);
 
-        let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
-        let disc = csa::op::geometric::disc(3.0, d); // Distance threshold 
+        let disc = csa::op::geometric::coordinate_disc(3.0, coords.clone(), coords.clone()); // Distance threshold 
 
         let m = random_mask & disc;
 
         let d = generator::blk::dynamics::uniform();
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m,
-            v: vec![w],
-            d: vec![d]
-        };
+        let ns = NetworkSet::new(m, vec![w], vec![d]);
 
-        let p = rng.gen_range(0.0..1.0);
+        let p = random::random_range((0.0, 1.0));
         let input_mask = csa::mask::random(p);
 
-        let input_w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let input_w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
         let input_cs = ConnectionSet {
             m: input_mask,
@@ -131,13 +123,9 @@ impl Generator for TypedModel {
         let mask = csa::op::sbm(labels.clone(), ValueSet::from_value(t_cpm.clone()));
 
         // Uniform weights
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
 
         let input_cs = Self::input_cs(m2, labels.clone(), config, env);
 
@@ -177,14 +165,12 @@ impl TypedModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let input_mask = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let input_mask = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
         ConnectionSet {
             m: input_mask,
@@ -248,21 +234,16 @@ impl Generator for GeometricTypedModel {
             10.0, // Max coordinate
             config.n + env.outputs);
 
-        let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
-        let disc = csa::op::geometric::disc(3.0, d); // Distance threshold 
+        let disc = csa::op::geometric::coordinate_disc(3.0, coords.clone(), coords.clone()); // Distance threshold 
 
         let sbm_mask = csa::op::sbm(labels.clone(), ValueSet::from_value(t_cpm.clone()));
         
         let mask = sbm_mask & disc;
 
         // Uniform weights
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
 
         let input_cs = Self::input_cs(m2, labels.clone(), coords.clone(), config, env);
 
@@ -302,19 +283,17 @@ impl GeometricTypedModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
         
-        let g_in: CoordinateFn = Arc::new(move |_| (rand::random::<f32>() * 10.0, 0.0));
+        let g_in: CoordinateFn = Arc::new(move |_| (random::random::<f32>() * 10.0, 0.0));
         let d: Metric = csa::op::geometric::distance_metric(g, g_in);
 
         let input_mask = m & csa::op::geometric::disc(3.0, d);
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
         ConnectionSet {
             m: input_mask,
@@ -12,15 +12,12 @@ use csa::mask::Mask;
 
 use model::neuron::izhikevich::IzhikevichParameters;
 
-use utils::math;
+use utils::{math, random};
 use utils::config::{ConfigSection, Configurable, EmptyConfig};
 use utils::environment::Environment;
 use utils::parameters::{Parameter, ParameterSet};
 
-use rand::Rng;
 use serde::Deserialize;
-use ndarray::Array;
-use ndarray_rand::RandomExt;
 use rand::distributions::Uniform;
 
 use std::sync::Arc;
@@ -45,41 +42,34 @@ impl Generator for TypedUniformModel {
 
         let l = csa::op::label(dist, k);
 
-        let mut rng = rand::thread_rng();
 
         // Generate random matrix of type-type connection proabilities.
-        let t_cpm = Array::random((k,k), Uniform::new(0.0,1.0));
+        let t_cpm = random::random_matrix((k,k), Uniform::new(0.0,1.0));
         let m = csa::op::sbm(l.clone(), ValueSet::from_value(t_cpm));
 
-        //let p = rng.gen_range(0.0..1.0);
+        //let p = random::random_range((0.0, 1.0));
         //let m = csa::mask::random(p);
 
         let td = generator::blk::dynamics::uniform_typed(k);
 
         let d = csa::op::n_group(l.clone(), td);
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m,
-            v: vec![w],
-            d: vec![d]
-        };
+        let ns = NetworkSet::new(m, vec![w], vec![d]);
 
-        let input_t_cpm = Array::random((k, config.model.k_in), Uniform::new(0.0,1.0));
+        let input_t_cpm = random::random_matrix((k, config.model.k_in), Uniform::new(0.0,1.0));
 
         let dist = vec![env.inputs / config.model.k_in;config.model.k_in];
 
         let input_l = csa::op::label(dist, config.model.k_in);
 
-        let input_m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   rand::thread_rng().gen_range(0.0..1.0) < input_t_cpm[[l(i) as usize, input_l(j) as usize]]
-               })
-        };
+        let input_m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_l(j) as usize]]
+            }));
 
-        let input_w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let input_w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
         let input_cs = ConnectionSet {
             m: input_m,
             v: vec![input_w]
@@ -21,9 +21,8 @@ use utils::environment::Environment;
 
 use serde::Deserialize;
 
-use rand::Rng;
 use ndarray::{array, Array, Array1, Array2};
-use ndarray_rand::rand_distr::{Binomial, Distribution};
+use ndarray_rand::rand_distr::Binomial;
 
 use std::sync::Arc;
 
@@ -88,19 +87,13 @@ impl Generator for UDDBaseModel {
             config.model.max_coordinate,
             config.n + env.outputs);
 
-        let d: Metric = csa::op::geometric::distance_metric(coords.clone(), coords.clone());
-
-        let disc = csa::op::geometric::disc(config.model.distance_threshold, d);
+        let disc = csa::op::geometric::coordinate_disc(config.model.distance_threshold, coords.clone(), coords.clone());
 
         let mask = sbm_mask & disc;
 
         let w = Self::minimal_weights(itypes, labels.clone(), config);
 
-        let ns = NetworkSet {
-            m: mask,
-            v: vec![w],
-            d: vec![dynamics]
-        };
+        let ns = NetworkSet::new(mask, vec![w], vec![dynamics]);
 
         let input_cs = Self::input_cs(m2, labels.clone(), coords.clone(), config, env);
 
@@ -137,7 +130,7 @@ impl UDDBaseModel {
 
         let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
         ValueSet { f: Arc::new(
-            move |_i, j| w_map[l(j) as usize] * (1 + bin.sample(&mut rand::thread_rng())) as f32
+            move |_i, j| w_map[l(j) as usize] * (1 + random::random_sample(bin)) as f32
         )}
     }
 
@@ -153,15 +146,15 @@ impl UDDBaseModel {
         let r = IzhikevichParameters::RANGES;
 
                                     // Excitatory
-        let d: Array2<f32> = array![[rand::thread_rng().gen_range(r[0].0..r[0].1),     // a
-                                     rand::thread_rng().gen_range(r[1].0..r[1].1),     // b
-                                     rand::thread_rng().gen_range(r[2].0..r[2].1),     // c
-                                     rand::thread_rng().gen_range(r[3].0..r[3].1),
+        let d: Array2<f32> = array![[random::random_range((r[0].0, r[0].1)),     // a
+                                     random::random_range((r[1].0, r[1].1)),     // b
+                                     random::random_range((r[2].0, r[2].1)),     // c
+                                     random::random_range((r[3].0, r[3].1)),
                                      0.0], // Inhibitory
-                                    [rand::thread_rng().gen_range(r[0].0..r[0].1),     // a
-                                     rand::thread_rng().gen_range(r[1].0..r[1].1),     // b
-                                     rand::thread_rng().gen_range(r[2].0..r[2].1),     // c
-                                     rand::thread_rng().gen_range(r[3].0..r[3].1),
+                                    [random::random_range((r[0].0, r[0].1)),     // a
+                                     random::random_range((r[1].0, r[1].1)),     // b
+                                     random::random_range((r[2].0, r[2].1)),     // c
+                                     random::random_range((r[3].0, r[3].1)),
                                      1.0]];
 
         let n_inhibitory_types = (config.model.k as f32 * INHIBITORY_FRACTION) as usize;
@@ -191,15 +184,13 @@ impl UDDBaseModel {
 
         let input_label = csa::op::label(dist, config.model.k_in);
 
-        let m = Mask {
-            f: Arc::new(
-               move |i, j| {
-                   random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
-               })
-        };
+        let m = Mask::new(Arc::new(
+            move |i, j| {
+                random::random_range((0.0, 1.0)) < input_t_cpm[[l(i) as usize, input_label(j) as usize]]
+            }));
 
         let max_x = config.model.max_coordinate;
-        let g_in: CoordinateFn = Arc::new(move |_| (rand::random::<f32>() * max_x, 0.0));
+        let g_in: CoordinateFn = Arc::new(move |_| (random::random::<f32>() * max_x, 0.0));
         let d: Metric = csa::op::geometric::distance_metric(g, g_in);
 
         let input_mask = m & csa::op::geometric::disc(config.model.distance_threshold, d);
@@ -216,7 +207,7 @@ impl UDDBaseModel {
 fn weights(w: f32) -> ValueSet {
     let bin = Binomial::new(WT_DRAW_COUNT, WT_DRAW_PROB).unwrap();
     ValueSet { f: Arc::new(
-        move |_i, _j| w * (1 + bin.sample(&mut rand::thread_rng())) as f32
+        move |_i, _j| w * (1 + random::random_sample(bin)) as f32
     )}
 }
 
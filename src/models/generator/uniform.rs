@@ -11,9 +11,9 @@ use model::neuron::izhikevich::IzhikevichParameters;
 
 use utils::config::{ConfigSection, Configurable, EmptyConfig};
 use utils::environment::Environment;
+use utils::random;
 use utils::parameters::{Parameter, ParameterSet};
 
-use rand::Rng;
 use ndarray::array;
 
 use serde::Deserialize;
@@ -28,25 +28,19 @@ pub struct UniformModel;
 
 impl Generator for UniformModel {
     fn get(p: &ParameterSet, config: &ModelConfig<Self>, env: &Environment) -> (NetworkSet, ConnectionSet) {
-        let mut rng = rand::thread_rng();
-
-        let p = rng.gen_range(0.0..1.0);
+        let p = random::random_range((0.0, 1.0));
         let m = csa::mask::random(p);
 
         let d = generator::blk::dynamics::uniform();
 
-        let w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
-        let ns = NetworkSet {
-            m,
-            v: vec![w],
-            d: vec![d]
-        };
+        let ns = NetworkSet::new(m, vec![w], vec![d]);
 
-        let p = rng.gen_range(0.0..1.0);
+        let p = random::random_range((0.0, 1.0));
         let input_mask = csa::mask::random(p);
 
-        let input_w = ValueSet { f: Arc::new( move |_i, _j| rand::thread_rng().gen_range(0.0..MAX_W) ) };
+        let input_w = ValueSet { f: Arc::new( move |_i, _j| random::random_range((0.0, MAX_W)) ) };
 
         let input_cs = ConnectionSet {
             m: input_mask,
@@ -1,8 +1,18 @@
 use crate::models::generator::Generator;
 
 use model::Model;
-use model::network::representation::{DefaultRepresentation, NetworkRepresentation, NeuronDescription};
-use model::neuron::izhikevich::IzhikevichParameters;
+use model::network::representation::{NetworkRepresentation, NeuronDescription, Representation};
+use model::neuron::NeuronModel;
+use model::neuron::izhikevich::{Izhikevich, IzhikevichParameters};
+use model::neuron::lapicque::{Lapicque, LapicqueParameters};
+use model::neuron::lif::{Lif, LifParameters};
+use model::neuron::adex::{AdEx, AdExParameters};
+use model::neuron::mixed::{Mixed, MixedParameters};
+use model::plasticity::Plasticity;
+use model::noise::Noise;
+use model::network::weights::WeightRule;
+use model::network::populations::Populations;
+use model::synapse::tsodyks_markram::ShortTermPlasticity;
 
 use utils::parameters::ParameterSet;
 use utils::environment::Environment;
@@ -10,6 +20,8 @@ use utils::config::{Configurable, ConfigSection};
 
 use serde::Deserialize;
 
+use ndarray::{Array1, Array2};
+
 use std::fmt::Debug;
 
 
@@ -22,7 +34,7 @@ pub struct GeneratorModel<G: Generator> {
     env: Environment,
 }
 
-impl<G: Generator> Model for GeneratorModel<G> {
+impl<G: Generator, N: FromDynamics> Model<NeuronDescription<N>> for GeneratorModel<G> {
     fn new(
         conf: &ModelConfig<G>,
         params: &ParameterSet,
@@ -36,7 +48,7 @@ impl<G: Generator> Model for GeneratorModel<G> {
         }
     }
 
-    fn develop(&self) -> DefaultRepresentation {
+    fn develop(&self) -> Representation<N> {
         let n = self.n + self.env.outputs;
 
         let (neural_set, input_cs) = G::get(&self.params, &self.conf, &self.env);
@@ -44,7 +56,8 @@ impl<G: Generator> Model for GeneratorModel<G> {
         let mask = neural_set.m;
 
         // Self connections are always removed
-        let network_cm = (mask - csa::mask::one_to_one()).matrix(n);
+        let connections = (mask - csa::mask::one_to_one()).connections(n, n);
+        let network_cm = csa::mask::connection_matrix(&connections, (n, n));
 
         let dynamics = &neural_set.d[0];
         let d = dynamics.vec(n);
@@ -54,45 +67,200 @@ impl<G: Generator> Model for GeneratorModel<G> {
             let inhibitory = if d[i][4] == 1.0 { true } else { false };
             neurons.push(NeuronDescription::new(
                     i as u32,
-                    IzhikevichParameters {
-                        a: d[i][0],
-                        b: d[i][1],
-                        c: d[i][2],
-                        d: d[i][3],
-                    },
+                    N::from_dynamics(&d[i], &self.conf.mixed),
                     inhibitory,
             ));
         }
 
         assert!(neurons.len() == n);
 
-        for mut n in neurons[(n - self.env.outputs)..].iter_mut() {
-            n.inhibitory = false;
+        if let Some(p) = &self.conf.populations {
+            p.validate(n, &self.env).unwrap_or_else(|e| panic!("{e}"));
+
+            // The generators only define the input of the first n neurons
+            assert!(p.input.iter().all(|i| *i < self.n),
+                "input populations must be among the first {} neurons", self.n);
+        }
+
+        // Readout neurons must be excitatory, unless the populations allow otherwise
+        let readouts: Vec<usize> = match &self.conf.populations {
+            Some(p) if p.inhibitory_readouts => vec![],
+            Some(p) => p.outputs(),
+            None => ((n - self.env.outputs)..n).collect()
+        };
+
+        for i in readouts {
+            neurons[i].inhibitory = false;
         }
 
-        // output neurons must be excitatory
-        assert!(neurons[(n - self.env.outputs)..].iter().all(|x| !x.inhibitory),
-        "developing network with inhibitory output neurons");
+        // Values are only evaluated for the connections of the network
+        let network_w = neural_set.v[0].sparse_matrix(&connections, (n, n));
 
-        let network_w = neural_set.v[0].matrix(n);
+        // Row r of the input matrices targets neuron input[r]
+        let input: Vec<usize> = match &self.conf.populations {
+            Some(p) => p.input.clone(),
+            None => (0..self.n).collect()
+        };
 
-        let input_cm = input_cs.m.r_matrix(self.n, self.env.inputs);
-        let input_w = input_cs.v[0].r_matrix(self.n, self.env.inputs);
+        let input_connections: Vec<(u32, u32)> = input.iter().enumerate()
+            .flat_map(|(r, i)| input_cs.m.row(*i as u32, self.env.inputs).into_iter().map(move |j| (r as u32, j)))
+            .collect();
+
+        let input_cm = csa::mask::connection_matrix(&input_connections, (input.len(), self.env.inputs));
+
+        let mut input_w = Array2::zeros((input.len(), self.env.inputs));
+        for (r, j) in &input_connections {
+            input_w[[*r as usize, *j as usize]] = (input_cs.v[0].f)(input[*r as usize] as u32, *j);
+        }
 
         if input_cm.iter().all(|c| *c == 0) {
             log::trace!("No connections from input");
         }
 
-        NetworkRepresentation::new(neurons.into(),
-            network_cm,
-            network_w,
-            input_cm,
-            input_w,
-            self.env.clone())
+        let representation = match &self.conf.populations {
+            Some(populations) => NetworkRepresentation::new_with_populations(neurons.into(),
+                network_cm,
+                network_w,
+                input_cm,
+                input_w,
+                self.env.clone(),
+                populations.clone()),
+            None => NetworkRepresentation::new(neurons.into(),
+                network_cm,
+                network_w,
+                input_cm,
+                input_w,
+                self.env.clone())
+        };
+
+        let representation = match &neural_set.delays {
+            Some(delay) => representation.with_delays(
+                delay.sparse_matrix(&connections, (n, n)).mapv(|d| d.round() as u32)),
+            None => representation
+        };
+
+        let representation = match &neural_set.stp {
+            Some(stp) => representation.with_short_term_plasticity(
+                ShortTermPlasticity::new(
+                    stp.u.sparse_matrix(&connections, (n, n)),
+                    stp.tau_rec.sparse_matrix(&connections, (n, n)),
+                    stp.tau_facil.sparse_matrix(&connections, (n, n)))),
+            None => representation
+        };
+
+        let representation = self.conf.noise.iter()
+            .fold(representation, |r, noise| r.with_noise(*noise));
+
+        let representation = self.conf.weights.iter()
+            .fold(representation, |r, rule| r.with_weight_rule(*rule));
+
+        match &self.conf.plasticity {
+            Some(c) => {
+                let plasticity = if c.evolve {
+                    // The parameters of the rule are the last of the parameter set, see params()
+                    c.plasticity().evolved(self.params.set.last().unwrap())
+                } else {
+                    c.plasticity()
+                };
+
+                representation.with_plasticity(plasticity)
+            },
+            None => representation
+        }
     }
 
     fn params(config: &ModelConfig<G>, env: &Environment) -> ParameterSet {
-        G::params(config, env)
+        Self::validate_dynamics::<N>(&config.mixed).unwrap_or_else(|e| panic!("{e}"));
+
+        let mut params = G::params(config, env);
+
+        if let Some(c) = &config.plasticity {
+            if c.evolve {
+                params.set.push(c.plasticity().params());
+            }
+        }
+
+        params
+    }
+}
+
+impl<G: Generator> GeneratorModel<G> {
+    /// Ensures that the dynamics evolved by the generator are used by neurons of type N
+    pub fn validate_dynamics<N: FromDynamics>(mixed: &MixedConfig) -> Result<(), String> {
+        if G::EVOLVES_DYNAMICS && !N::uses_dynamics(mixed) {
+            return Err(format!("{} evolves Izhikevich dynamics, which {} neurons do not use; \
+                use the izhikevich neuron model, or mixed with izhikevich neurons of both types",
+                std::any::type_name::<G>(), std::any::type_name::<N>()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Neuron models that can be developed from the dynamics produced by a generator.
+///
+/// Generators produce a vector [a, b, c, d, inhibitory] for each neuron, where a-d
+/// are Izhikevich parameters. Other models pick separate excitatory and inhibitory
+/// parameters by the inhibitory flag, and so cannot be used with generators that evolve
+/// the dynamics. The mixed model picks the model of each neuron by its type, as given
+/// by the config.
+pub trait FromDynamics: NeuronModel {
+    fn from_dynamics(d: &Array1<f32>, config: &MixedConfig) -> Self::Parameters;
+
+    /// Whether the Izhikevich parameters of the dynamics are used by all neurons
+    fn uses_dynamics(_config: &MixedConfig) -> bool {
+        false
+    }
+}
+
+impl FromDynamics for Izhikevich {
+    fn from_dynamics(d: &Array1<f32>, _config: &MixedConfig) -> IzhikevichParameters {
+        IzhikevichParameters {
+            a: d[0],
+            b: d[1],
+            c: d[2],
+            d: d[3],
+        }
+    }
+
+    fn uses_dynamics(_config: &MixedConfig) -> bool {
+        true
+    }
+}
+
+impl FromDynamics for Lapicque {
+    fn from_dynamics(d: &Array1<f32>, _config: &MixedConfig) -> LapicqueParameters {
+        if d[4] == 1.0 { LapicqueParameters::INHIBITORY } else { LapicqueParameters::EXCITATORY }
+    }
+}
+
+impl FromDynamics for Lif {
+    fn from_dynamics(d: &Array1<f32>, _config: &MixedConfig) -> LifParameters {
+        if d[4] == 1.0 { LifParameters::INHIBITORY } else { LifParameters::EXCITATORY }
+    }
+}
+
+impl FromDynamics for AdEx {
+    fn from_dynamics(d: &Array1<f32>, _config: &MixedConfig) -> AdExParameters {
+        if d[4] == 1.0 { AdExParameters::INHIBITORY } else { AdExParameters::EXCITATORY }
+    }
+}
+
+/// Excitatory and inhibitory neurons of the models given by the config
+impl FromDynamics for Mixed {
+    fn from_dynamics(d: &Array1<f32>, config: &MixedConfig) -> MixedParameters {
+        let model = if d[4] == 1.0 { config.inhibitory } else { config.excitatory };
+
+        match model {
+            MixedNeuron::Izhikevich => MixedParameters::Izhikevich(Izhikevich::from_dynamics(d, config)),
+            MixedNeuron::Lapicque   => MixedParameters::Lapicque(Lapicque::from_dynamics(d, config)),
+            MixedNeuron::Lif        => MixedParameters::Lif(Lif::from_dynamics(d, config)),
+            MixedNeuron::AdEx       => MixedParameters::AdEx(AdEx::from_dynamics(d, config)),
+        }
+    }
+
+    fn uses_dynamics(config: &MixedConfig) -> bool {
+        config.excitatory == MixedNeuron::Izhikevich && config.inhibitory == MixedNeuron::Izhikevich
     }
 }
 
@@ -103,7 +271,77 @@ impl<G: Generator> Configurable for GeneratorModel<G> {
 #[derive(Clone, Debug, Deserialize)]
 pub struct ModelConfig<G: Generator> {
     pub n: usize,
-    pub model: G::Config
+    pub model: G::Config,
+
+    #[serde(default)]
+    pub plasticity: Option<PlasticityConfig>,
+
+    /// Background activity of the developed networks, see model::noise
+    #[serde(default)]
+    pub noise: Vec<Noise>,
+
+    /// Constraints on the weights of the developed networks, see model::network::weights
+    #[serde(default)]
+    pub weights: Vec<WeightRule>,
+
+    /// Neurons receiving input and read out, see model::network::populations. If not set,
+    /// the input reaches the first n neurons and the output is read from the last outputs.
+    #[serde(default)]
+    pub populations: Option<Populations>,
+
+    /// Neuron models of the excitatory and inhibitory neurons, when neuron = "mixed"
+    #[serde(default)]
+    pub mixed: MixedConfig,
+}
+
+/// Neuron models of each type of neuron for the mixed model, see model::neuron::mixed
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct MixedConfig {
+    pub excitatory: MixedNeuron,
+    pub inhibitory: MixedNeuron,
+}
+
+/// Izhikevich excitatory neurons and LIF interneurons
+impl Default for MixedConfig {
+    fn default() -> Self {
+        MixedConfig {
+            excitatory: MixedNeuron::Izhikevich,
+            inhibitory: MixedNeuron::Lif,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+pub enum MixedNeuron {
+    #[serde(rename = "izhikevich")]
+    Izhikevich,
+    #[serde(rename = "lapicque")]
+    Lapicque,
+    #[serde(rename = "lif")]
+    Lif,
+    #[serde(rename = "adex")]
+    AdEx,
+}
+
+/// Learning rule of the developed networks, see model::plasticity
+#[derive(Clone, Debug, Deserialize)]
+pub struct PlasticityConfig {
+    pub rule: String,   // pair | triplet | reward_modulated
+
+    /// Whether the parameters of the rule are evolved along with the model
+    #[serde(default = "default_evolve")]
+    pub evolve: bool,
+}
+
+impl PlasticityConfig {
+    pub fn plasticity(&self) -> Plasticity {
+        Plasticity::from_name(&self.rule)
+            .unwrap_or_else(|| panic!("Unknown plasticity rule: {}", self.rule))
+    }
+}
+
+fn default_evolve() -> bool {
+    true
 }
 
 impl<G: Generator> ConfigSection for ModelConfig<G> {
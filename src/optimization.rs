@@ -1,4 +1,4 @@
-use crate::eval::{run_network_on_task, Evaluation, MultiEvaluator};
+use crate::eval::{run_network_on_task, evaluate_with_readout, Evaluation, MultiEvaluator};
 use crate::process::MainConf;
 use crate::analysis::graph::{Graph, GraphAnalysis};
 
@@ -6,7 +6,9 @@ use utils::config::{Configurable, ConfigSection};
 use utils::parameters::ParameterSet;
 
 use model::Model;
-use model::network::representation::DefaultRepresentation;
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::representation::{NeuronDescription, Representation};
 
 use tasks::{Task, TaskEval};
 
@@ -37,12 +39,12 @@ impl Configurable for Optimizer {
 }
 
 impl Optimizer {
-    pub fn optimize<M: Model, T: Task + TaskEval, A: Algorithm>(
-        mut eval: MultiEvaluator<T>,
+    pub fn optimize<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval, A: Algorithm>(
+        mut eval: MultiEvaluator<T, N, S>,
         conf: &MainConf<M, A>,
         env: Environment,
         stop_signal: Arc<AtomicBool>)
-    -> OptimizationStatistics {
+    -> OptimizationStatistics<Representation<N>> {
 
         let mut algo = A::new(conf.algorithm.clone(), M::params(&conf.model, &env));
 
@@ -87,7 +89,7 @@ impl Optimizer {
                }
             }
 
-            log_generation::<T>(gen, &mut stats, &evaluations, &ps, &eval);
+            log_generation::<T, N, S>(gen, &mut stats, &evaluations, &ps, &eval);
 
             algo.step(fitness);
 
@@ -110,7 +112,7 @@ impl ConfigSection for OptimizationConfig {
     }
 }
 
-fn sorted_fitness(evals: &[Evaluation]) -> Vec<(u32, f32)> {
+fn sorted_fitness<N: NeuronModel>(evals: &[Evaluation<N>]) -> Vec<(u32, f32)> {
     let mut sorted_fitness: Vec<(u32, f32)> = evals.iter()
         .map(|g| (g.0, g.1)).collect();
 
@@ -121,12 +123,12 @@ fn sorted_fitness(evals: &[Evaluation]) -> Vec<(u32, f32)> {
     sorted_fitness
 }
 
-fn log_generation<T: Task + TaskEval>(
+fn log_generation<T: Task + TaskEval, N: NeuronModel, S: Synapse>(
     gen: usize,
-    stats: &mut OptimizationStatistics,
-    evals: &[Evaluation],
+    stats: &mut OptimizationStatistics<Representation<N>>,
+    evals: &[Evaluation<N>],
     ps: &[ParameterSet],
-    eval: &MultiEvaluator<T>)
+    eval: &MultiEvaluator<T, N, S>)
 {
     let sorted = sorted_fitness(evals);
 
@@ -136,15 +138,15 @@ fn log_generation<T: Task + TaskEval>(
     let fitness_std: f32 = scores.std(0.0);
     let best_fitness: f32 = sorted[0].1;
 
-    let best_repr: &DefaultRepresentation = evals.iter()
-        .filter_map(|(i,_,r)| if *i == sorted[0].0 {Some(r)} else {None})
-        .collect::<Vec<&DefaultRepresentation>>()[0];
+    let (best_repr, best_seed): &(Representation<N>, u64) = evals.iter()
+        .filter_map(|(i,_,d)| if *i == sorted[0].0 {Some(d)} else {None})
+        .collect::<Vec<&(Representation<N>, u64)>>()[0];
 
     let best_ps: &ParameterSet = ps.iter().enumerate()
         .filter_map(|(i, p)| if i as u32 == sorted[0].0 { Some(p) } else { None } )
         .collect::<Vec<&ParameterSet>>()[0];
 
-    analyze_model::<T>(best_repr);
+    analyze_model::<T, N>(best_repr);
 
     if gen % LOG_FREQ == 0 {
         log::info!("Gen. {} - [Fitness] best: {:.3}, mean: {:.3}, std: {:.3} ]",
@@ -156,10 +158,10 @@ fn log_generation<T: Task + TaskEval>(
         validation(best_repr, eval, stats);
     }
 
-    stats.log_generation(best_fitness, fitness_mean, fitness_std, (best_repr.clone(), best_ps.clone()));
+    stats.log_generation(best_fitness, fitness_mean, fitness_std, (best_repr.clone(), best_ps.clone(), *best_seed));
 }
 
-fn analyze_model<T: Task + TaskEval>(r: &DefaultRepresentation) {
+fn analyze_model<T: Task + TaskEval, N: NeuronModel>(r: &Representation<N>) {
     if log::log_enabled!(log::Level::Trace) {
         log::trace!("Analyzing best network..");
 
@@ -170,15 +172,24 @@ fn analyze_model<T: Task + TaskEval>(r: &DefaultRepresentation) {
     }
 }
 
-fn validation<T: Task + TaskEval>(
-    r: &DefaultRepresentation,
-    eval: &MultiEvaluator<T>,
-    stats: &mut OptimizationStatistics
+fn validation<T: Task + TaskEval, N: NeuronModel, S: Synapse>(
+    r: &Representation<N>,
+    eval: &MultiEvaluator<T, N, S>,
+    stats: &mut OptimizationStatistics<Representation<N>>
     ) {
     let validation_setups = eval.validation_setups();
 
     if validation_setups.len() != 0 {
-        let results = run_network_on_task::<T>(r, validation_setups);
+        if let Some(readout) = eval.readout() {
+            let val = evaluate_with_readout::<T, N, S>(r, validation_setups, eval.validation_clock(), readout);
+
+            log::debug!("validation: {:.3}", val);
+            stats.log_validation(val);
+
+            return;
+        }
+
+        let results = run_network_on_task::<T, N, S>(r, validation_setups, eval.validation_clock());
 
         let accuracy = T::accuracy(&results);
         let val = T::fitness(results);
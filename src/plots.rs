@@ -47,7 +47,7 @@ pub fn plot_all_potentials(record: &Record) {
     }
 }
 
-pub fn plot_stats(stats: &OptimizationStatistics, name: &str) {
+pub fn plot_stats<R>(stats: &OptimizationStatistics<R>, name: &str) {
     let best: Vec<Vec<f32>> = stats.runs.iter().map(|x| x.best_series().clone()).collect();
     let mean: Vec<Vec<f32>> = stats.runs.iter().map(|x| x.mean_series().clone()).collect();
     let std: Vec<Vec<f32>> = stats.runs.iter().map(|x| x.stddev_series().clone()).collect();
@@ -67,7 +67,7 @@ pub fn plot_stats(stats: &OptimizationStatistics, name: &str) {
     }
 }
 
-pub fn plot_run(r: &Run, name: &str) {
+pub fn plot_run<R>(r: &Run<R>, name: &str) {
     let best: Vec<f32> = r.best_series();
     let mean: Vec<f32> = r.mean_series();
     let std:  Vec<f32> = r.stddev_series();
@@ -8,7 +8,7 @@ use crate::eval::config::{Batch, BatchConfig, EvalConfig};
 use crate::config::{get_config, BaseConfig};
 use crate::optimization::{Optimizer, OptimizationConfig};
 
-use crate::models::generator_model::GeneratorModel;
+use crate::models::generator_model::{GeneratorModel, FromDynamics};
 
 use crate::models::generator::uniform::UniformModel;
 use crate::models::generator::typed_uniform::TypedUniformModel;
@@ -25,6 +25,19 @@ use crate::models::generator::ex1_ablation::TypedModel;
 use crate::models::generator::ex1_ablation::GeometricTypedModel;
 
 use model::Model;
+use model::neuron::NeuronModel;
+use model::neuron::izhikevich::Izhikevich;
+use model::neuron::lapicque::Lapicque;
+use model::neuron::lif::Lif;
+use model::neuron::adex::AdEx;
+use model::neuron::mixed::Mixed;
+use model::synapse::Synapse;
+use model::synapse::basic::BasicSynapse;
+use model::synapse::exponential::ExponentialSynapse;
+use model::synapse::bi_exponential::BiExponentialSynapse;
+use model::synapse::conductance::ConductanceSynapse;
+use model::synapse::tsodyks_markram::TsodyksMarkramSynapse;
+use model::network::representation::NeuronDescription;
 
 use tasks::{Task, TaskEval};
 use tasks::catching_task::CatchingTask;
@@ -43,54 +56,108 @@ use evolution::algorithm::Algorithm;
 
 use serde::Serialize;
 
+use utils::config::Configurable;
 use utils::environment::Environment;
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::marker::PhantomData;
 
 
-pub trait Process: Sync {
-    fn run<M: Model, T: Task + TaskEval>(conf: BaseConfig);
+/// A computation that is generic over the synapse model, see resolve_synapse()
+pub trait SynapseFn {
+    type Output;
+
+    fn call<S: Synapse>(self) -> Self::Output;
+}
+
+/// Calls f with the synapse model of the given name, or returns None if it is unknown
+pub fn resolve_synapse<F: SynapseFn>(name: &str, f: F) -> Option<F::Output> {
+    match name {
+        "exponential"       => Some(f.call::<ExponentialSynapse>()),
+        "bi_exponential"    => Some(f.call::<BiExponentialSynapse>()),
+        "basic"             => Some(f.call::<BasicSynapse>()),
+        "conductance"       => Some(f.call::<ConductanceSynapse>()),
+        "tsodyks_markram"   => Some(f.call::<TsodyksMarkramSynapse>()),
+        _ => None
+    }
+}
+
+/// Resolves the model of a process, once its neuron and synapse are resolved
+struct ResolveModel<P, N> {
+    config: BaseConfig,
+    types: PhantomData<(P, N)>,
+}
+
+impl<P: Process, N: FromDynamics> SynapseFn for ResolveModel<P, N> {
+    type Output = ();
+
+    fn call<S: Synapse>(self) {
+        P::resolve_m::<N, S>(self.config);
+    }
+}
+
+pub trait Process: Sync + Sized {
+    fn run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(conf: BaseConfig);
 
     fn init(config: BaseConfig) {
-        Self::resolve_m(config);
+        Self::resolve_n(config);
+    }
+
+    fn resolve_n(config: BaseConfig) {
+        match config.neuron.as_str() {
+            "izhikevich"    => { Self::resolve_s::<Izhikevich>(config); },
+            "lapicque"      => { Self::resolve_s::<Lapicque>(config); },
+            "lif"           => { Self::resolve_s::<Lif>(config); },
+            "adex"          => { Self::resolve_s::<AdEx>(config); },
+            "mixed"         => { Self::resolve_s::<Mixed>(config); },
+            _ => { println!("Unknown neuron model: {}", config.neuron); }
+        }
+    }
+
+    fn resolve_s<N: FromDynamics>(config: BaseConfig) {
+        let synapse = config.synapse.clone();
+
+        if resolve_synapse(&synapse, ResolveModel::<Self, N> { config, types: PhantomData }).is_none() {
+            println!("Unknown synapse: {synapse}");
+        }
     }
 
-    fn resolve_m(config: BaseConfig) {
+    fn resolve_m<N: FromDynamics, S: Synapse>(config: BaseConfig) {
         match config.model.as_str() {
-            "base_model"                => { Self::resolve_t::<GeneratorModel<BaseModel>>(config); },
-            "ed_model"                  => { Self::resolve_t::<GeneratorModel<EvolvedDynamicsModel>>(config); },
-            "uniform_model"             => { Self::resolve_t::<GeneratorModel<UniformModel>>(config); },
-            "er0_model"                 => { Self::resolve_t::<GeneratorModel<ER0Model>>(config); },
-            "typed_uniform_model"       => { Self::resolve_t::<GeneratorModel<TypedUniformModel>>(config); },
-            "udd_base_model"            => { Self::resolve_t::<GeneratorModel<UDDBaseModel>>(config); },
-            "utd_base_model"            => { Self::resolve_t::<GeneratorModel<UTDBaseModel>>(config); },
-            "edd_base_model"            => { Self::resolve_t::<GeneratorModel<EDDModel>>(config); },
-            "geometric_uniform"         => { Self::resolve_t::<GeneratorModel<GeometricModel>>(config); },
-            "typed_model"               => { Self::resolve_t::<GeneratorModel<TypedModel>>(config); },
-            "geometric_typed_model"     => { Self::resolve_t::<GeneratorModel<GeometricTypedModel>>(config); },
+            "base_model"                => { Self::resolve_t::<N, S, GeneratorModel<BaseModel>>(config); },
+            "ed_model"                  => { Self::resolve_t::<N, S, GeneratorModel<EvolvedDynamicsModel>>(config); },
+            "uniform_model"             => { Self::resolve_t::<N, S, GeneratorModel<UniformModel>>(config); },
+            "er0_model"                 => { Self::resolve_t::<N, S, GeneratorModel<ER0Model>>(config); },
+            "typed_uniform_model"       => { Self::resolve_t::<N, S, GeneratorModel<TypedUniformModel>>(config); },
+            "udd_base_model"            => { Self::resolve_t::<N, S, GeneratorModel<UDDBaseModel>>(config); },
+            "utd_base_model"            => { Self::resolve_t::<N, S, GeneratorModel<UTDBaseModel>>(config); },
+            "edd_base_model"            => { Self::resolve_t::<N, S, GeneratorModel<EDDModel>>(config); },
+            "geometric_uniform"         => { Self::resolve_t::<N, S, GeneratorModel<GeometricModel>>(config); },
+            "typed_model"               => { Self::resolve_t::<N, S, GeneratorModel<TypedModel>>(config); },
+            "geometric_typed_model"     => { Self::resolve_t::<N, S, GeneratorModel<GeometricTypedModel>>(config); },
             _ => { println!("Unknown model: {}", config.model); }
         }
     }
 
     /// NOTE: When adding a new task, to enable batching, it must also be added to the
     /// condition in the evaluator() function below. It's stupid, but I don't have time to fix it right now.
-    fn resolve_t<M: Model>(config: BaseConfig) {
+    fn resolve_t<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>>(config: BaseConfig) {
         match config.task.as_str() {
-            "pattern"               => { Self::run::<M, PatternTask>(config); },
-            "pattern_similarity"    => { Self::run::<M, PatternSimilarityTask>(config); },
-            "multipattern"          => { Self::run::<M, MultiPatternTask>(config); },
-            "catching"              => { Self::run::<M, CatchingTask>(config); },
-            "xor"                   => { Self::run::<M, XORTask>(config); },
-            "mnist"                 => { Self::run::<M, MNISTTask>(config); },
-            "testing"               => { Self::run::<M, TestTask>(config); },
-            "single_sin_time_series"       => { Self::run::<M, TimeSeriesTask<SinSeries>>(config); },
-            "multi_sin_time_series"       => { Self::run::<M, TimeSeriesTask<RandomSinSeries>>(config); },
+            "pattern"               => { Self::run::<N, S, M, PatternTask>(config); },
+            "pattern_similarity"    => { Self::run::<N, S, M, PatternSimilarityTask>(config); },
+            "multipattern"          => { Self::run::<N, S, M, MultiPatternTask>(config); },
+            "catching"              => { Self::run::<N, S, M, CatchingTask>(config); },
+            "xor"                   => { Self::run::<N, S, M, XORTask>(config); },
+            "mnist"                 => { Self::run::<N, S, M, MNISTTask>(config); },
+            "testing"               => { Self::run::<N, S, M, TestTask>(config); },
+            "single_sin_time_series"       => { Self::run::<N, S, M, TimeSeriesTask<SinSeries>>(config); },
+            "multi_sin_time_series"       => { Self::run::<N, S, M, TimeSeriesTask<RandomSinSeries>>(config); },
             _ => { println!("Unknown task: {}", config.task); }
         }
     }
 
-    fn main_conf<M: Model, T: Task + TaskEval, A: Algorithm>() -> MainConf<M, A> {
+    fn main_conf<M: Configurable, T: Task + TaskEval, A: Algorithm>() -> MainConf<M, A> {
         MainConf {
             model: get_config::<M>(),
             algorithm: get_config::<A>(),
@@ -108,7 +175,10 @@ pub trait Process: Sync {
         }
     }
 
-    fn evaluator<T: Task + TaskEval>(base_conf: &BaseConfig, eval_conf: &EvalConfig, setups: Vec<T::Setup>) -> MultiEvaluator<T> {
+    fn evaluator<T: Task + TaskEval, N: NeuronModel, S: Synapse>(
+        base_conf: &BaseConfig,
+        eval_conf: &EvalConfig,
+        setups: Vec<T::Setup>) -> MultiEvaluator<T, N, S> {
         let batch_conf = match base_conf.task.as_str() {
             "mnist" | "pattern" | "multipattern" | "pattern_similarity" => {
                 let bc = get_config::<Batch>();
@@ -140,11 +210,12 @@ pub trait Process: Sync {
         log::info!("Use Ctrl-C to stop gracefully");
     }
 
-    fn log_config<M: Model, A: Algorithm>(
+    fn log_config<N, M: Model<N>, A: Algorithm>(
         base_config: &BaseConfig,
         main_config: &MainConf<M, A>,
         env: &Environment) {
         log::info!("Model: {} ({} params)", base_config.model, M::params(&main_config.model, env).size());
+        log::info!("Neuron: {}, synapse: {}", base_config.neuron, base_config.synapse);
         log::info!("Task: {}", base_config.task);
         log::info!("Configs: \n\
                 model = {:#?}\n\
@@ -170,7 +241,7 @@ pub trait Process: Sync {
 
 // TODO: Rename to something else
 #[derive(Debug, Clone)]
-pub struct MainConf<M: Model, A: Algorithm> {
+pub struct MainConf<M: Configurable, A: Algorithm> {
     pub model: M::Config,
     pub algorithm: A::Config,
     pub eval: EvalConfig,
@@ -10,6 +10,9 @@ use crate::optimization::Optimizer;
 use crate::plots;
 use crate::plots::plt;
 use model::Model;
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::representation::{NeuronDescription, Representation};
 
 use tasks::{Task, TaskEval};
 
@@ -28,32 +31,37 @@ use ndarray::Array2;
 
 pub struct DefaultProcess;
 impl Process for DefaultProcess {
-    fn run<M: Model, T: Task + TaskEval>(conf: BaseConfig) {
+    fn run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(conf: BaseConfig) {
         let main_conf = Self::main_conf::<M, T, SeparableNES>();
         let env = Self::environment::<T>();
 
         Self::log_config(&conf, &main_conf, &env);
 
-        Self::single_run::<M, T>(conf, main_conf, env);
+        Self::single_run::<N, S, M, T>(conf, main_conf, env);
     }
 }
 
 impl DefaultProcess {
-    fn single_run<M: Model, T: Task + TaskEval>(conf: BaseConfig, main_conf: MainConf<M, SeparableNES>, env: Environment) {
+    fn single_run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(
+        conf: BaseConfig,
+        main_conf: MainConf<M, SeparableNES>,
+        env: Environment) {
         let stop_signal = Arc::new(AtomicBool::new(false));
         Self::init_ctrl_c_handler(stop_signal.clone());
 
         let setups = T::eval_setups();
 
-        let evaluator: MultiEvaluator<T> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
+        let evaluator: MultiEvaluator<T, N, S> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
 
-        let mut stats = Optimizer::optimize::<M, T, SeparableNES>(evaluator,
+        let mut stats = Optimizer::optimize::<N, S, M, T, SeparableNES>(evaluator,
             &main_conf, env.clone(), stop_signal.clone());
 
-        Self::report::<T>(&mut stats, &conf);
+        Self::report::<T, N, S>(&mut stats, &conf);
     }
 
-    fn report<T: Task + TaskEval>(stats: &mut OptimizationStatistics, base_config: &BaseConfig) {
+    fn report<T: Task + TaskEval, N: NeuronModel, S: Synapse>(
+        stats: &mut OptimizationStatistics<Representation<N>>,
+        base_config: &BaseConfig) {
         plots::plot_stats(stats, "run");
 
         let (f, repr, _) = stats.best();
@@ -62,7 +70,7 @@ impl DefaultProcess {
 
 
         let setup = T::eval_setups()[0].clone();
-        let record = run_analysis::<T>(repr, &[setup])[0].clone();
+        let record = run_analysis::<T, N, S>(repr, &[setup])[0].clone();
 
         plots::generate_plots(&record);
         plots::plot_run_spikes(&record, None);
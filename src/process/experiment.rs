@@ -11,6 +11,9 @@ use crate::eval::MultiEvaluator;
 use crate::optimization::Optimizer;
 use crate::plots;
 use model::Model;
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::representation::{NeuronDescription, Representation};
 
 use tasks::{Task, TaskEval};
 
@@ -28,24 +31,24 @@ use std::env;
 
 pub struct Experiment;
 impl Process for Experiment {
-    fn run<M: Model, T: Task + TaskEval>(conf: BaseConfig) {
+    fn run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(conf: BaseConfig) {
         let main_conf = Self::main_conf::<M, T, SeparableNES>();
 
         let experiment_conf = get_config::<Self>();
 
         log::info!("Starting experiment with {} runs", experiment_conf.n_runs);
-        let experiment_stats = Self::multiple_runs::<M, T>(&conf, main_conf, experiment_conf.clone());
+        let experiment_stats = Self::multiple_runs::<N, S, M, T>(&conf, main_conf, experiment_conf.clone());
 
         Self::experiment_report(experiment_stats, conf, experiment_conf);
     }
 }
 
 impl Experiment {
-    fn multiple_runs<M: Model, T: Task + TaskEval>(
+    fn multiple_runs<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(
         conf: &BaseConfig,
         main_conf: MainConf<M, SeparableNES>,
         experiment_conf: ExperimentConfig)
-    -> Vec<OptimizationStatistics> {
+    -> Vec<OptimizationStatistics<Representation<N>>> {
         let env = Self::environment::<T>();
 
         Self::log_config(&conf, &main_conf, &env);
@@ -54,19 +57,19 @@ impl Experiment {
         Self::init_ctrl_c_handler(stop_signal.clone());
 
 
-        let mut run_stats: Vec<OptimizationStatistics> = Vec::new();
+        let mut run_stats: Vec<OptimizationStatistics<Representation<N>>> = Vec::new();
 
         for n in 0..experiment_conf.n_runs {
             let setups = T::eval_setups();
 
             log::info!("Run {n}");
-            let evaluator: MultiEvaluator<T> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
+            let evaluator: MultiEvaluator<T, N, S> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
 
-            let stats = Optimizer::optimize::<M, T, SeparableNES>(evaluator,
+            let stats = Optimizer::optimize::<N, S, M, T, SeparableNES>(evaluator,
                 &main_conf, env.clone(), stop_signal.clone());
 
             if experiment_conf.save_individual_runs {
-                Self::run_report::<T>(&stats, n);
+                Self::run_report::<T, N>(&stats, n);
             }
 
             run_stats.push(stats);
@@ -79,11 +82,14 @@ impl Experiment {
         run_stats
     }
 
-    fn run_report<T: Task + TaskEval>(stats: &OptimizationStatistics, n: usize) {
-        Self::save::<OptimizationStatistics>(stats.clone(), format!("run_stats_{n}"));
+    fn run_report<T: Task + TaskEval, N: NeuronModel>(stats: &OptimizationStatistics<Representation<N>>, n: usize) {
+        Self::save::<OptimizationStatistics<Representation<N>>>(stats.clone(), format!("run_stats_{n}"));
     }
 
-    fn experiment_report(stats: Vec<OptimizationStatistics>, base_conf: BaseConfig, conf: ExperimentConfig) {
+    fn experiment_report<N: NeuronModel>(
+        stats: Vec<OptimizationStatistics<Representation<N>>>,
+        base_conf: BaseConfig,
+        conf: ExperimentConfig) {
         // Merge the stats
         let mut experiment_stats = OptimizationStatistics::empty();
 
@@ -4,10 +4,12 @@ use serde::{Serialize, Deserialize};
 
 use evolution::stats::OptimizationStatistics;
 
+use model::network::representation::DefaultRepresentation;
+
 
 #[derive(Clone, Deserialize, Serialize)]
-pub struct ExperimentReport {
-    pub stats: OptimizationStatistics,
+pub struct ExperimentReport<R = DefaultRepresentation> {
+    pub stats: OptimizationStatistics<R>,
     pub conf: BaseConfig,
     pub version: String,
     pub desc: Option<String>
@@ -10,7 +10,9 @@ use crate::config::BaseConfig;
 use crate::analysis;
 
 use model::Model;
-use model::network::representation::DefaultRepresentation;
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::representation::{NeuronDescription, Representation};
 
 use evolution::algorithm::snes::SeparableNES;
 use evolution::stats::OptimizationStatistics;
@@ -26,7 +28,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 pub struct HyperOptimization;
 
 impl Process for HyperOptimization {
-    fn run<M: Model, T: Task + TaskEval>(conf: BaseConfig) {
+    fn run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(conf: BaseConfig) {
         let mut main_conf = Self::main_conf::<M, T, SeparableNES>();
         let env = Self::environment::<T>();
 
@@ -55,8 +57,8 @@ impl Process for HyperOptimization {
 
             log::info!("lr_mu: {}, lr_sigma: {}", main_conf.algorithm.lr_mu, main_conf.algorithm.lr_sigma);
 
-            let evaluator: MultiEvaluator<T> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
-            let s = Optimizer::optimize::<M, T, SeparableNES>(evaluator, &main_conf, env.clone(), stop_signal.clone());
+            let evaluator: MultiEvaluator<T, N, S> = Self::evaluator(&conf, &main_conf.eval, setups.clone());
+            let s = Optimizer::optimize::<N, S, M, T, SeparableNES>(evaluator, &main_conf, env.clone(), stop_signal.clone());
             stats.push(s);
 
             if stop_signal.load(Ordering::SeqCst) {
@@ -65,16 +67,18 @@ impl Process for HyperOptimization {
             }
         }
 
-        Self::hyper_report::<T>(&mut stats, params.as_slice());
+        Self::hyper_report::<T, N>(&mut stats, params.as_slice());
     }
 }
 
 impl HyperOptimization {
-    fn hyper_report<T: Task + TaskEval>(stats: &mut [OptimizationStatistics], param_range: &[(f32, f32)]) {
+    fn hyper_report<T: Task + TaskEval, N: NeuronModel>(
+        stats: &mut [OptimizationStatistics<Representation<N>>],
+        param_range: &[(f32, f32)]) {
         log::info!("Experiment report:");
 
         // Best eval for each experiment
-        let best: Vec<(f32, &DefaultRepresentation, _)> = stats.iter().map(|x| x.best()).collect();
+        let best: Vec<(f32, &Representation<N>, _)> = stats.iter().map(|x| x.best()).collect();
 
         let z: Vec<(f32, (f32, f32))> = best.iter().map(|x| x.0).zip(param_range)
                                             .map(|(a,b)| (a,*b)).collect();
@@ -9,7 +9,10 @@ use crate::plots;
 use crate::eval;
 
 use model::Model;
-use model::network::representation::DefaultRepresentation;
+use model::clock::Clock;
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
+use model::network::representation::{DefaultRepresentation, NeuronDescription};
 
 use tasks::{Task, TaskEval, TaskInput};
 use tasks::task_runner::{TaskRunner, Runnable};
@@ -33,7 +36,7 @@ use ndarray::{s, Array, Array1, Array2};
 
 pub struct TestProcess;
 impl Process for TestProcess {
-    fn run<M: Model, T: Task + TaskEval>(conf: BaseConfig) {
+    fn run<N: NeuronModel, S: Synapse, M: Model<NeuronDescription<N>>, T: Task + TaskEval>(conf: BaseConfig) {
         log::info!("Running test process");
 
         //test_time_series_task();
@@ -57,17 +60,17 @@ impl Process for TestProcess {
         //let r = model.develop();
 
         let path = "out/network_ed_model_sin_time_series_919830.json";      // dynamics model
-        let r: DefaultRepresentation = utils::data::load(path).unwrap();
+        let r = DefaultRepresentation::load(path).unwrap_or_else(|e| panic!("{e}"));
         println!("{:#?}", r.env);
 
         let analysis = crate::analysis::analyze_network(&r);
 
         let setups = TimeSeriesTask::<SinSeries>::eval_setups();
 
-        let record = run_analysis::<TimeSeriesTask<SinSeries>>(&r, &setups)[0].clone();
+        let record = run_analysis::<TimeSeriesTask<SinSeries>, _, S>(&r, &setups)[0].clone();
 
         let results: Vec<<TimeSeriesTask<SinSeries> as Task>::Result>
-            = eval::run_network_on_task::<TimeSeriesTask<SinSeries>>(&r, &setups);
+            = eval::run_network_on_task::<TimeSeriesTask<SinSeries>, _, S>(&r, &setups, Clock::default());
 
         let fitness = TimeSeriesTask::<SinSeries>::fitness(results);
         println!("fitness: {fitness}");
@@ -171,7 +174,7 @@ fn test_multipattern_task() {
 }
 
 
-fn network_sample_analysis<M: Model>(model: M) -> (f32, f32) {
+fn network_sample_analysis<N, M: Model<N>>(model: M) -> (f32, f32) {
     let n = 100;
 
     let mut density = Array::zeros(n);
@@ -1,13 +1,15 @@
 //! Wraps a Network to enable running on tasks
 
-use model::network::Network;
+use model::network::{Network, SpikingNetwork};
+use model::neuron::NeuronModel;
+use model::synapse::Synapse;
 use model::spikes::Spikes;
+use model::clock::Clock;
 
 use tasks::TaskOutput;
 use tasks::task_runner::Runnable;
 
-use model::DefaultNetwork;
-use model::network::representation::DefaultRepresentation;
+use model::network::representation::Representation;
 use model::network::builder::NetworkBuilder;
 
 use ndarray::Array1;
@@ -16,14 +18,24 @@ use ndarray::Array1;
 pub struct RunnableNetwork<N: Network> {
     pub network: N,
     pub inputs: usize,
-    pub outputs: usize
+    pub outputs: usize,
+
+    /// Number of network steps per task step
+    pub steps_per_tick: usize,
 }
 
 impl<N: Network> Runnable for RunnableNetwork<N> {
     fn step(&mut self, output: TaskOutput) -> Vec<u32> {
         let network_input = self.get_network_input(output);
 
-        let network_state = self.network.step(network_input); // network_state: len(N)
+        // A task step is 1 ms, and the input is given in the first of the network steps making up that time
+        let mut network_state = self.network.step(network_input); // network_state: len(N)
+
+        for _ in 1..self.steps_per_tick {
+            let s = self.network.step(Spikes::new(self.inputs));
+
+            network_state.data = &network_state.data | &s.data;
+        }
 
         self.get_network_output(network_state)
     }
@@ -31,19 +43,32 @@ impl<N: Network> Runnable for RunnableNetwork<N> {
     fn reset(&mut self) {
         self.network.reset_state();
     }
+
+    fn reward(&mut self, r: f32) {
+        self.network.reward(r);
+    }
 }
 
-impl<N: Network> RunnableNetwork<N> {
-    pub fn build(repr: &DefaultRepresentation) -> RunnableNetwork<DefaultNetwork> {
-        let network = NetworkBuilder::build(repr);
+impl<N: NeuronModel, S: Synapse> RunnableNetwork<SpikingNetwork<N, S>> {
+    pub fn build(repr: &Representation<N>) -> RunnableNetwork<SpikingNetwork<N, S>> {
+        Self::build_with_clock(repr, Clock::default())
+    }
+
+    pub fn build_with_clock(repr: &Representation<N>, clock: Clock) -> RunnableNetwork<SpikingNetwork<N, S>> {
+        let mut network: SpikingNetwork<N, S> = NetworkBuilder::build(repr);
+        network.set_clock(clock);
 
         RunnableNetwork {
             network,
             inputs: repr.env.inputs,
             outputs: repr.env.outputs,
+
+            steps_per_tick: clock.steps_per_ms(),
         }
     }
+}
 
+impl<N: Network> RunnableNetwork<N> {
     fn get_network_input(&self, output: TaskOutput) -> Spikes {
         // Ensure that task output is boolean
         let task_data: Array1<bool> = output.data.mapv(|x| if x != 0.0 { true } else { false });
@@ -31,6 +31,9 @@ pub struct CatchingTask {
     pub sensors: Vec<Sensor>,
     pub setup: CatchingTaskSetup,
     ticks: usize,
+
+    /// Horizontal distance to the apple gained in the last tick, relative to the agent speed
+    reward: f32,
 }
 
 #[derive(Copy, Clone)]
@@ -59,13 +62,18 @@ impl Task for CatchingTask {
             sensors: CatchingTask::init_sensors(),
             agent,
             setup: *setup,
-            apple
+            apple,
+            reward: 0.0,
         }
     }
 
     fn tick(&mut self, input: TaskInput) -> TaskState<CatchingTaskResult> {
+        let dx = (self.agent.x - self.apple.x).abs();
+
         self.parse_input(input);
 
+        self.reward = (dx - (self.agent.x - self.apple.x).abs()) as f32 / AGENT_SPEED as f32;
+
         self.apple.y += APPLE_SPEED;
 
         let mut result: Option<CatchingTaskResult> = None;
@@ -98,6 +106,11 @@ impl Task for CatchingTask {
         self.agent = Agent::new();
         self.apple = Apple::new(self.setup.target_pos);
         self.ticks = 0;
+        self.reward = 0.0;
+    }
+
+    fn reward(&self) -> Option<f32> {
+        Some(self.reward)
     }
 }
 
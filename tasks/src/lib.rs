@@ -53,12 +53,25 @@ pub trait Task {
     fn tick(&mut self, input: TaskInput) -> TaskState<Self::Result>;
     fn reset(&mut self);
     fn environment() -> TaskEnvironment;
+
+    /// Reward signal following the most recent tick, for tasks that provide one
+    fn reward(&self) -> Option<f32> {
+        None
+    }
 }
 
+/// Target values of a readout, given for some of the ticks of a task as (tick, target)
+pub type Targets = Vec<(usize, Array1<f32>)>;
+
 pub trait TaskEval: Task {
     fn eval_setups() -> Vec<Self::Setup>;
 
     fn fitness(results: Vec<Self::Result>) -> f32;
 
     fn accuracy(results: &[Self::Result]) -> Option<f32>;
+
+    /// Targets of a readout trained on the state of the agent, for tasks that provide them
+    fn readout_targets(_setup: &Self::Setup) -> Option<Targets> {
+        None
+    }
 }
@@ -1,7 +1,7 @@
 //! Task where the goal is discriminate between N different patterns.
 //!
 
-use crate::{Task, TaskEval, TaskInput, TaskOutput, TaskState, TaskEnvironment};
+use crate::{Task, TaskEval, TaskInput, TaskOutput, TaskState, TaskEnvironment, Targets};
 
 use utils::math;
 use utils::encoding::rate_encode;
@@ -214,6 +214,14 @@ impl TaskEval for PatternTask {
 
         Some(accuracy)
     }
+
+    /// The one-hot encoded label, in the time between the end of the pattern and the response
+    fn readout_targets(setup: &Self::Setup) -> Option<Targets> {
+        let mut label = Array::zeros(N_CLASSES);
+        label[setup.label] = 1.0;
+
+        Some((SEND_TIME..RESPONSE_START_T).map(|t| (t as usize, label.clone())).collect())
+    }
 }
 
 pub const PATTERNS: [[f32;4]; 3] = [
@@ -221,7 +221,7 @@ pub mod pattern {
     use super::*;
 
     pub fn generate(n:usize) -> PatternSimilarityTaskSetup {
-        if rand::random::<f32>() > 0.5 {
+        if random::random::<f32>() > 0.5 {
             generate_different(n)
         } else {
             generate_equal(n)
@@ -13,6 +13,9 @@ pub enum ExecutionState {
 pub trait Runnable {
     fn step(&mut self, task_output: TaskOutput) -> Vec<u32>;
     fn reset(&mut self);
+
+    /// Receives the reward signal of the task, if it provides one
+    fn reward(&mut self, _r: f32) { }
 }
 
 pub struct TaskRunner<'a, T: Task, R: Runnable> {
@@ -49,6 +52,10 @@ impl<'a, T: Task, R: Runnable> TaskRunner<'a, T, R> {
         let task_state = self.task.tick( TaskInput { data: self.task_inputs.clone() });
         self.task_inputs.clear();
 
+        if let Some(reward) = self.task.reward() {
+            self.runnable.reward(reward);
+        }
+
         if let Some(r) = task_state.result {
             self.state = ExecutionState::FINISHED;
             return Some(r);
@@ -2,6 +2,7 @@ use crate::{Task, TaskEval, TaskInput, TaskOutput, TaskState, TaskEnvironment};
 
 use utils::math::ml;
 use utils::encoding;
+use utils::random;
 
 use ndarray::{Array, Array1, Array2};
 
@@ -93,7 +94,7 @@ impl TaskEval for TestTask {
         //    println!("fr max: {}", math::maxf(fr.as_slice().unwrap()));
         //}
 
-        let f = rand::random();
+        let f = random::random();
 
         f
     }
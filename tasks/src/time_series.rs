@@ -5,14 +5,14 @@
 
 use ts::TimeSeries;
 
-use crate::{Task, TaskEval, TaskInput, TaskOutput, TaskState, TaskEnvironment};
+use crate::{Task, TaskEval, TaskInput, TaskOutput, TaskState, TaskEnvironment, Targets};
 use crate::lorenz_task;
 
 use utils::math;
+use utils::random;
 use utils::encoding::rate_encode;
 
 use ndarray::{s, Array, Array1, Array2, Axis};
-use ndarray_rand::rand::Rng;
 
 
 const N_SETUPS: usize = 100;
@@ -207,6 +207,13 @@ impl<S: TimeSeries> TaskEval for TimeSeriesTask<S> {
     fn accuracy(results: &[Self::Result]) -> Option<f32> {
         None
     }
+
+    /// The value of the series at each step
+    fn readout_targets(setup: &Self::Setup) -> Option<Targets> {
+        Some((0..T as usize)
+            .map(|t| (t, setup.series.slice(s![t, ..N_VARIABLES]).to_owned()))
+            .collect())
+    }
 }
 
 pub mod ts {
@@ -255,7 +262,7 @@ pub mod ts {
             let mut s: Array2<f64> = Array::zeros((T as usize, 1));
 
             let a = 2.0;
-            let b = rand::thread_rng().gen_range(0.03..0.1);
+            let b = random::random_range((0.03, 0.1));
             let c = 4.0;
 
             for (i, mut x) in s.iter_mut().enumerate() {
@@ -3,7 +3,7 @@
 //! The agent is to give output if it receives spikes on either
 //! of its inputs, but not both.
 
-use crate::{Task, TaskInput, TaskOutput, TaskState, TaskEnvironment, TaskEval};
+use crate::{Task, TaskInput, TaskOutput, TaskState, TaskEnvironment, TaskEval, Targets};
 
 use ndarray::{array, Array};
 
@@ -14,6 +14,9 @@ const AGENT_OUTPUTS: usize = 1;
 const INPUT_T: u32 = 50;
 const READOUT_T: u32 = 100;
 
+/// Ticks at the end of the input over which the state is read out
+const STATE_READOUT_T: u32 = 20;
+
 #[derive(Copy, Clone)]
 pub struct XORTaskSetup {
     inputs: (u32, u32),
@@ -121,4 +124,10 @@ impl TaskEval for XORTask {
     fn accuracy(_results: &[Self::Result]) -> Option<f32> {
         None
     }
+
+    fn readout_targets(setup: &XORTaskSetup) -> Option<Targets> {
+        Some(((INPUT_T - STATE_READOUT_T)..=INPUT_T)
+            .map(|t| (t as usize, array![setup.output as f32]))
+            .collect())
+    }
 }
@@ -1,16 +1,51 @@
 //! Tests building SpikingNetwork from NetworkRepresentation, and running the result
 
+use model::Model;
+use model::network::{Network, SpikingNetwork};
 use model::network::builder::NetworkBuilder;
 use model::neuron::NeuronModel;
-use model::neuron::izhikevich::{Izhikevich, IzhikevichParameters};
-use model::network::representation::{NetworkRepresentation, NeuronDescription};
+use model::neuron::izhikevich::Izhikevich;
+use model::neuron::lapicque::{Lapicque, LapicqueParameters};
+use model::neuron::lif::Lif;
+use model::neuron::adex::{AdEx, AdExParameters};
+use model::neuron::mixed::{Mixed, MixedParameters};
+use model::neuron::lif::LifParameters;
+use model::synapse::Synapse;
+use model::synapse::basic::BasicSynapse;
+use model::synapse::exponential::ExponentialSynapse;
+use model::synapse::bi_exponential::BiExponentialSynapse;
+use model::synapse::conductance::ConductanceSynapse;
+use model::synapse::tsodyks_markram::{TsodyksMarkramSynapse, TsodyksMarkramParameters, ShortTermPlasticity};
+use model::network::representation::{DefaultRepresentation, NetworkRepresentation, NeuronDescription, Representation};
+use model::network::populations::{Populations, Population};
+use model::network::error::{RepresentationError, LoadError};
+use model::network::weights::WeightRule;
+use model::spikes::Spikes;
+use model::clock::{Clock, Integrator};
+use model::plasticity::Plasticity;
+use model::noise::Noise;
+use model::record::{RecordType, RecordConfig, RecordSink, RecordData};
+use model::readout::{Readout, ReadoutConfig};
+
+use srsnn::eval::evaluate_with_readout;
+use srsnn::config::{base_config, get_config};
+use srsnn::models::generator_model::{GeneratorModel, FromDynamics, MixedConfig, MixedNeuron};
+use srsnn::models::generator::base::{BaseModel, TypeStpConfig};
+use srsnn::models::generator::ed::EvolvedDynamicsModel;
+use srsnn::models::generator::edd_base::EDDModel;
+
+use tasks::TaskEval;
+use tasks::xor_task::XORTask;
 
 use utils::random;
 use utils::environment::Environment;
 
-use ndarray::{Array, Array2};
+use ndarray::{array, Array, Array1, Array2};
 use ndarray_rand::rand_distr::Uniform;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 fn test_representation<N: NeuronModel>(n: usize, env: Environment)
 -> NetworkRepresentation<NeuronDescription<N>> {
     let mut nvec: Vec<NeuronDescription<N>> = Vec::new();
@@ -30,20 +65,39 @@ fn test_representation<N: NeuronModel>(n: usize, env: Environment)
 
     let weights: Array2<f32> = connection_mask.map(|x| *x as f32);
 
-    let mut input_cm: Array2<u32> = Array::zeros((n, env.inputs));
-    let mut output_cm: Array2<u32> = Array::zeros((env.outputs, n));
+    let mut input_cm: Array2<u32> = Array::zeros((n - env.outputs, env.inputs));
 
     for i in 0..env.inputs {
         input_cm[[i,i]] = 1;
     }
 
-    for i in 0..env.outputs {
-        output_cm[[i,i]] = 1;
-    }
+    let input_w: Array2<f32> = Array::ones((n - env.outputs, env.inputs));
 
-    let input_w: Array2<f32> = Array::ones((n, env.inputs));
+    NetworkRepresentation::new(neurons, connection_mask, weights, input_cm, input_w, env)
+}
 
-    NetworkRepresentation::new(neurons, connection_mask, weights, input_cm, input_w, output_cm, env)
+fn build_and_run<N: NeuronModel, S: Synapse>() {
+    build_and_run_with_clock::<N, S>(Clock::default());
+}
+
+fn build_and_run_with_clock<N: NeuronModel, S: Synapse>(clock: Clock) {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<N>(100, env.clone());
+
+    let mut network: SpikingNetwork<N, S> = NetworkBuilder::build(&desc);
+    network.set_clock(clock);
+
+    for _ in 0..100 {
+        let output = network.step(Spikes::new(env.inputs));
+
+        assert!(output.len() == env.outputs);
+    }
+
+    network.reset_state();
 }
 
 
@@ -56,5 +110,678 @@ fn can_build_network_from_representation() {
 
     let desc = test_representation::<Izhikevich>(100, env);
 
-    let _network = NetworkBuilder::build(&desc);
+    let _network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+}
+
+#[test]
+fn can_run_network_with_any_neuron_and_synapse() {
+    build_and_run::<Izhikevich, ExponentialSynapse>();
+    build_and_run::<Izhikevich, BiExponentialSynapse>();
+    build_and_run::<Izhikevich, BasicSynapse>();
+    build_and_run::<Izhikevich, ConductanceSynapse>();
+    build_and_run::<Izhikevich, TsodyksMarkramSynapse>();
+
+    build_and_run::<Lapicque, ExponentialSynapse>();
+    build_and_run::<Lif, BiExponentialSynapse>();
+    build_and_run::<AdEx, BasicSynapse>();
+    build_and_run::<AdEx, ConductanceSynapse>();
+}
+
+#[test]
+fn can_run_network_with_any_integrator() {
+    for integrator in [Integrator::Euler, Integrator::ExponentialEuler, Integrator::RK4] {
+        build_and_run_with_clock::<Izhikevich, BiExponentialSynapse>(Clock::new(0.1, integrator));
+        build_and_run_with_clock::<Lif, ExponentialSynapse>(Clock::new(0.1, integrator));
+    }
+}
+
+#[test]
+fn can_run_network_with_delays() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone());
+    let delays: Array2<u32> = random::random_matrix((100, 100), Uniform::new(0, 10));
+    let desc = desc.with_delays(delays);
+
+    for clock in [Clock::default(), Clock::new(0.1, Integrator::RK4)] {
+        let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+        network.set_clock(clock);
+
+        for _ in 0..100 {
+            let output = network.step(Spikes::new(env.inputs));
+
+            assert!(output.len() == env.outputs);
+        }
+    }
+}
+
+#[test]
+fn can_run_network_with_plasticity() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    for rule in ["pair", "triplet", "reward_modulated"] {
+        let desc = test_representation::<Izhikevich>(100, env.clone())
+            .with_plasticity(Plasticity::from_name(rule).unwrap());
+
+        let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+        assert!(network.plasticity.is_some());
+
+        for _ in 0..100 {
+            let mut input = Spikes::new(env.inputs);
+            input.data.fill(true);
+
+            network.step(input);
+            network.reward(1.0);
+        }
+
+        assert!(network.synapse.weights_mut().unwrap().matrix() != desc.network_w,
+            "{rule}: weights are unchanged");
+    }
+}
+
+#[test]
+fn can_run_network_with_short_term_plasticity() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    for p in [TsodyksMarkramParameters::DEPRESSING, TsodyksMarkramParameters::FACILITATING] {
+        let desc = test_representation::<Izhikevich>(100, env.clone())
+            .with_short_term_plasticity(ShortTermPlasticity::uniform((100, 100), p));
+
+        let mut network: SpikingNetwork<Izhikevich, TsodyksMarkramSynapse> = NetworkBuilder::build(&desc);
+
+        for _ in 0..100 {
+            let output = network.step(Spikes::new(env.inputs));
+
+            assert!(output.len() == env.outputs);
+        }
+    }
+}
+
+fn trials_from_snapshot_are_identical<N: NeuronModel, S: Synapse>() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let delays: Array2<u32> = random::random_matrix((100, 100), Uniform::new(0, 5));
+    let desc = test_representation::<N>(100, env.clone()).with_delays(delays);
+
+    let mut network: SpikingNetwork<N, S> = NetworkBuilder::build(&desc);
+
+    let mut input = Spikes::new(env.inputs);
+    input.data.fill(true);
+
+    // Warm up on constant input, then branch
+    for _ in 0..50 {
+        network.step(input.clone());
+    }
+
+    let snapshot = network.snapshot();
+
+    // Recorded steps depend on the time of the network, which is part of the snapshot
+    network.record_config.every = 3;
+    network.enable_recording();
+
+    let mut trial = |network: &mut SpikingNetwork<N, S>| -> Vec<Array1<f32>> {
+        (0..50).map(|i| {
+            network.step(if i % 2 == 0 { input.clone() } else { Spikes::new(env.inputs) });
+            network.neurons.potentials()
+        }).collect()
+    };
+
+    let first = trial(&mut network);
+    let first_record = network.record.as_array(RecordType::Potentials);
+
+    network.reset_state();
+    network.restore(&snapshot);
+
+    assert_eq!(trial(&mut network), first);
+    assert_eq!(network.record.as_array(RecordType::Potentials), first_record);
+}
+
+#[test]
+fn can_restore_network_from_snapshot() {
+    trials_from_snapshot_are_identical::<Izhikevich, ExponentialSynapse>();
+    trials_from_snapshot_are_identical::<Lif, BiExponentialSynapse>();
+    trials_from_snapshot_are_identical::<AdEx, ConductanceSynapse>();
+    trials_from_snapshot_are_identical::<Lapicque, TsodyksMarkramSynapse>();
+    trials_from_snapshot_are_identical::<Izhikevich, BasicSynapse>();
+}
+
+#[test]
+fn can_run_network_with_noise() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone())
+        .with_noise(Noise::Gaussian { mean: 0.0, std: 5.0 })
+        .with_noise(Noise::Poisson { rate: 20.0, weight: 10.0 })
+        .with_noise(Noise::OrnsteinUhlenbeck { mean: 2.0, std: 2.0, tau: 5.0 });
+
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+
+    let mut run = |network: &mut SpikingNetwork<Izhikevich, ExponentialSynapse>| -> Vec<Array1<f32>> {
+        (0..100).map(|_| {
+            network.step(Spikes::new(env.inputs));
+            network.neurons.potentials()
+        }).collect()
+    };
+
+    network.reset_state();
+    let first = run(&mut network);
+
+    // Without input, activity is driven by the noise alone
+    assert!(first.iter().any(|v| v.iter().any(|v| *v >= 30.0)), "no spikes from noise");
+
+    network.reset_state();
+    assert_eq!(run(&mut network), first, "noise is not repeated after reset");
+}
+
+#[test]
+fn can_record_selected_neurons_and_steps() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone());
+
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+    network.enable_recording();
+    network.record_config = RecordConfig {
+        types: vec![RecordType::Potentials, RecordType::OutputSpikes],
+        neurons: Some(vec![0, 5, 99]),
+        every: 4,
+    };
+
+    for _ in 0..100 {
+        network.step(Spikes::new(env.inputs));
+    }
+
+    let potentials = network.record.as_array(RecordType::Potentials);
+
+    assert_eq!(potentials.shape(), [25, 3]);
+    assert_eq!(network.record.as_array(RecordType::OutputSpikes).shape(), [25, env.outputs]);
+    assert!(network.record.get(RecordType::Spikes).is_empty());
+}
+
+/// Keeps the step and type of each record
+struct TestSink(Rc<RefCell<Vec<(usize, RecordType)>>>);
+
+impl RecordSink for TestSink {
+    fn write(&mut self, t: usize, record_type: RecordType, _data: &RecordData) -> std::io::Result<()> {
+        self.0.borrow_mut().push((t, record_type));
+
+        Ok(())
+    }
+}
+
+#[test]
+fn can_stream_records_to_sink() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone());
+    let records = Rc::new(RefCell::new(Vec::new()));
+
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+    network.record_to(Box::new(TestSink(records.clone())));
+    network.record_config.types = vec![RecordType::Spikes];
+    network.record_config.every = 10;
+
+    for _ in 0..100 {
+        network.step(Spikes::new(env.inputs));
+    }
+
+    let expected: Vec<(usize, RecordType)> = (0..100).step_by(10).map(|t| (t, RecordType::Spikes)).collect();
+
+    assert_eq!(*records.borrow(), expected);
+    assert!(network.record.get(RecordType::Spikes).is_empty(), "records are kept in memory");
+}
+
+#[test]
+fn can_export_record_and_network_to_npz() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone());
+
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+    network.enable_recording();
+
+    for _ in 0..10 {
+        network.step(Spikes::new(env.inputs));
+    }
+
+    let dir = std::env::temp_dir();
+    let record_path = dir.join("srsnn_test_record.npz");
+    let network_path = dir.join("srsnn_test_network.npz");
+
+    network.record.save_npz(record_path.to_str().unwrap()).unwrap();
+    desc.save_npz(network_path.to_str().unwrap()).unwrap();
+
+    for path in [record_path, network_path] {
+        let data = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Zip archives start with a local file header
+        assert_eq!(&data[..4], b"PK\x03\x04");
+    }
+}
+
+#[test]
+fn can_run_network_with_populations() {
+    let env = Environment {
+        inputs: 2,
+        outputs: 4,
+    };
+
+    let n = 20;
+    let mut desc = test_representation::<Izhikevich>(n, Environment { inputs: 2, outputs: 0 });
+    desc.env = env.clone();
+
+    // Input reaches neurons 10 and 11, and each drives one of the readouts
+    let populations = Populations::new(vec![10, 11], vec![
+        Population::new("left", vec![0, 1]),
+        Population::new("right", vec![2, 3]),
+    ]).allow_inhibitory_readouts();
+
+    let mut network_w: Array2<f32> = Array::zeros((n, n));
+    for (from, to) in [(10, 0), (10, 1), (11, 2), (11, 3)] {
+        network_w[[to, from]] = 50.0;
+    }
+
+    let mut neurons = desc.neurons.clone();
+    neurons[0].inhibitory = true;
+
+    let desc = NetworkRepresentation::new_with_populations(neurons, network_w.mapv(|w| (w > 0.0) as u32), network_w,
+        Array::from_diag(&Array::from_elem(2, 1)), Array::from_diag(&Array::from_elem(2, 50.0)), env.clone(), populations);
+
+    let mut network: SpikingNetwork<Izhikevich, BasicSynapse> = NetworkBuilder::build(&desc);
+
+    let mut input = Spikes::new(env.inputs);
+    input.data[0] = true;
+
+    let mut left = 0;
+    for _ in 0..100 {
+        let output = network.step(input.clone());
+        assert!(output.len() == env.outputs);
+
+        assert_eq!(output.data.to_vec()[..2], network.readout("left").unwrap().data.to_vec());
+        assert!(network.readout("right").unwrap().firing().is_empty(), "right readout fired without input");
+
+        left += output.firing().len();
+    }
+
+    assert!(left > 0, "no output from stimulated readout");
+    assert!(network.readout("hidden").is_none());
+}
+
+#[test]
+fn can_fit_readout_to_network_state() {
+    let env = Environment {
+        inputs: 2,
+        outputs: 1,
+    };
+
+    let desc = test_representation::<Izhikevich>(50, env);
+
+    // The target is whether the first input is on, which the state of the network separates
+    let setups = &XORTask::eval_setups()[..2];
+
+    let eval = |model: Readout| -> f32 {
+        let config = ReadoutConfig { model, window: 20, test_fraction: 0.0 };
+
+        evaluate_with_readout::<XORTask, Izhikevich, ExponentialSynapse>(&desc, setups, Clock::default(), &config)
+    };
+
+    let ridge = eval(Readout::Ridge { alpha: 1.0 });
+    let logistic = eval(Readout::Logistic { alpha: 0.0, iterations: 500, learning_rate: 0.5 });
+
+    // Ridge is scored by R², where chance is 0, and logistic by accuracy, where chance is 50
+    assert!(ridge > 50.0, "ridge: {ridge}");
+    assert!(logistic > 90.0, "logistic: {logistic}");
+
+    // Untrained, the logistic readout predicts 0.5 for every sample, which is the positive class
+    assert_eq!(eval(Readout::Logistic { alpha: 0.0, iterations: 0, learning_rate: 0.5 }), 50.0);
+}
+
+#[test]
+fn can_report_invalid_representation() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone());
+
+    let mut input_w = desc.input_w.clone();
+    input_w[[0, 0]] = -1.0;
+
+    let errors = NetworkRepresentation::try_new(desc.neurons.clone(), desc.network_cm.clone(),
+        Array::zeros((100, 99)), desc.input_cm.clone(), input_w, env.clone()).err().unwrap().errors;
+
+    assert_eq!(errors, vec![
+        RepresentationError::Shape { field: "network_w", expected: vec![100, 100], found: vec![100, 99] },
+        RepresentationError::NegativeWeights { field: "input_w", count: 1 },
+    ]);
+
+    // Output neurons must be excitatory
+    let mut desc = desc;
+    desc.neurons[99].inhibitory = true;
+
+    assert!(NetworkBuilder::try_build::<Izhikevich, ExponentialSynapse>(&desc).is_err());
+    assert_eq!(desc.validate().err().unwrap().errors, vec![RepresentationError::InhibitoryOutput { neuron: 99 }]);
+
+    // Loading names the file and the field
+    let path = std::env::temp_dir().join("srsnn_test_invalid_network.json");
+    let path = path.to_str().unwrap();
+
+    desc.network_cm = Array::zeros((5, 5));
+    utils::data::save(&desc, path).unwrap();
+
+    let error = Representation::<Izhikevich>::load(path).err().unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert!(matches!(error, LoadError::Invalid {..}));
+
+    let message = error.to_string();
+    assert!(message.starts_with(path) && message.contains("network_cm"), "{message}");
+
+    // As does failing to parse it
+    std::fs::write(path, "{}").unwrap();
+
+    let error = Representation::<Izhikevich>::load(path).err().unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert!(matches!(error, LoadError::Read {..}));
+    assert!(error.to_string().contains("missing field"), "{error}");
+}
+
+#[test]
+fn can_run_network_with_mixed_neuron_models() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    // Every fourth neuron is an inhibitory LIF neuron, except for the outputs
+    let mut desc = test_representation::<Mixed>(100, env.clone());
+
+    for n in desc.neurons.iter_mut().take(90).step_by(4) {
+        n.params = MixedParameters::Lif(LifParameters::INHIBITORY);
+        n.inhibitory = true;
+    }
+
+    let mut network: SpikingNetwork<Mixed, ExponentialSynapse> = NetworkBuilder::build(&desc);
+
+    let mut input = Spikes::new(env.inputs);
+    input.data.fill(true);
+
+    for _ in 0..50 {
+        let output = network.step(input.clone());
+
+        assert!(output.len() == env.outputs);
+    }
+
+    // Neurons are grouped by model, keeping their index in the network
+    let [izhikevich, lif, ..] = network.neurons.groups();
+
+    assert_eq!(lif, (0..90).step_by(4).collect::<Vec<_>>());
+    assert_eq!(izhikevich.len() + lif.len(), 100);
+
+    trials_from_snapshot_are_identical::<Mixed, BiExponentialSynapse>();
+}
+
+#[test]
+fn can_build_network_with_weight_rules() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let desc = test_representation::<Izhikevich>(100, env.clone())
+        .with_weight_rule(WeightRule::Clip { max: 0.5 })
+        .with_weight_rule(WeightRule::Normalize { total: 4.0 });
+
+    for (row, cm) in desc.network_w.rows().into_iter().zip(desc.network_cm.rows()) {
+        if cm.sum() > 0 {
+            assert!((row.sum() - 4.0).abs() < 1e-4, "incoming weights sum to {}", row.sum());
+        }
+    }
+
+    // The network is built with the weights of the representation
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+    assert_eq!(network.synapse.weights_mut().unwrap().matrix(), desc.network_w);
+
+    for _ in 0..100 {
+        network.step(Spikes::new(env.inputs));
+    }
+}
+
+#[test]
+fn can_reproduce_network_from_seed() {
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let run = |seed: u64| -> (Array2<u32>, Vec<Array1<f32>>) {
+        random::set_seed(seed);
+
+        let desc = test_representation::<Izhikevich>(100, env.clone())
+            .with_noise(Noise::Poisson { rate: 20.0, weight: 10.0 });
+
+        let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&desc);
+
+        let potentials = (0..100).map(|_| {
+            network.step(Spikes::new(env.inputs));
+            network.neurons.potentials()
+        }).collect();
+
+        (desc.network_cm, potentials)
+    };
+
+    // Both the structure and the noise are drawn from the seeded stream
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1).0, run(2).0);
+}
+
+#[test]
+fn can_develop_network_from_seed() {
+    type M = GeneratorModel<BaseModel>;
+
+    base_config(None);
+
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let conf = get_config::<M>();
+    let params = <M as Model>::params(&conf, &env);
+    let params = params.assign(&random::random_vector(params.size(), Uniform::new(-1.0, 1.0)));
+
+    let model = <M as Model>::new(&conf, &params, &env);
+
+    let develop = |seed: u64| -> DefaultRepresentation { model.develop_with_seed(seed) };
+
+    random::set_seed(1);
+    let a = develop(7);
+    let after_a: f32 = random::random();
+
+    random::set_seed(2);
+    let b = develop(7);
+
+    assert_eq!(a.network_cm, b.network_cm);
+    assert_eq!(a.network_w, b.network_w);
+    assert_ne!(a.network_cm, develop(8).network_cm);
+
+    // Developing does not change the stream of the caller
+    random::set_seed(1);
+    assert_eq!(after_a, random::random::<f32>());
+}
+
+#[test]
+fn inhibitory_dynamics_give_inhibitory_parameters() {
+    let excitatory = array![0.02, 0.2, -65.0, 8.0, 0.0];
+    let inhibitory = array![0.1, 0.2, -65.0, 2.0, 1.0];
+
+    let config = MixedConfig::default();
+
+    assert_eq!(AdEx::from_dynamics(&excitatory, &config).tau_w, AdExParameters::EXCITATORY.tau_w);
+    assert_eq!(AdEx::from_dynamics(&inhibitory, &config).tau_w, AdExParameters::INHIBITORY.tau_w);
+
+    assert_eq!(Lapicque::from_dynamics(&excitatory, &config).c, LapicqueParameters::EXCITATORY.c);
+    assert_eq!(Lapicque::from_dynamics(&inhibitory, &config).c, LapicqueParameters::INHIBITORY.c);
+}
+
+#[test]
+fn evolved_dynamics_require_izhikevich_neurons() {
+    let izhikevich = MixedConfig { excitatory: MixedNeuron::Izhikevich, inhibitory: MixedNeuron::Izhikevich };
+
+    assert!(GeneratorModel::<EvolvedDynamicsModel>::validate_dynamics::<Izhikevich>(&MixedConfig::default()).is_ok());
+    assert!(GeneratorModel::<EvolvedDynamicsModel>::validate_dynamics::<Mixed>(&izhikevich).is_ok());
+
+    assert!(GeneratorModel::<EvolvedDynamicsModel>::validate_dynamics::<Lif>(&MixedConfig::default()).is_err());
+    assert!(GeneratorModel::<EDDModel>::validate_dynamics::<AdEx>(&MixedConfig::default()).is_err());
+    assert!(GeneratorModel::<EvolvedDynamicsModel>::validate_dynamics::<Mixed>(&MixedConfig::default()).is_err());
+
+    // Fixed dynamics are mapped to the parameters of each model
+    assert!(GeneratorModel::<BaseModel>::validate_dynamics::<Lif>(&MixedConfig::default()).is_ok());
+}
+
+#[test]
+fn mixed_dynamics_follow_config() {
+    let excitatory = array![0.02, 0.2, -65.0, 8.0, 0.0];
+    let inhibitory = array![0.1, 0.2, -65.0, 2.0, 1.0];
+
+    let config = MixedConfig::default();
+
+    assert!(matches!(Mixed::from_dynamics(&excitatory, &config), MixedParameters::Izhikevich(_)));
+    assert!(matches!(Mixed::from_dynamics(&inhibitory, &config), MixedParameters::Lif(_)));
+
+    let config = MixedConfig { excitatory: MixedNeuron::AdEx, inhibitory: MixedNeuron::Izhikevich };
+
+    assert!(matches!(Mixed::from_dynamics(&excitatory, &config),
+        MixedParameters::AdEx(p) if p.tau_w == AdExParameters::EXCITATORY.tau_w));
+    assert!(matches!(Mixed::from_dynamics(&inhibitory, &config),
+        MixedParameters::Izhikevich(p) if p.a == 0.1));
+}
+
+#[test]
+fn generator_develops_short_term_plasticity_without_delays() {
+    type M = GeneratorModel<BaseModel>;
+
+    base_config(None);
+
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let mut conf = get_config::<M>();
+    conf.model.delay_scale = None;
+    conf.model.short_term_plasticity = Some(TypeStpConfig {
+        by_type: Some(vec![vec![TsodyksMarkramParameters::FACILITATING; conf.model.k + conf.model.k_out];
+            conf.model.k + conf.model.k_out]),
+        ee: TsodyksMarkramParameters::DEPRESSING,
+        ei: TsodyksMarkramParameters::DEPRESSING,
+        ie: TsodyksMarkramParameters::DEPRESSING,
+        ii: TsodyksMarkramParameters::DEPRESSING,
+    });
+
+    let params = <M as Model>::params(&conf, &env);
+    let params = params.assign(&random::random_vector(params.size(), Uniform::new(-1.0, 1.0)));
+
+    let repr: DefaultRepresentation = <M as Model>::new(&conf, &params, &env).develop();
+
+    assert!(repr.network_delay.is_none());
+
+    let stp = repr.network_stp.expect("no short-term plasticity");
+    for (c, u) in repr.network_cm.iter().zip(stp.u.iter()) {
+        assert_eq!(*u, if *c == 1 { TsodyksMarkramParameters::FACILITATING.u } else { 0.0 });
+    }
+}
+
+#[test]
+fn base_model_balances_inhibition() {
+    type M = GeneratorModel<BaseModel>;
+
+    base_config(None);
+
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let mut conf = get_config::<M>();
+    conf.weights = vec![WeightRule::Balance { ratio: 0.3 }];
+
+    let params = <M as Model>::params(&conf, &env);
+    let params = params.assign(&random::random_vector(params.size(), Uniform::new(-1.0, 1.0)));
+
+    let repr: DefaultRepresentation = <M as Model>::new(&conf, &params, &env).develop();
+    assert_eq!(repr.weight_rules, conf.weights);
+
+    for row in repr.network_w.rows() {
+        let (mut exc, mut inh) = (0.0, 0.0);
+
+        for (w, n) in row.iter().zip(&repr.neurons) {
+            if n.inhibitory { inh += w } else { exc += w }
+        }
+
+        if exc > 0.0 && inh > 0.0 {
+            assert!((inh - 0.3 * exc).abs() < 1e-3 * exc, "inhibition {inh}, excitation {exc}");
+        }
+    }
+}
+
+#[test]
+fn generator_develops_network_with_populations() {
+    type M = GeneratorModel<BaseModel>;
+
+    base_config(None);
+
+    let env = Environment {
+        inputs: 10,
+        outputs: 10,
+    };
+
+    let mut conf = get_config::<M>();
+    let n = conf.n + env.outputs;
+
+    let populations = Populations::new((0..5).collect(), vec![
+        Population::new("left", (n - 5..n).collect()),
+        Population::new("right", (5..10).collect()),
+    ]);
+    conf.populations = Some(populations.clone());
+
+    let params = <M as Model>::params(&conf, &env);
+    let params = params.assign(&random::random_vector(params.size(), Uniform::new(-1.0, 1.0)));
+
+    let repr: DefaultRepresentation = <M as Model>::new(&conf, &params, &env).develop();
+
+    assert_eq!(repr.populations, Some(populations.clone()));
+    assert_eq!(repr.input_cm.shape(), [5, env.inputs]);
+    assert!(populations.outputs().iter().all(|i| !repr.neurons[*i].inhibitory));
+
+    let mut network: SpikingNetwork<Izhikevich, ExponentialSynapse> = NetworkBuilder::build(&repr);
+
+    assert_eq!(network.step(Spikes::new(env.inputs)).len(), env.outputs);
 }
@@ -7,3 +7,5 @@ pub mod data;
 pub mod environment;
 pub mod parameters;
 pub mod analysis;
+pub mod npy;
+pub mod regression;
@@ -2,8 +2,9 @@ use std::collections::HashMap;
 
 use num_traits::Float;
 
+use crate::random;
+
 use ndarray::{Array, Array1};
-use rand::Rng;
 
 
 pub const P_TOLERANCE: f32 = 1.0001;
@@ -67,10 +68,8 @@ fn multinomial(n: usize, p: &[f64]) -> Array1<u32> {
 
     let mut d: Array1<u32> = Array::zeros(n);
 
-    let mut rng = rand::thread_rng();
-
     for i in 0..n {
-        let r: f64 = rng.gen();
+        let r: f64 = random::random();
 
         let mut sum = 0.0;
         for (l, prob) in p.iter().enumerate() {
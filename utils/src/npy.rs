@@ -0,0 +1,286 @@
+//! Writes arrays in the NumPy .npy format, and collections of arrays as .npz archives.
+//!
+//! See https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+
+use ndarray::{ArrayBase, Data, Dimension};
+
+use std::fs::File;
+use std::io::{self, Write, BufWriter};
+
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Headers are padded so that the data is aligned to this many bytes
+const ALIGNMENT: usize = 64;
+
+/// Types that can be stored in .npy files
+pub trait NpyElement {
+    /// Type description, as given by numpy.dtype.str
+    const DESCR: &'static str;
+
+    fn write_le<W: Write>(&self, w: &mut W) -> io::Result<()>;
+}
+
+impl NpyElement for f32 {
+    const DESCR: &'static str = "<f4";
+
+    fn write_le<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.to_le_bytes())
+    }
+}
+
+impl NpyElement for u32 {
+    const DESCR: &'static str = "<u4";
+
+    fn write_le<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.to_le_bytes())
+    }
+}
+
+impl NpyElement for bool {
+    const DESCR: &'static str = "|b1";
+
+    fn write_le<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&[*self as u8])
+    }
+}
+
+/// Writes an array in the .npy format, version 1.0
+pub fn write_npy<A, S, D, W>(w: &mut W, a: &ArrayBase<S, D>) -> io::Result<()>
+    where A: NpyElement, S: Data<Elem = A>, D: Dimension, W: Write
+{
+    let shape = match a.shape() {
+        [n] => format!("({n},)"),
+        s => format!("({})", s.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(", ")),
+    };
+
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {shape}, }}", A::DESCR);
+
+    // magic, version and header length make up 10 bytes, and the header ends with a newline
+    let len = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((ALIGNMENT - len % ALIGNMENT) % ALIGNMENT));
+    header.push('\n');
+
+    w.write_all(MAGIC)?;
+    w.write_all(&[1, 0])?;
+    w.write_all(&(header.len() as u16).to_le_bytes())?;
+    w.write_all(header.as_bytes())?;
+
+    // Elements are iterated in logical (C) order, regardless of memory layout
+    for x in a.iter() {
+        x.write_le(w)?;
+    }
+
+    Ok(())
+}
+
+pub fn save_npy<A, S, D>(a: &ArrayBase<S, D>, path: &str) -> io::Result<()>
+    where A: NpyElement, S: Data<Elem = A>, D: Dimension
+{
+    let mut w = BufWriter::new(File::create(path)?);
+
+    write_npy(&mut w, a)?;
+    w.flush()
+}
+
+/// Writes named arrays to an uncompressed .npz archive, which is a zip file of .npy files
+/// that can be read with numpy.load.
+pub struct NpzWriter<W: Write> {
+    writer: W,
+
+    /// Central directory entries of the files written so far
+    directory: Vec<u8>,
+    n_files: u16,
+    offset: u32,
+}
+
+impl NpzWriter<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(NpzWriter::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> NpzWriter<W> {
+    pub fn new(writer: W) -> NpzWriter<W> {
+        NpzWriter {
+            writer,
+
+            directory: Vec::new(),
+            n_files: 0,
+            offset: 0,
+        }
+    }
+
+    /// Adds an array, which is loaded as npz[name]
+    pub fn add_array<A, S, D>(&mut self, name: &str, a: &ArrayBase<S, D>) -> io::Result<()>
+        where A: NpyElement, S: Data<Elem = A>, D: Dimension
+    {
+        let mut data = Vec::new();
+        write_npy(&mut data, a)?;
+
+        let name = format!("{name}.npy");
+        let crc = crc32(&data);
+
+        let size = zip_size(data.len(), &name)?;
+        let n_files = self.n_files.checked_add(1)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "too many files for a zip archive"))?;
+
+        // Local file header, stored without compression
+        let mut header = Vec::new();
+        header.extend(0x04034b50u32.to_le_bytes());
+        header.extend(self.file_fields(crc, size, &name));
+        header.extend(name.as_bytes());
+
+        let offset = zip_size(self.offset as usize + header.len() + data.len(), "archive")?;
+
+        // Central directory entry
+        self.directory.extend(0x02014b50u32.to_le_bytes());
+        self.directory.extend(20u16.to_le_bytes()); // version made by
+        self.directory.extend(self.file_fields(crc, size, &name));
+        self.directory.extend([0u8; 6]); // comment length, disk, internal attributes
+        self.directory.extend(0u32.to_le_bytes()); // external attributes
+        self.directory.extend(self.offset.to_le_bytes());
+        self.directory.extend(name.as_bytes());
+
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&data)?;
+
+        self.n_files = n_files;
+        self.offset = offset;
+
+        Ok(())
+    }
+
+    /// Writes the central directory, completing the archive
+    pub fn finish(mut self) -> io::Result<W> {
+        let directory_size = zip_size(self.directory.len(), "central directory")?;
+        zip_size(self.offset as usize + self.directory.len(), "archive")?;
+
+        self.writer.write_all(&self.directory)?;
+
+        self.writer.write_all(&0x06054b50u32.to_le_bytes())?;
+        self.writer.write_all(&[0u8; 4])?; // disk numbers
+        self.writer.write_all(&self.n_files.to_le_bytes())?;
+        self.writer.write_all(&self.n_files.to_le_bytes())?;
+        self.writer.write_all(&directory_size.to_le_bytes())?;
+        self.writer.write_all(&self.offset.to_le_bytes())?;
+        self.writer.write_all(&0u16.to_le_bytes())?; // comment length
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    /// Fields shared by the local header and the central directory
+    fn file_fields(&self, crc: u32, size: u32, name: &str) -> Vec<u8> {
+        let mut f = Vec::new();
+
+        f.extend(20u16.to_le_bytes()); // version needed to extract
+        f.extend(0u16.to_le_bytes());  // flags
+        f.extend(0u16.to_le_bytes());  // compression: stored
+        f.extend(0u16.to_le_bytes());  // modification time
+        f.extend(33u16.to_le_bytes()); // modification date: 1980-01-01
+        f.extend(crc.to_le_bytes());
+        f.extend(size.to_le_bytes());  // compressed size
+        f.extend(size.to_le_bytes());  // uncompressed size
+        f.extend((name.len() as u16).to_le_bytes());
+        f.extend(0u16.to_le_bytes());  // extra field length
+
+        f
+    }
+}
+
+/// Size or offset as stored in a zip archive, which is limited to 32 bits without zip64
+fn zip_size(x: usize, what: &str) -> io::Result<u32> {
+    u32::try_from(x).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput,
+        format!("{what} is {x} bytes, which exceeds the 4 GiB limit of a zip archive")))
+}
+
+/// CRC-32 checksum, as used by zip
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for b in data {
+        crc ^= *b as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    #[test]
+    fn test_npy_header() {
+        let mut buf = Vec::new();
+        write_npy(&mut buf, &array![[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).unwrap();
+
+        let header_len = u16::from_le_bytes([buf[8], buf[9]]) as usize;
+        let header = std::str::from_utf8(&buf[10..10 + header_len]).unwrap();
+
+        assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert!(header.ends_with('\n'));
+        assert_eq!((10 + header_len) % ALIGNMENT, 0);
+
+        assert_eq!(buf.len(), 10 + header_len + 6 * 4);
+        assert_eq!(&buf[10 + header_len..14 + header_len], &1.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_npy_c_order() {
+        // Transposed arrays are written in logical order
+        let a = array![[1u32, 2], [3, 4]];
+
+        let mut buf = Vec::new();
+        write_npy(&mut buf, &a.t()).unwrap();
+
+        let data: Vec<u32> = buf[buf.len() - 16..].chunks(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect();
+
+        assert_eq!(data, vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn test_zip_size_overflow() {
+        assert_eq!(zip_size(u32::MAX as usize, "a.npy").unwrap(), u32::MAX);
+
+        let e = zip_size(u32::MAX as usize + 1, "a.npy").unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_npz_archive() {
+        let mut npz = NpzWriter::new(Vec::new());
+        npz.add_array("a", &array![1.0f32, 2.0]).unwrap();
+        npz.add_array("b", &array![true, false]).unwrap();
+
+        let buf = npz.finish().unwrap();
+
+        // The end of central directory record gives the number of files, and the
+        // offset of the directory, which starts with the entry of the first file
+        let end = &buf[buf.len() - 22..];
+        assert_eq!(&end[..4], &0x06054b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+
+        let directory_size = u32::from_le_bytes(end[12..16].try_into().unwrap()) as usize;
+        let directory = u32::from_le_bytes(end[16..20].try_into().unwrap()) as usize;
+
+        assert_eq!(directory + directory_size, buf.len() - 22);
+        assert_eq!(&buf[directory..directory + 4], &0x02014b50u32.to_le_bytes());
+
+        // Each entry is 46 bytes followed by the name
+        assert_eq!(&buf[directory + 46 + 5..directory + 50 + 5], &0x02014b50u32.to_le_bytes());
+    }
+}
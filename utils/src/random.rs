@@ -8,31 +8,66 @@ use ndarray::{Array1, Array2, Array};
 use ndarray_rand::RandomExt;
 use ndarray_rand::rand::{Rng, SeedableRng};
 use ndarray_rand::rand::rngs::StdRng;
-use ndarray_rand::rand_distr::{StandardNormal, Distribution};
+use ndarray_rand::rand_distr::{Standard, StandardNormal, Distribution};
 use ndarray_rand::rand_distr::uniform::SampleUniform;
 
 use rand::seq::SliceRandom;
 
 
+/// Seed of threads that have not been seeded otherwise
 pub const SEED: u64 = 1337;
 
-// TODO: Replace with ThreadRng?
+// All random draws go through the stream of the current thread, so that a run can be
+// reproduced from its seed. Threads that draw random numbers, such as the workers of
+// MultiEvaluator, are seeded with a seed derived from the stream of the thread that spawns them.
 thread_local! {
     static RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(SEED));
 }
 
 pub fn set_seed(seed: u64) {
-    log::debug!("Seed: {seed}");
     RNG.replace(StdRng::seed_from_u64(seed));
 }
 
-pub fn random_seed() {
+/// Seeds the stream of the current thread from entropy, and returns the seed
+pub fn random_seed() -> u64 {
     let seed: u64 = rand::random();
-    log::debug!("Seed: {seed}");
+    set_seed(seed);
 
-    RNG.replace(StdRng::seed_from_u64(seed));
+    seed
+}
+
+/// Draws a seed for another stream, such as that of a worker thread
+pub fn derive_seed() -> u64 {
+    random_sample(Standard)
+}
+
+/// Calls f with the stream of the current thread seeded by seed, and restores the stream afterwards,
+/// such that the draws of f do not change the draws that follow
+pub fn with_seed<T, F: FnOnce() -> T>(seed: u64, f: F) -> T {
+    let _guard = StreamGuard {
+        outer: Some(RNG.replace(StdRng::seed_from_u64(seed)))
+    };
+
+    f()
 }
 
+/// Restores the outer stream when dropped, also when unwinding from a panic
+struct StreamGuard {
+    outer: Option<StdRng>,
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        if let Some(outer) = self.outer.take() {
+            RNG.replace(outer);
+        }
+    }
+}
+
+/// Calls f with the random stream of the current thread
+pub fn with_rng<T, F: FnOnce(&mut StdRng) -> T>(f: F) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
 
 /// Applies bounded and weighted gaussian noise.
 pub fn gaussian(x: f32, w: f32, bounds: (f32, f32)) -> f32 {
@@ -46,27 +81,102 @@ pub fn gaussian(x: f32, w: f32, bounds: (f32, f32)) -> f32 {
 }
 
 pub fn random_matrix<T, D: Distribution<T>>(shape: (usize, usize), dist: D) -> Array2<T>{
-    Array::random(shape, dist)
+    with_rng(|rng| Array::random_using(shape, dist, rng))
 }
 
 pub fn random_vector<D: Distribution<f32>>(size: usize, dist: D) -> Array1<f32> {
-    Array::random(size, dist)
+    with_rng(|rng| Array::random_using(size, dist, rng))
 }
 
 /// Get a random sample from a specified range
 pub fn random_range<T: SampleUniform + PartialOrd>(range: (T, T)) -> T {
-    rand::thread_rng().gen_range(range.0..range.1)
+    with_rng(|rng| rng.gen_range(range.0..range.1))
 }
 
+/// A random value of the standard distribution of T, as rand::random
+pub fn random<T>() -> T where Standard: Distribution<T> {
+    random_sample(Standard)
+}
 
 /// Sample from a distribution
 pub fn random_sample<T, D: Distribution<T>> (dist: D) -> T {
-    RNG.with(|rng| rng.borrow_mut().sample(dist))
+    with_rng(|rng| rng.sample(dist))
 }
 
 /// Choose a random element from a Vec
 pub fn random_choice<T> (v: &Vec<T>) -> &T {
     assert!(v.len() != 0);
 
-    RNG.with(|rng| v.choose(&mut (*rng.borrow_mut()))).unwrap()
+    with_rng(|rng| v.choose(rng)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray_rand::rand_distr::Uniform;
+
+    fn draws() -> (f32, Array2<f32>, u64) {
+        (random_range((0.0, 1.0)), random_matrix((2, 2), Uniform::new(0.0, 1.0)), derive_seed())
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_draws() {
+        set_seed(42);
+        let a = draws();
+
+        set_seed(42);
+        let b = draws();
+
+        set_seed(43);
+        let c = draws();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_derived_streams_are_reproducible() {
+        set_seed(42);
+        let seed = derive_seed();
+
+        let worker = move || {
+            set_seed(seed);
+            draws()
+        };
+
+        let a = std::thread::spawn(worker).join().unwrap();
+        let b = std::thread::spawn(worker).join().unwrap();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_with_seed_restores_stream() {
+        set_seed(42);
+        let a = draws();
+
+        set_seed(42);
+        let inner = with_seed(7, draws);
+        let b = draws();
+
+        assert_eq!(a, b);
+        assert_eq!(inner, with_seed(7, draws));
+    }
+
+    #[test]
+    fn test_with_seed_restores_stream_on_panic() {
+        set_seed(42);
+        let a = draws();
+
+        set_seed(42);
+        let result = std::panic::catch_unwind(|| with_seed(7, || {
+            draws();
+            panic!("develop failed")
+        }));
+        let b = draws();
+
+        assert!(result.is_err());
+        assert_eq!(a, b);
+    }
 }
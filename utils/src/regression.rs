@@ -0,0 +1,153 @@
+//! Linear models fit to samples given as the rows of a matrix
+
+use crate::math::ml;
+
+use ndarray::{Array, Array1, Array2, Axis};
+
+
+/// Maps samples x (m x features) to outputs x·w + b (m x outputs)
+#[derive(Clone, Debug)]
+pub struct LinearModel {
+    pub w: Array2<f32>,
+    pub b: Array1<f32>,
+}
+
+impl LinearModel {
+    pub fn predict(&self, x: &Array2<f32>) -> Array2<f32> {
+        x.dot(&self.w) + &self.b
+    }
+}
+
+/// Least squares fit of y with penalty alpha on the squared weights. The bias is not penalized.
+pub fn ridge(x: &Array2<f32>, y: &Array2<f32>, alpha: f32) -> LinearModel {
+    assert!(x.nrows() == y.nrows(), "# samples ({}) != # targets ({})", x.nrows(), y.nrows());
+    assert!(alpha > 0.0, "alpha must be positive, got {alpha}");
+
+    let x = x.mapv(|v| v as f64);
+    let y = y.mapv(|v| v as f64);
+
+    // Fitting centered data separates out the bias
+    let x_mean = x.mean_axis(Axis(0)).unwrap();
+    let y_mean = y.mean_axis(Axis(0)).unwrap();
+
+    let xc = &x - &x_mean;
+    let yc = &y - &y_mean;
+
+    let a = xc.t().dot(&xc) + Array2::<f64>::eye(x.ncols()) * alpha as f64;
+    let w = cholesky_solve(a, xc.t().dot(&yc));
+
+    let b = &y_mean - &x_mean.dot(&w);
+
+    LinearModel {
+        w: w.mapv(|v| v as f32),
+        b: b.mapv(|v| v as f32),
+    }
+}
+
+/// Fits each column of y, with values in [0, 1], by logistic regression with penalty alpha on
+/// the squared weights, using gradient descent. Probabilities are given by the sigmoid of the prediction.
+pub fn logistic(x: &Array2<f32>, y: &Array2<f32>, alpha: f32, iterations: usize, learning_rate: f32) -> LinearModel {
+    assert!(x.nrows() == y.nrows(), "# samples ({}) != # targets ({})", x.nrows(), y.nrows());
+
+    let m = x.nrows() as f32;
+
+    let mut model = LinearModel {
+        w: Array::zeros((x.ncols(), y.ncols())),
+        b: Array::zeros(y.ncols()),
+    };
+
+    for _ in 0..iterations {
+        let error = model.predict(x).mapv(ml::sigmoid) - y;
+
+        let dw = x.t().dot(&error) / m + &model.w * alpha;
+        let db = error.mean_axis(Axis(0)).unwrap();
+
+        model.w -= &(dw * learning_rate);
+        model.b -= &(db * learning_rate);
+    }
+
+    model
+}
+
+/// Solves a·x = b for symmetric positive definite a
+fn cholesky_solve(a: Array2<f64>, b: Array2<f64>) -> Array2<f64> {
+    let n = a.nrows();
+
+    // a = l·lᵀ
+    let mut l: Array2<f64> = Array::zeros((n, n));
+
+    for i in 0..n {
+        for j in 0..=i {
+            let s = a[[i, j]] - (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum::<f64>();
+
+            l[[i, j]] = if i == j {
+                assert!(s > 0.0, "matrix is not positive definite");
+                s.sqrt()
+            } else {
+                s / l[[j, j]]
+            };
+        }
+    }
+
+    // Forward substitution of l·z = b, then back substitution of lᵀ·x = z
+    let mut x = b;
+
+    for i in 0..n {
+        for k in 0..i {
+            let v = x.row(k).to_owned() * l[[i, k]];
+            x.row_mut(i).scaled_add(-1.0, &v);
+        }
+
+        x.row_mut(i).mapv_inplace(|v| v / l[[i, i]]);
+    }
+
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            let v = x.row(k).to_owned() * l[[k, i]];
+            x.row_mut(i).scaled_add(-1.0, &v);
+        }
+
+        x.row_mut(i).mapv_inplace(|v| v / l[[i, i]]);
+    }
+
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ndarray::array;
+
+    #[test]
+    fn test_cholesky_solve() {
+        let a = array![[4.0, 2.0, 0.6], [2.0, 5.0, 1.0], [0.6, 1.0, 3.0]];
+        let b = array![[1.0], [2.0], [3.0]];
+
+        let x = cholesky_solve(a.clone(), b.clone());
+
+        assert!((a.dot(&x) - b).iter().all(|e| e.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_ridge_recovers_linear_function() {
+        let x = array![[0.0, 1.0], [1.0, 0.0], [1.0, 1.0], [2.0, 3.0], [0.5, -1.0]];
+        let y = x.map_axis(Axis(1), |r| 2.0 * r[0] - r[1] + 0.5).insert_axis(Axis(1));
+
+        let model = ridge(&x, &y, 1e-6);
+
+        assert!((model.predict(&x) - &y).iter().all(|e| e.abs() < 1e-3));
+        assert!((model.b[0] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_logistic_separates_classes() {
+        let x = array![[0.0, 0.1], [0.2, 0.0], [0.1, 0.2], [0.9, 1.0], [1.0, 0.8], [0.8, 0.9]];
+        let y = array![[0.0], [0.0], [0.0], [1.0], [1.0], [1.0]];
+
+        let model = logistic(&x, &y, 0.0, 2000, 1.0);
+        let p = model.predict(&x).mapv(ml::sigmoid);
+
+        assert!(p.iter().zip(&y).all(|(p, y)| (p - y).abs() < 0.5));
+    }
+}